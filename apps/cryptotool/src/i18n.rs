@@ -0,0 +1,10 @@
+pub use appkit::i18n::Catalog;
+pub(crate) use appkit::tr;
+
+/// 英文译文，以界面中的中文原文为键
+pub static CATALOG: Catalog = Catalog::new(&[
+    ("加解密工具", "Crypto Tool"),
+    ("这里将提供常见算法（如 AES、RSA、哈希 等）的演示与转换。", "Demos and conversions for common algorithms (AES, RSA, hashes, ...) will live here."),
+    ("🎨 外观", "🎨 Appearance"),
+    ("主题、界面缩放、字号、字体和配色", "Theme, zoom, font size, fonts and colors"),
+]);
@@ -1,47 +1,64 @@
 #![windows_subsystem = "windows"]
 
-use eframe::egui;
-
-struct CryptoToolApp;
+mod i18n;
 
-fn install_cjk_fonts(ctx: &egui::Context) {
-    use egui::{FontData, FontDefinitions, FontFamily};
+use appkit::fonts::install_fonts;
+use appkit::theme::ThemeSettings;
+use eframe::egui;
 
-    let mut fonts = FontDefinitions::default();
+use crate::i18n::tr;
 
-    let candidates = [
-        r"C:\\Windows\\Fonts\\msyh.ttc",
-        r"C:\\Windows\\Fonts\\msyh.ttf",
-        r"C:\\Windows\\Fonts\\simhei.ttf",
-        r"C:\\Windows\\Fonts\\simsun.ttc",
-    ];
+/// eframe 持久化存储中外观设置的键
+const THEME_KEY: &str = "cryptotool_theme";
 
-    let mut loaded_any = false;
-    for path in candidates {
-        if let Ok(bytes) = std::fs::read(path) {
-            fonts.font_data.insert("cjk".to_owned(), egui::FontData::from_owned(bytes).into());
-            loaded_any = true;
-            break;
-        }
-    }
+struct CryptoToolApp {
+    theme: ThemeSettings,
+    theme_window: bool,
+}
 
-    if loaded_any {
-        if let Some(list) = fonts.families.get_mut(&egui::FontFamily::Proportional) {
-            list.insert(0, "cjk".to_owned());
-        }
-        if let Some(list) = fonts.families.get_mut(&egui::FontFamily::Monospace) {
-            list.insert(0, "cjk".to_owned());
+impl CryptoToolApp {
+    /// 创建应用并恢复上次保存的外观设置
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut theme: ThemeSettings = cc
+            .storage
+            .and_then(|s| eframe::get_value(s, THEME_KEY))
+            .unwrap_or_default();
+        install_fonts(&cc.egui_ctx, &mut theme.font_path);
+        theme.apply(&cc.egui_ctx);
+        cc.egui_ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("加解密工具").to_owned()));
+        Self {
+            theme,
+            theme_window: false,
         }
     }
-
-    ctx.set_fonts(fonts);
 }
 
 impl eframe::App for CryptoToolApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, THEME_KEY, &self.theme);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            if ui.button(tr!("🎨 外观")).on_hover_text(tr!("主题、界面缩放、字号、字体和配色")).clicked() {
+                self.theme_window = true;
+            }
+        });
+        let mut changed = false;
+        egui::Window::new(tr!("🎨 外观"))
+            .open(&mut self.theme_window)
+            .resizable(false)
+            .show(ctx, |ui| {
+                changed = self.theme.show(ui);
+            });
+        if changed {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("加解密工具").to_owned()));
+        }
+        self.theme.sync_zoom(ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("加解密工具");
-            ui.label("这里将提供常见算法（如 AES、RSA、哈希 等）的演示与转换。");
+            ui.heading(tr!("加解密工具"));
+            ui.label(tr!("这里将提供常见算法（如 AES、RSA、哈希 等）的演示与转换。"));
         });
     }
 }
@@ -51,10 +68,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "加解密工具",
         options,
-        Box::new(|cc| {
-            install_cjk_fonts(&cc.egui_ctx);
-            Ok(Box::new(CryptoToolApp))
-        }),
+        Box::new(|cc| Ok(Box::new(CryptoToolApp::new(cc)))),
     )
 }
 
@@ -1,44 +1,94 @@
+use appkit::fonts::install_fonts;
+use appkit::theme::Palette;
 use eframe::egui;
+use eframe::egui::text::{CCursor, CCursorRange};
 use egui_json_tree::{
-    DefaultExpand, JsonTree, JsonTreeMaxWidth, JsonTreeStyle, 
-    JsonTreeWrapping, JsonTreeWrappingConfig,
+    render::{DefaultRender, RenderContext}, DefaultExpand, JsonTree, JsonTreeMaxWidth, JsonTreeStyle, 
+    JsonTreeVisuals, JsonTreeWrapping, JsonTreeWrappingConfig,
 };
-use serde::Serialize;
+use std::collections::HashSet;
 
-use crate::context_menu::show_context_menu;
-use crate::edit::{apply_edits, EditDialog, JsonEdit};
-use crate::ui::{install_cjk_fonts, try_fill_from_clipboard};
+use crate::bulk_panel::BulkPanel;
+use crate::clipboard::{self, ClipboardWatcher};
+use crate::command_palette::{CommandPalette, KeymapEditor};
+use crate::commands::Command;
+use crate::context_menu::{expand, is_expanded, reveal_ancestor, set_expanded, show_context_menu, TreeActions};
+use crate::document::{Document, TreeExpand};
+use crate::edit::{EditDialog, JsonEdit};
+use crate::export_panel::ExportPanel;
+use crate::editor::{edits_text, Decorations, SyntaxColors};
+use crate::i18n::tr;
+use crate::merge_panel::{MergePanel, MergeSource};
+use crate::redact_panel::RedactPanel;
+use crate::script_panel::ScriptPanel;
+use crate::settings::Settings;
+use crate::span::{byte_to_char, char_to_byte};
+use crate::stats_panel::StatsPanel;
+use crate::tree_ops::CutNode;
+use crate::ui::try_fill_from_clipboard;
+use crate::watch;
+
+/// 树视图使用与编辑器相同的主题配色
+pub fn tree_visuals(palette: &Palette) -> JsonTreeVisuals {
+    JsonTreeVisuals {
+        object_key_color: palette.key,
+        array_idx_color: palette.number,
+        null_color: palette.literal,
+        bool_color: palette.literal,
+        number_color: palette.number,
+        string_color: palette.string,
+        highlight_color: palette.tree_match,
+        punctuation_color: palette.punct,
+    }
+}
 
 pub struct JsonFmtApp {
-    input: String,
-    error: Option<String>,
+    tabs: Vec<Document>,
+    active: usize,
+    next_doc_id: u64,
     indent_spaces: usize,
     tried_clipboard_once: bool,
-    last_json: Option<serde_json::Value>,
     fonts_loaded: bool,
-    search_input: String,
-    pending_edits: Vec<JsonEdit>,
-    edit_dialog: Option<EditDialog>,
+    clipboard_watcher: Option<ClipboardWatcher>,
+    clipboard_offer: Option<serde_json::Value>,
+    cut_buffer: Option<CutNode>,
+    settings: Settings,
+    redact_panel: Option<RedactPanel>,
+    merge_panel: Option<MergePanel>,
+    script_panel: Option<ScriptPanel>,
+    theme_window: bool,
+    command_palette: Option<CommandPalette>,
+    keymap_editor: Option<KeymapEditor>,
 }
 
 impl Default for JsonFmtApp {
     fn default() -> Self {
         let mut app = Self {
-            input: String::new(),
-            error: None,
+            tabs: Vec::new(),
+            active: 0,
+            next_doc_id: 0,
             indent_spaces: 2,
             tried_clipboard_once: false,
-            last_json: None,
             fonts_loaded: false,
-            search_input: String::new(),
-            pending_edits: Vec::new(),
-            edit_dialog: None,
+            clipboard_watcher: None,
+            clipboard_offer: None,
+            cut_buffer: None,
+            settings: Settings::default(),
+            redact_panel: None,
+            merge_panel: None,
+            script_panel: None,
+            theme_window: false,
+            command_palette: None,
+            keymap_editor: None,
         };
+        app.new_tab(tr!("未命名"));
         
         // 尝试从剪贴板填充
-        if let Some(value) = try_fill_from_clipboard(&mut app.input, app.indent_spaces) {
-            app.last_json = Some(value);
+        let doc = &mut app.tabs[0];
+        if let Some(value) = try_fill_from_clipboard(&mut doc.input, app.indent_spaces) {
+            doc.last_json = Some(value);
         }
+        doc.mark_saved();
         app.tried_clipboard_once = true;
         
         app
@@ -46,60 +96,487 @@ impl Default for JsonFmtApp {
 }
 
 impl JsonFmtApp {
-    /// 格式化 JSON 字符串
-    fn format_json(&self, json_str: &str) -> Result<String, String> {
-        match serde_json::from_str::<serde_json::Value>(json_str) {
-            Ok(v) => {
-                let mut buf = Vec::new();
-                let indent = " ".repeat(self.indent_spaces);
-                let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
-                let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
-                v.serialize(&mut ser).map_err(|e| e.to_string())?;
-                String::from_utf8(buf).map_err(|e| e.to_string())
+    /// 创建应用并恢复上次保存的设置
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let settings = Settings::load(cc.storage);
+        settings.theme.apply(&cc.egui_ctx);
+        cc.egui_ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("JSON 格式化").to_owned()));
+        Self {
+            settings,
+            ..Self::default()
+        }
+    }
+
+    /// 新建标签页并切换过去
+    fn new_tab(&mut self, title: &str) -> &mut Document {
+        self.next_doc_id += 1;
+        let title = if self.tabs.is_empty() {
+            title.to_string()
+        } else {
+            format!("{} {}", title, self.next_doc_id)
+        };
+        self.tabs.push(Document::new(self.next_doc_id, title));
+        self.active = self.tabs.len() - 1;
+        &mut self.tabs[self.active]
+    }
+
+    /// 关闭标签页，至少保留一个
+    fn close_tab(&mut self, idx: usize) {
+        self.tabs.remove(idx);
+        if self.tabs.is_empty() {
+            self.new_tab(tr!("未命名"));
+        }
+        if self.active >= self.tabs.len() || self.active > idx {
+            self.active = self.active.saturating_sub(1);
+        }
+    }
+
+    fn active_doc(&mut self) -> &mut Document {
+        &mut self.tabs[self.active]
+    }
+
+    /// 选择文件并在当前标签页打开；当前标签页有未保存修改时改为新建标签页
+    fn open_file_dialog(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .add_filter(tr!("所有文件"), &["*"])
+            .pick_file()
+        {
+            let format = self.settings.auto_format.then_some(self.indent_spaces);
+            let doc = self.active_doc();
+            if doc.is_dirty() && !doc.input.trim().is_empty() {
+                self.new_tab(tr!("未命名"));
             }
-            Err(e) => Err(e.to_string()),
+            self.active_doc().open_file(path, format);
         }
     }
 
-    /// 应用待处理的编辑操作
-    fn apply_edits(&mut self) {
-        if let Some(ref mut value) = self.last_json {
-            if let Some(formatted) = apply_edits(value, &mut self.pending_edits) {
-                self.input = formatted;
+    /// 检查剪贴板监视器，发现新的 JSON 时提示用户
+    fn poll_clipboard(&mut self) {
+        let Some(value) = self.clipboard_watcher.as_mut().and_then(|w| w.poll()) else {
+            return;
+        };
+        // 与已打开的内容相同（例如刚从本程序复制出去）时不再提示
+        if self.tabs.iter().any(|doc| doc.last_json.as_ref() == Some(&value)) {
+            return;
+        }
+        self.clipboard_offer = Some(value);
+    }
+
+    /// 显示剪贴板新内容的提示条
+    fn show_clipboard_offer(&mut self, ctx: &egui::Context) {
+        let Some(value) = &self.clipboard_offer else {
+            return;
+        };
+        let kind = if value.is_array() { tr!("数组") } else { tr!("对象") };
+        let mut open_new = false;
+        let mut replace = false;
+        let mut dismiss = false;
+        let can_replace = !self.tabs[self.active].is_dirty();
+
+        egui::TopBottomPanel::top("clipboard_offer").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(tr!("📋 剪贴板中检测到新的 JSON {}", kind));
+                open_new = ui.button(tr!("在新标签页打开")).clicked();
+                replace = ui
+                    .add_enabled(can_replace, egui::Button::new(tr!("替换当前标签页")))
+                    .on_disabled_hover_text(tr!("当前标签页有未保存的修改"))
+                    .clicked();
+                dismiss = ui.button(tr!("忽略")).clicked();
+            });
+        });
+
+        if open_new || replace {
+            if let Some(value) = self.clipboard_offer.take() {
+                let indent_spaces = self.indent_spaces;
+                if open_new {
+                    self.new_tab(tr!("剪贴板"));
+                }
+                let doc = self.active_doc();
+                doc.load_value(value, indent_spaces);
+                doc.mark_saved();
             }
+        } else if dismiss {
+            self.clipboard_offer = None;
+        }
+    }
+
+    /// 显示标签栏
+    fn show_tab_bar(&mut self, ctx: &egui::Context) {
+        let mut close = None;
+        let mut add = false;
+        egui::TopBottomPanel::top("tabs").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (idx, doc) in self.tabs.iter().enumerate() {
+                    let title = if doc.is_dirty() {
+                        format!("● {}", doc.display_title())
+                    } else {
+                        doc.display_title()
+                    };
+                    if ui.selectable_label(idx == self.active, title).clicked() {
+                        self.active = idx;
+                    }
+                    if ui.small_button("×").on_hover_text(tr!("关闭标签页")).clicked() {
+                        close = Some(idx);
+                    }
+                    ui.separator();
+                }
+                add = ui.button("➕").on_hover_text(tr!("新建标签页")).clicked();
+            });
+        });
+        if let Some(idx) = close {
+            self.close_tab(idx);
+        }
+        if add {
+            self.new_tab(tr!("未命名"));
+        }
+    }
+
+    /// 显示批量编辑面板
+    fn show_bulk_panel(&mut self, ctx: &egui::Context) {
+        let doc = &mut self.tabs[self.active];
+        let Some(panel) = &mut doc.bulk_panel else {
+            return;
+        };
+        let mut open = true;
+        let mut edits = None;
+        egui::Window::new(tr!("🧰 批量编辑"))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                edits = panel.show(ui, doc.last_json.as_ref(), &doc.input, doc.search.hits());
+            });
+        if let Some(edits) = edits {
+            doc.pending_edits.extend(edits);
+        }
+        if !open {
+            doc.bulk_panel = None;
+        }
+    }
+
+    /// 显示统计面板
+    fn show_stats_panel(&mut self, ctx: &egui::Context) {
+        let doc = &mut self.tabs[self.active];
+        let Some(panel) = &mut doc.stats_panel else {
+            return;
+        };
+        let mut open = true;
+        let mut target = None;
+        egui::Window::new(tr!("📊 统计"))
+            .open(&mut open)
+            .default_size([520.0, 560.0])
+            .show(ctx, |ui| {
+                target = panel.show(ui, doc.last_json.as_ref(), &doc.input);
+            });
+        if let Some(pointer) = target {
+            doc.reveal_node(pointer);
+        }
+        if !open {
+            doc.stats_panel = None;
+        }
+    }
+
+    /// 显示导出面板
+    fn show_export_panel(&mut self, ctx: &egui::Context) {
+        let doc = &mut self.tabs[self.active];
+        let title = doc.display_title();
+        let Some(panel) = &mut doc.export_panel else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(tr!("📤 导出"))
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                panel.show(ui, doc.last_json.as_ref(), &doc.input, &title, &self.settings, self.indent_spaces);
+            });
+        if !open {
+            doc.export_panel = None;
+        }
+    }
+
+    /// 显示数组的表格视图
+    fn show_table_view(&mut self, ctx: &egui::Context) {
+        let doc = &mut self.tabs[self.active];
+        let Some(view) = &mut doc.table_view else {
+            return;
+        };
+        let mut open = true;
+        let mut edits = None;
+        egui::Window::new(tr!("▦ 表格视图"))
+            .id(egui::Id::new(("table_view", doc.id)))
+            .open(&mut open)
+            .default_size([720.0, 480.0])
+            .show(ctx, |ui| {
+                edits = view.show(ui, doc.last_json.as_ref(), &doc.input);
+            });
+        if let Some(edits) = edits {
+            doc.pending_edits.extend(edits);
+        }
+        if !open {
+            doc.table_view = None;
+        }
+    }
+
+    /// 显示脱敏面板
+    fn show_redact_panel(&mut self, ctx: &egui::Context) {
+        let Some(panel) = &mut self.redact_panel else {
+            return;
+        };
+        let doc = &mut self.tabs[self.active];
+        let mut open = true;
+        let mut edits = None;
+        egui::Window::new(tr!("🕶 脱敏"))
+            .open(&mut open)
+            .default_width(440.0)
+            .show(ctx, |ui| {
+                edits = panel.show(
+                    ui,
+                    &mut self.settings.redaction_profiles,
+                    doc.last_json.as_ref(),
+                    &doc.input,
+                    self.indent_spaces,
+                );
+            });
+        if let Some(edits) = edits {
+            doc.pending_edits.extend(edits);
+        }
+        if !open {
+            self.redact_panel = None;
+        }
+    }
+
+    /// 显示合并面板，结果在新标签页中打开
+    fn show_merge_panel(&mut self, ctx: &egui::Context) {
+        let Some(panel) = &mut self.merge_panel else {
+            return;
+        };
+        let sources: Vec<MergeSource> = self
+            .tabs
+            .iter()
+            .map(|doc| MergeSource {
+                id: doc.id,
+                title: doc.display_title(),
+                text: &doc.input,
+                value: doc.last_json.as_ref(),
+            })
+            .collect();
+        let mut open = true;
+        let mut result = None;
+        egui::Window::new(tr!("🔀 合并"))
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                result = panel.show(ui, &sources, self.indent_spaces);
+            });
+        if let Some(value) = result {
+            let indent_spaces = self.indent_spaces;
+            let doc = self.new_tab(tr!("合并结果"));
+            doc.load_value(value, indent_spaces);
+        }
+        if !open {
+            self.merge_panel = None;
+        }
+    }
+
+    /// 显示脚本面板，结果作为可撤销的编辑应用到当前文档
+    fn show_script_panel(&mut self, ctx: &egui::Context) {
+        let Some(panel) = &mut self.script_panel else {
+            return;
+        };
+        let doc = &mut self.tabs[self.active];
+        let mut open = true;
+        let mut result = None;
+        egui::Window::new(tr!("📜 脚本"))
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                result = panel.show(
+                    ui,
+                    &mut self.settings.script_snippets,
+                    doc.id,
+                    doc.last_json.as_ref(),
+                    &doc.input,
+                    self.indent_spaces,
+                );
+            });
+        if let Some(new_value) = result {
+            doc.pending_edits.push(JsonEdit::EditValue {
+                pointer: String::new(),
+                new_value,
+            });
+        }
+        if !open {
+            self.script_panel = None;
+        }
+    }
+
+    /// 显示外观设置窗口
+    fn show_theme_window(&mut self, ctx: &egui::Context) {
+        let mut changed = false;
+        egui::Window::new(tr!("🎨 外观"))
+            .open(&mut self.theme_window)
+            .resizable(false)
+            .show(ctx, |ui| {
+                changed = self.settings.theme.show(ui);
+            });
+        // 切换语言后窗口标题也随之更新
+        if changed {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("JSON 格式化").to_owned()));
+        }
+    }
+
+    /// 显示快捷键设置窗口
+    fn show_keymap_editor(&mut self, ctx: &egui::Context) {
+        let Some(editor) = &mut self.keymap_editor else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(tr!("⌨ 快捷键"))
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                editor.show(ui, &mut self.settings.keymap);
+            });
+        if !open {
+            self.keymap_editor = None;
+        }
+    }
+
+    /// 显示命令面板，返回选中的命令
+    fn show_command_palette(&mut self, ctx: &egui::Context) -> Option<Command> {
+        let enabled: HashSet<Command> = Command::ALL.into_iter().filter(|&c| self.can_run(c)).collect();
+        let palette = self.command_palette.as_mut()?;
+        let mut open = true;
+        let command = palette.show(ctx, &self.settings.keymap, &enabled, &mut open);
+        if !open {
+            self.command_palette = None;
+        }
+        command
+    }
+
+    /// 命令在当前状态下能否执行
+    fn can_run(&self, command: Command) -> bool {
+        let doc = &self.tabs[self.active];
+        match command {
+            Command::Undo => doc.history.can_undo(),
+            Command::Redo => doc.history.can_redo(),
+            Command::FollowFile => doc.file_path.is_some(),
+            Command::Merge => self.tabs.len() > 1,
+            Command::FocusSearch
+            | Command::ExpandAll
+            | Command::CollapseAll
+            | Command::ExpandEmbedded
+            | Command::BulkEdit
+            | Command::Redact
+            | Command::Stats
+            | Command::Export
+            | Command::Script => doc.last_json.is_some(),
+            _ => true,
+        }
+    }
+
+    /// 执行命令；工具栏按钮、快捷键和命令面板都经由这里
+    fn run_command(&mut self, ctx: &egui::Context, command: Command) {
+        if !self.can_run(command) {
+            return;
+        }
+        let indent_spaces = self.indent_spaces;
+        let doc = &mut self.tabs[self.active];
+        match command {
+            Command::Format => doc.format_input(indent_spaces),
+            Command::Minify => doc.minify_input(),
+            Command::Clear => {
+                doc.input.clear();
+                doc.error = None;
+            }
+            Command::Copy => ctx.copy_text(doc.input.clone()),
+            Command::Open => self.open_file_dialog(),
+            Command::NewTab => {
+                self.new_tab(tr!("未命名"));
+            }
+            Command::CloseTab => self.close_tab(self.active),
+            Command::NextTab => self.active = (self.active + 1) % self.tabs.len(),
+            Command::PrevTab => self.active = (self.active + self.tabs.len() - 1) % self.tabs.len(),
+            Command::Undo => doc.undo(),
+            Command::Redo => doc.redo(),
+            Command::FocusSearch => doc.search.focus(),
+            Command::Find => doc.find.open(false),
+            Command::Replace => doc.find.open(true),
+            Command::ExpandAll => doc.set_tree_expand(TreeExpand::All),
+            Command::CollapseAll => doc.set_tree_expand(TreeExpand::None),
+            Command::ExpandEmbedded => doc.pending_edits.push(JsonEdit::ExpandEmbedded {
+                pointer: String::new(),
+            }),
+            Command::BulkEdit => {
+                doc.bulk_panel.get_or_insert_with(BulkPanel::default);
+            }
+            Command::Redact => {
+                self.redact_panel.get_or_insert_with(RedactPanel::default);
+            }
+            Command::Stats => {
+                doc.stats_panel.get_or_insert_with(StatsPanel::default);
+            }
+            Command::Export => {
+                doc.export_panel.get_or_insert_with(|| ExportPanel::new(String::new()));
+            }
+            Command::Merge => {
+                // 默认合并当前标签页和下一个标签页
+                let next = self.tabs[(self.active + 1) % self.tabs.len()].id;
+                let layers = vec![self.tabs[self.active].id, next];
+                self.merge_panel.get_or_insert_with(|| MergePanel::new(layers));
+            }
+            Command::Script => {
+                self.script_panel.get_or_insert_with(ScriptPanel::default);
+            }
+            Command::FollowFile => {
+                let follow = doc.watcher.is_none();
+                doc.set_following(follow);
+            }
+            Command::WatchClipboard => {
+                self.clipboard_watcher = match self.clipboard_watcher {
+                    Some(_) => None,
+                    None => Some(ClipboardWatcher::new()),
+                };
+                self.clipboard_offer = None;
+            }
+            Command::Appearance => self.theme_window = true,
+            Command::Keybindings => {
+                self.keymap_editor.get_or_insert_with(KeymapEditor::default);
+            }
+            Command::Palette => self.command_palette = Some(CommandPalette::default()),
         }
     }
 
     /// 显示编辑对话框
     fn show_edit_dialog(&mut self, ctx: &egui::Context) {
-        if let Some(dialog) = &mut self.edit_dialog {
+        let doc = &mut self.tabs[self.active];
+        if let Some(dialog) = &mut doc.edit_dialog {
             let mut should_close = false;
             let mut should_save = false;
 
             egui::Window::new(match dialog {
-                EditDialog::EditValue { .. } => "✏️ 编辑值",
-                EditDialog::EditKey { .. } => "✏️ 编辑键",
+                EditDialog::EditValue { .. } => tr!("✏️ 编辑值"),
+                EditDialog::EditKey { .. } => tr!("✏️ 编辑键"),
             })
             .collapsible(false)
             .resizable(false)
             .show(ctx, |ui| {
-                match dialog {
-                    EditDialog::EditValue { input, .. } => {
-                        ui.label("输入新值：");
-                        ui.text_edit_singleline(input);
-                    }
+                let valid = match dialog {
+                    EditDialog::EditValue { editor, pointer } => editor.show(ui, ("edit_value", pointer.as_str())),
                     EditDialog::EditKey { input, .. } => {
-                        ui.label("输入新键名：");
+                        ui.label(tr!("输入新键名："));
                         ui.text_edit_singleline(input);
+                        true
                     }
-                }
+                };
 
                 ui.horizontal(|ui| {
-                    if ui.button("✅ 保存").clicked() {
+                    if ui.add_enabled(valid, egui::Button::new(tr!("✅ 保存"))).clicked() {
                         should_save = true;
                         should_close = true;
                     }
-                    if ui.button("❌ 取消").clicked() {
+                    if ui.button(tr!("❌ 取消")).clicked() {
                         should_close = true;
                     }
                 });
@@ -107,14 +584,16 @@ impl JsonFmtApp {
 
             if should_save {
                 match dialog {
-                    EditDialog::EditValue { pointer, input } => {
-                        self.pending_edits.push(JsonEdit::EditValue {
-                            pointer: pointer.clone(),
-                            new_value: input.clone(),
-                        });
+                    EditDialog::EditValue { pointer, editor } => {
+                        if let Ok(new_value) = editor.build() {
+                            doc.pending_edits.push(JsonEdit::EditValue {
+                                pointer: pointer.clone(),
+                                new_value,
+                            });
+                        }
                     }
                     EditDialog::EditKey { object_pointer, old_key, input } => {
-                        self.pending_edits.push(JsonEdit::EditObjectKey {
+                        doc.pending_edits.push(JsonEdit::EditObjectKey {
                             object_pointer: object_pointer.clone(),
                             old_key: old_key.clone(),
                             new_key: input.clone(),
@@ -124,96 +603,244 @@ impl JsonFmtApp {
             }
 
             if should_close {
-                self.edit_dialog = None;
+                doc.edit_dialog = None;
             }
         }
     }
 }
 
 impl eframe::App for JsonFmtApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.settings.save(storage);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // 延迟加载 CJK 字体
+        // 延迟加载系统字体（中文与符号）
         if !self.fonts_loaded {
-            install_cjk_fonts(ctx);
+            install_fonts(ctx, &mut self.settings.theme.font_path);
             self.fonts_loaded = true;
         }
 
+        // 跟随文件：检查磁盘上的变化
+        let format = self.settings.auto_format.then_some(self.indent_spaces);
+        for doc in &mut self.tabs {
+            if let Some(result) = doc.watcher.as_mut().and_then(|w| w.poll()) {
+                doc.reload_followed(result, format);
+            }
+            if doc.watcher.is_some() {
+                ctx.request_repaint_after(watch::POLL_INTERVAL);
+            }
+        }
+
+        // 剪贴板监视
+        if self.clipboard_watcher.is_some() {
+            self.poll_clipboard();
+            ctx.request_repaint_after(clipboard::POLL_INTERVAL);
+        }
+
         // 显示编辑对话框
         self.show_edit_dialog(ctx);
+        self.show_bulk_panel(ctx);
+        self.show_redact_panel(ctx);
+        self.show_merge_panel(ctx);
+        self.show_script_panel(ctx);
+        self.show_table_view(ctx);
+        self.show_stats_panel(ctx);
+        self.show_export_panel(ctx);
+        self.show_theme_window(ctx);
+        self.show_keymap_editor(ctx);
+        self.settings.theme.sync_zoom(ctx);
+        if let Some(command) = self.show_command_palette(ctx) {
+            self.run_command(ctx, command);
+        }
+
+        // 快捷键；录制新快捷键或命令面板打开时不响应
+        let recording = self.keymap_editor.as_ref().is_some_and(KeymapEditor::recording);
+        let command = if recording || self.command_palette.is_some() {
+            None
+        } else {
+            self.settings.keymap.consume(ctx, ctx.wants_keyboard_input())
+        };
+        if let Some(command) = command {
+            self.run_command(ctx, command);
+        }
+        let find = &mut self.tabs[self.active].find;
+        if find.visible && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            find.visible = false;
+        }
+
+        let mut command = None;
 
         // 顶部工具栏
         egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            let doc = &self.tabs[self.active];
+            let has_json = doc.last_json.is_some();
             ui.horizontal_wrapped(|ui| {
-                if ui.button("格式化").clicked() {
-                    self.error = None;
-                    match serde_json::from_str::<serde_json::Value>(&self.input) {
-                        Ok(v) => {
-                            let mut buf = Vec::new();
-                            let indent = " ".repeat(self.indent_spaces);
-                            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
-                            let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
-                            let _ = v.serialize(&mut ser);
-                            self.input = String::from_utf8(buf).unwrap_or_default();
-                            if v.is_object() || v.is_array() {
-                                self.last_json = Some(v);
-                            }
-                        }
-                        Err(e) => {
-                            self.error = Some(e.to_string());
-                        }
-                    }
+                if ui.button(tr!("格式化")).clicked() {
+                    command = Some(Command::Format);
+                }
+                if ui.button(tr!("压缩")).clicked() {
+                    command = Some(Command::Minify);
+                }
+                if ui.button(tr!("清空")).clicked() {
+                    command = Some(Command::Clear);
+                }
+                if ui.button(tr!("复制")).clicked() {
+                    command = Some(Command::Copy);
                 }
 
-                if ui.button("压缩").clicked() {
-                    self.error = None;
-                    match serde_json::from_str::<serde_json::Value>(&self.input) {
-                        Ok(v) => {
-                            self.input = serde_json::to_string(&v).unwrap_or_default();
-                            if v.is_object() || v.is_array() {
-                                self.last_json = Some(v);
-                            }
-                        }
-                        Err(e) => {
-                            self.error = Some(e.to_string());
-                        }
-                    }
+                ui.separator();
+                if ui
+                    .add_enabled(doc.history.can_undo(), egui::Button::new(tr!("↶ 撤销")))
+                    .clicked()
+                {
+                    command = Some(Command::Undo);
+                }
+                if ui
+                    .add_enabled(doc.history.can_redo(), egui::Button::new(tr!("↷ 重做")))
+                    .clicked()
+                {
+                    command = Some(Command::Redo);
+                }
+                if ui
+                    .add_enabled(has_json, egui::Button::new(tr!("🧩 展开内嵌 JSON")))
+                    .on_hover_text(tr!("把字符串中转义的 JSON（可多层嵌套）展开为真实子树"))
+                    .clicked()
+                {
+                    command = Some(Command::ExpandEmbedded);
+                }
+                if ui
+                    .add_enabled(has_json, egui::Button::new(tr!("🧰 批量编辑")))
+                    .on_hover_text(tr!("对搜索结果或路径表达式选中的所有节点执行删除、赋值、改名等操作"))
+                    .clicked()
+                {
+                    command = Some(Command::BulkEdit);
+                }
+                if ui
+                    .add_enabled(has_json, egui::Button::new(tr!("🕶 脱敏")))
+                    .on_hover_text(tr!("按规则遮盖、哈希或替换密码、令牌、邮箱、卡号等敏感内容"))
+                    .clicked()
+                {
+                    command = Some(Command::Redact);
+                }
+                if ui
+                    .add_enabled(has_json, egui::Button::new(tr!("📊 统计")))
+                    .on_hover_text(tr!("节点数、深度、各子树大小、类型分布和键名频率"))
+                    .clicked()
+                {
+                    command = Some(Command::Stats);
+                }
+                if ui
+                    .add_enabled(has_json, egui::Button::new(tr!("📤 导出")))
+                    .on_hover_text(tr!("导出为 HTML、Markdown 或树视图的 PNG 截图，可先脱敏"))
+                    .clicked()
+                {
+                    command = Some(Command::Export);
+                }
+                if ui
+                    .add_enabled(self.tabs.len() > 1, egui::Button::new(tr!("🔀 合并")))
+                    .on_hover_text(tr!("深度合并多个标签页的文档，逐个处理冲突"))
+                    .on_disabled_hover_text(tr!("需要至少两个标签页"))
+                    .clicked()
+                {
+                    command = Some(Command::Merge);
+                }
+                if ui
+                    .add_enabled(has_json, egui::Button::new(tr!("📜 脚本")))
+                    .on_hover_text(tr!("用 jq 脚本变换整个文档，预览结果后作为可撤销的编辑应用"))
+                    .clicked()
+                {
+                    command = Some(Command::Script);
+                }
+
+                ui.separator();
+                if ui.button(tr!("📂 打开")).clicked() {
+                    command = Some(Command::Open);
                 }
 
-                if ui.button("清空").clicked() {
-                    self.input.clear();
-                    self.error = None;
+                let mut follow = doc.watcher.is_some();
+                if ui
+                    .add_enabled(doc.file_path.is_some(), egui::Checkbox::new(&mut follow, tr!("跟随文件")))
+                    .on_hover_text(tr!("文件变化时自动重新载入，并高亮变化的节点"))
+                    .changed()
+                {
+                    command = Some(Command::FollowFile);
                 }
-                if ui.button("复制").clicked() {
-                    ui.ctx().copy_text(self.input.clone());
+                if let Some(path) = &doc.file_path {
+                    ui.label(doc.display_title()).on_hover_text(path.display().to_string());
+                }
+
+                let mut watch_clipboard = self.clipboard_watcher.is_some();
+                if ui
+                    .checkbox(&mut watch_clipboard, tr!("监视剪贴板"))
+                    .on_hover_text(tr!("复制新的 JSON 时提示在新标签页中打开"))
+                    .changed()
+                {
+                    command = Some(Command::WatchClipboard);
                 }
 
                 ui.separator();
-                ui.label("缩进：");
+                ui.label(tr!("缩进："));
                 egui::ComboBox::from_id_salt("indent_top")
-                    .selected_text(format!("{} 空格", self.indent_spaces))
+                    .selected_text(tr!("{} 空格", self.indent_spaces))
                     .show_ui(ui, |ui| {
                         for s in [0, 1, 2, 3, 4].iter().copied() {
-                            ui.selectable_value(&mut self.indent_spaces, s, format!("{} 空格", s));
+                            ui.selectable_value(&mut self.indent_spaces, s, tr!("{} 空格", s));
                         }
                     });
+                ui.checkbox(&mut self.settings.auto_format, tr!("自动格式化"))
+                    .on_hover_text(tr!("编辑或打开文件时按当前缩进重新排版整个文档；关闭时保留原有排版，只在执行“格式化”时排版"));
+                if ui.button(tr!("🎨 外观")).on_hover_text(tr!("主题、界面缩放、字号、字体和配色")).clicked() {
+                    command = Some(Command::Appearance);
+                }
+                if ui.button(tr!("⌨ 快捷键")).on_hover_text(tr!("查看和修改快捷键")).clicked() {
+                    command = Some(Command::Keybindings);
+                }
+                let palette_hint = match self.settings.keymap.shortcut(Command::Palette) {
+                    Some(shortcut) => tr!("按名称搜索并执行任意命令（{}）", ctx.format_shortcut(&shortcut)),
+                    None => tr!("按名称搜索并执行任意命令").to_owned(),
+                };
+                if ui.button(tr!("🔎 命令")).on_hover_text(palette_hint).clicked() {
+                    command = Some(Command::Palette);
+                }
 
-                if let Some(err) = &self.error {
+                if let Some(err) = &doc.error {
                     ui.separator();
-                    ui.colored_label(egui::Color32::from_rgb(200, 60, 60), format!("错误：{}", err));
+                    ui.colored_label(Palette::of(ui).error, tr!("错误：{}", err));
                 }
             });
         });
 
+        if let Some(command) = command {
+            self.run_command(ctx, command);
+        }
+
+        self.show_tab_bar(ctx);
+        self.show_clipboard_offer(ctx);
+
         // 中央左右分栏
+        let indent_spaces = self.indent_spaces;
+        let auto_format = self.settings.auto_format;
+        let cut_buffer = &mut self.cut_buffer;
+        let tree_depth = &mut self.settings.tree_depth;
+        let mut depth_changed = false;
+        let doc = &mut self.tabs[self.active];
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.columns(2, |columns| {
                 // 左列：原始输入
                 let left = &mut columns[0];
-                left.label("原始 JSON：");
+                left.label(tr!("原始 JSON："));
+                if doc.find.visible
+                    && let Some(action) = doc.find.show(left)
+                    && let Some(text) = doc.find.replace(&doc.input, action)
+                {
+                    doc.replace_text(text);
+                }
+                doc.find.refresh(&doc.input);
                 let available_height = left.available_height();
                 
                 let edit_resp = egui::ScrollArea::vertical()
-                    .id_salt("input_scroll")
+                    .id_salt(("input_scroll", doc.id))
                     .auto_shrink(false)
                     .show(left, |ui| {
                         // 行号列宽度
@@ -229,143 +856,395 @@ impl eframe::App for JsonFmtApp {
                             
                             ui.add_space(4.0); // 小间距
                             
-                            // 文本编辑器
-                            let text_edit_output = egui::TextEdit::multiline(&mut self.input)
+                            // 树中选中节点后，在文本中选中对应范围
+                            let text_edit_id = egui::Id::new(("input_edit", doc.id));
+                            let jump = doc.text_jump.take().map(|range| {
+                                let start = byte_to_char(&doc.input, range.start);
+                                let end = byte_to_char(&doc.input, range.end);
+                                let mut state = egui::TextEdit::load_state(ui.ctx(), text_edit_id).unwrap_or_default();
+                                state.cursor.set_char_range(Some(CCursorRange::two(CCursor::new(start), CCursor::new(end))));
+                                state.store(ui.ctx(), text_edit_id);
+                                ui.memory_mut(|m| m.request_focus(text_edit_id));
+                                doc.last_caret = Some(end);
+                                start
+                            });
+
+                            // 要修改被折叠的内容时先展开，避免改动看不见的文本
+                            let editing = ui.memory(|m| m.has_focus(text_edit_id))
+                                && ui.input(|i| i.events.iter().any(edits_text));
+                            if editing
+                                && let Some(range) = egui::TextEdit::load_state(ui.ctx(), text_edit_id)
+                                    .and_then(|state| state.cursor.char_range())
+                            {
+                                let [a, b] = [range.primary.index, range.secondary.index]
+                                    .map(|c| char_to_byte(&doc.input, c));
+                                doc.highlighter.unfold_touching(&doc.input, a.min(b)..a.max(b));
+                            }
+
+                            // 文本编辑器：语法着色、括号匹配与折叠
+                            let colors = SyntaxColors::new(&Palette::of(ui), ui.visuals());
+                            let (matches, current_match) = doc.find.highlights();
+                            let decorations = Decorations {
+                                caret: doc.last_caret.map(|c| char_to_byte(&doc.input, c)),
+                                matches,
+                                current_match,
+                            };
+                            let highlighter = &mut doc.highlighter;
+                            let mut layouter = |ui: &egui::Ui, buf: &dyn egui::TextBuffer, wrap_width: f32| {
+                                let job = highlighter.layout_job(buf.as_str(), font_id.clone(), &colors, &decorations, wrap_width);
+                                ui.painter().layout_job(job)
+                            };
+                            let text_edit_output = egui::TextEdit::multiline(&mut doc.input)
+                                .id(text_edit_id)
                                 .desired_width(total_width - line_number_width - 4.0)
                                 .min_size(egui::vec2(total_width - line_number_width - 4.0, available_height))
                                 .code_editor()
-                                .hint_text("在此粘贴或输入原始 JSON")
+                                .layouter(&mut layouter)
+                                .hint_text(tr!("在此粘贴或输入原始 JSON"))
                                 .show(ui);
+
+                            if let Some(start) = jump {
+                                let rect = text_edit_output
+                                    .galley
+                                    .pos_from_cursor(CCursor::new(start))
+                                    .translate(text_edit_output.galley_pos.to_vec2());
+                                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                            }
+                            if let Some(range) = doc.find.take_scroll() {
+                                let rect = text_edit_output
+                                    .galley
+                                    .pos_from_cursor(CCursor::new(byte_to_char(&doc.input, range.start)))
+                                    .translate(text_edit_output.galley_pos.to_vec2());
+                                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                            }
                             
-                            // 绘制行号
+                            // 绘制行号、折叠标记、缩进参考线和当前行背景
                             let galley = &text_edit_output.galley;
                             let galley_pos = text_edit_output.galley_pos;
+                            let analysis = doc.highlighter.analyze(&doc.input);
+                            let caret_line = text_edit_output
+                                .cursor_range
+                                .filter(|_| text_edit_output.response.has_focus())
+                                .map(|r| analysis.line_of(char_to_byte(&doc.input, r.primary.index)));
+                            let painter = ui.painter();
+                            let gutter_color = Palette::of(ui).line_number;
+                            let mut fold_markers = Vec::new();
+                            let mut current_rows: Option<egui::Rect> = None;
                             
-                            let mut current_line = 1;
-                            let mut y_offset = galley_pos.y;
-                            
+                            let mut current_line = 0;
                             for (row_idx, row) in galley.rows.iter().enumerate() {
                                 // 检查这一行是否是新的逻辑行的开始
-                                let is_new_line = row_idx == 0 || {
-                                    if row_idx > 0 {
-                                        let prev_row = &galley.rows[row_idx - 1];
-                                        prev_row.ends_with_newline
-                                    } else {
-                                        false
-                                    }
-                                };
-                                
-                                if is_new_line {
-                                    // 绘制行号在预留区域
-                                    ui.painter().text(
-                                        egui::pos2(line_num_rect.right() - 4.0, y_offset),
-                                        egui::Align2::RIGHT_TOP,
-                                        format!("{}", current_line),
+                                let is_new_line = row_idx == 0 || galley.rows[row_idx - 1].ends_with_newline;
+                                if is_new_line && row_idx > 0 {
+                                    current_line += 1;
+                                }
+                                let rect = row.rect().translate(galley_pos.to_vec2());
+                                // 折叠隐藏的行高度接近 0，不绘制
+                                if rect.height() < font_id.size * 0.5 {
+                                    continue;
+                                }
+                                if caret_line == Some(current_line) {
+                                    current_rows = Some(current_rows.map_or(rect, |r| r.union(rect)));
+                                }
+                                if !is_new_line {
+                                    continue;
+                                }
+
+                                // 绘制行号在预留区域
+                                painter.text(
+                                    egui::pos2(line_num_rect.right() - 4.0, rect.top()),
+                                    egui::Align2::RIGHT_TOP,
+                                    format!("{}", current_line + 1),
+                                    font_id.clone(),
+                                    gutter_color,
+                                );
+
+                                if let Some(pair) = analysis.fold_at(current_line) {
+                                    let folded = doc.highlighter.is_folded(pair.open);
+                                    let marker_rect = egui::Rect::from_min_size(
+                                        egui::pos2(line_num_rect.left(), rect.top()),
+                                        egui::vec2(14.0, rect.height()),
+                                    );
+                                    painter.text(
+                                        marker_rect.left_center(),
+                                        egui::Align2::LEFT_CENTER,
+                                        if folded { "▸" } else { "▾" },
                                         font_id.clone(),
-                                        egui::Color32::from_gray(128),
+                                        gutter_color,
                                     );
-                                    current_line += 1;
+                                    fold_markers.push((marker_rect, pair.open));
+                                    if folded {
+                                        let at = galley
+                                            .pos_from_cursor(CCursor::new(byte_to_char(&doc.input, pair.open + 1)))
+                                            .translate(galley_pos.to_vec2());
+                                        painter.text(at.left_center(), egui::Align2::LEFT_CENTER, " ⋯ ", font_id.clone(), gutter_color);
+                                    }
+                                }
+
+                                // 缩进参考线：每级缩进画一条竖线
+                                let indent = doc.input[analysis.line_starts[current_line]..]
+                                    .bytes()
+                                    .take_while(|&b| b == b' ')
+                                    .count();
+                                for col in (0..indent).step_by(indent_spaces.max(1)) {
+                                    if let Some(glyph) = row.glyphs.get(col) {
+                                        let x = galley_pos.x + row.pos.x + glyph.pos.x;
+                                        painter.line_segment(
+                                            [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+                                            egui::Stroke::new(1.0, colors.indent_guide),
+                                        );
+                                    }
                                 }
-                                
-                                y_offset += row.rect().height();
+                            }
+
+                            if let Some(rows) = current_rows {
+                                let line_rect = egui::Rect::from_x_y_ranges(text_edit_output.response.rect.x_range(), rows.y_range());
+                                painter.rect_filled(line_rect, 0.0, colors.current_line);
+                            }
+
+                            // 点击行号左侧的标记折叠或展开
+                            let gutter_rect = egui::Rect::from_x_y_ranges(line_num_rect.x_range(), text_edit_output.response.rect.y_range());
+                            let gutter = ui.interact(gutter_rect, text_edit_id.with("gutter"), egui::Sense::click());
+                            let marker_at = |pos: Option<egui::Pos2>| {
+                                pos.and_then(|pos| fold_markers.iter().find(|(r, _)| r.contains(pos)).map(|&(_, open)| open))
+                            };
+                            if marker_at(gutter.hover_pos()).is_some() {
+                                ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                            }
+                            if gutter.clicked()
+                                && let Some(open) = marker_at(gutter.interact_pointer_pos())
+                            {
+                                doc.highlighter.toggle_fold(open);
                             }
                             
                             text_edit_output
                         }).inner
                     });
 
-                if edit_resp.inner.response.changed() {
-                    // 尝试自动格式化
-                    match self.format_json(&self.input) {
-                        Ok(formatted) => {
-                            self.input = formatted;
-                            self.error = None;
-                            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&self.input) {
-                                if v.is_object() || v.is_array() {
-                                    self.last_json = Some(v);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            self.error = Some(e);
-                            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&self.input) {
-                                if v.is_object() || v.is_array() {
-                                    self.last_json = Some(v);
-                                }
-                            }
-                        }
+                // 光标移动时同步选中树节点
+                let caret = edit_resp.inner.cursor_range.map(|r| r.primary.index);
+                if edit_resp.inner.response.has_focus() && caret.is_some() && caret != doc.last_caret {
+                    doc.last_caret = caret;
+                    if let Some(idx) = caret {
+                        doc.select_from_caret(idx);
                     }
                 }
 
+                if edit_resp.inner.response.changed() {
+                    // 重新解析；开启自动格式化时顺带排版
+                    doc.parse_input(auto_format.then_some(indent_spaces));
+                }
+
                 // 右列：JSON 树解析展示
                 let right = &mut columns[1];
-                right.horizontal(|ui| {
-                    ui.label("解析树：");
-                    ui.add(
-                        egui::TextEdit::singleline(&mut self.search_input)
-                            .hint_text("🔍 搜索...")
-                            .desired_width(f32::INFINITY)
-                    );
-                });
+                if let Some(pointer) = doc.search.show(right) {
+                    doc.reveal_node(pointer);
+                }
+                if let Some(pointer) = doc.path_bar.show(right, doc.last_json.as_ref(), doc.selected.as_deref()) {
+                    doc.jump_to(pointer);
+                }
+                if doc.last_json.is_some() {
+                    match doc.expand_bar.show(right, tree_depth) {
+                        Some(TreeExpand::Default) => depth_changed = true,
+                        Some(expand) => doc.set_tree_expand(expand),
+                        None => {}
+                    }
+                }
 
-                let to_show = if self.input.trim().is_empty() {
-                    self.last_json.as_ref()
-                } else if let Ok(v) = serde_json::from_str::<serde_json::Value>(&self.input) {
+                let to_show = if doc.input.trim().is_empty() {
+                    doc.last_json.as_ref()
+                } else if let Ok(v) = serde_json::from_str::<serde_json::Value>(&doc.input) {
                     if v.is_object() || v.is_array() {
-                        self.last_json = Some(v);
-                        self.last_json.as_ref()
+                        doc.last_json = Some(v);
+                        doc.last_json.as_ref()
                     } else {
-                        self.last_json.as_ref()
+                        doc.last_json.as_ref()
                     }
                 } else {
-                    self.last_json.as_ref()
+                    doc.last_json.as_ref()
                 };
 
                 if let Some(v) = to_show {
+                    doc.search.refresh(&doc.input, v);
                     let available_height = right.available_height();
-                    let pending_edits = &mut self.pending_edits;
-                    let edit_dialog = &mut self.edit_dialog;
-                    let search_input = &self.search_input;
-                    
-                    egui::ScrollArea::vertical()
-                        .id_salt("tree_scroll")
-                        .auto_shrink(false)
-                        .max_height(available_height)
-                        .show(right, |ui| {
-                            let default_expand = if search_input.is_empty() {
-                                DefaultExpand::ToLevel(3)
-                            } else {
-                                DefaultExpand::SearchResultsOrAll(search_input)
-                            };
+                    let mut expand_subtree = None;
+                    let mut actions = TreeActions {
+                        pending_edits: &mut doc.pending_edits,
+                        edit_dialog: &mut doc.edit_dialog,
+                        cut_buffer: &mut *cut_buffer,
+                        table_view: &mut doc.table_view,
+                        expand_subtree: &mut expand_subtree,
+                        export_panel: &mut doc.export_panel,
+                        root: v,
+                    };
+                    let search = &doc.search;
+                    let previews = &mut doc.previews;
+                    let expansion = &mut doc.expansion;
+                    let changed = &doc.changed_pointers;
+                    let selected = doc.selected.as_deref();
+                    let reveal = doc.reveal.as_ref().map(|(p, _)| p.as_str());
+                    let mut clicked = None;
+                    let mut revealed = false;
+                    let doc_id = doc.id;
+                    let main_expand = match doc.tree_expand {
+                        TreeExpand::Default => DefaultExpand::ToLevel(*tree_depth),
+                        TreeExpand::Level(level) => DefaultExpand::ToLevel(level),
+                        TreeExpand::All => DefaultExpand::All,
+                        TreeExpand::None => DefaultExpand::None,
+                    };
 
-                            // 配置文本换行：最多显示2行，使用可用宽度，不在任意位置断行
-                            let wrapping = JsonTreeWrapping {
-                                max_rows: 2,
-                                max_width: JsonTreeMaxWidth::UiAvailableWidth,
-                                break_anywhere: false,
-                            };
-                            let wrapping_config = JsonTreeWrappingConfig {
-                                value_when_root: wrapping,
-                                value_with_expanded_parent: wrapping,
-                                value_in_collapsed_root: wrapping,
-                            };
+                    // 筛选模式下显示只含匹配分支的树（只读），路径需换算回原文档
+                    let pruned = if search.filtering() { search.pruned() } else { None };
+                    let (tree_id, tree_value, default_expand) = match pruned {
+                        Some(p) => (egui::Id::new(("json_tree_filtered", doc_id)), &p.value, DefaultExpand::All),
+                        None => (egui::Id::new(("json_tree", doc_id)), v, main_expand),
+                    };
+                    // 重置展开状态只作用于完整的树，筛选期间保留请求
+                    let reset_expanded = doc.reset_expanded && !search.filtering();
+                    let reveal_shown = reveal.and_then(|r| match pruned {
+                        Some(p) => p.filtered(r),
+                        None => Some(r),
+                    });
 
-                            JsonTree::new("json_tree", v)
-                                .default_expand(default_expand)
-                                .style(JsonTreeStyle::new().wrapping_config(wrapping_config))
-                                .on_render(|ui, context| {
-                                    let pointer = context.pointer().to_json_pointer_string();
-                                    show_context_menu(ui, context, pointer, pending_edits, edit_dialog);
-                                })
-                                .show(ui);
-                        });
+                    if search.filtering() && pruned.is_none() {
+                        right.label(tr!("无匹配结果"));
+                    } else {
+                        egui::ScrollArea::vertical()
+                            .id_salt(("tree_scroll", doc_id))
+                            .auto_shrink(false)
+                            .max_height(available_height)
+                            .show(right, |ui| {
+                                // 配置文本换行：最多显示2行，使用可用宽度，不在任意位置断行
+                                let wrapping = JsonTreeWrapping {
+                                    max_rows: 2,
+                                    max_width: JsonTreeMaxWidth::UiAvailableWidth,
+                                    break_anywhere: false,
+                                };
+                                let wrapping_config = JsonTreeWrappingConfig {
+                                    value_when_root: wrapping,
+                                    value_with_expanded_parent: wrapping,
+                                    value_in_collapsed_root: wrapping,
+                                };
+
+                                let palette = Palette::of(ui);
+
+                                // 每个标签页使用独立的 id，展开状态互不影响，重新载入时也得以保留
+                                let tree = JsonTree::new(tree_id, tree_value)
+                                    .default_expand(default_expand)
+                                    .style(
+                                        JsonTreeStyle::new()
+                                            .visuals(tree_visuals(&palette))
+                                            .wrapping_config(wrapping_config),
+                                    )
+                                    .on_render(|ui, mut context| {
+                                        let shown = context.pointer().to_json_pointer_string();
+                                        let pointer = pruned.map_or_else(|| shown.clone(), |p| p.original(&shown).to_string());
+                                        // 编辑后恢复手动展开的状态；筛选视图的路径与完整的树不同，不参与记录
+                                        let forced = if pruned.is_none() { expansion.forced(&pointer) } else { None };
+                                        if let Some(open) = forced {
+                                            set_expanded(&mut context, open);
+                                        }
+                                        if let Some(target) = reveal_shown {
+                                            reveal_ancestor(&mut context, &shown, target);
+                                        }
+                                        if search.should_expand(&pointer) {
+                                            expand(&mut context);
+                                        }
+                                        if let (None, Some(open)) = (pruned, is_expanded(&context)) {
+                                            expansion.observe(&pointer, open);
+                                        }
+                                        // 先占位背景，渲染完成后再按节点区域填充高亮
+                                        let background = ui.painter().add(egui::Shape::Noop);
+                                        let is_selected = selected == Some(pointer.as_str());
+                                        let is_hit = search.is_hit(&pointer);
+                                        let is_changed = changed.contains(&pointer);
+                                        let is_reveal_target = reveal == Some(pointer.as_str());
+                                        let leaf = match &context {
+                                            RenderContext::BaseValue(ctx) => Some(ctx.value),
+                                            _ => None,
+                                        };
+                                        let response = if pruned.is_some() {
+                                            context.render_default(ui)
+                                        } else {
+                                            show_context_menu(ui, context, pointer.clone(), &mut actions)
+                                        };
+                                        // 时间戳、颜色、链接等值后面附加预览标记
+                                        if let Some(value) = leaf {
+                                            previews.show_inline(ui, value);
+                                        }
+                                        if response.clicked() {
+                                            clicked = Some(pointer);
+                                        }
+                                        if is_reveal_target {
+                                            response.scroll_to_me(Some(egui::Align::Center));
+                                            revealed = true;
+                                        }
+                                        let highlight = if is_selected {
+                                            Some(ui.visuals().selection.bg_fill.gamma_multiply(0.6))
+                                        } else if is_hit {
+                                            Some(palette.tree_match)
+                                        } else if is_changed {
+                                            Some(palette.tree_changed)
+                                        } else {
+                                            None
+                                        };
+                                        if let Some(color) = highlight {
+                                            ui.painter().set(
+                                                background,
+                                                egui::Shape::rect_filled(response.rect.expand(1.0), 2.0, color),
+                                            );
+                                        }
+                                    })
+                                    .show(ui);
+                                if reset_expanded {
+                                    tree.reset_expanded(ui);
+                                    ui.ctx().request_repaint();
+                                }
+                            });
+                    }
+                    if reset_expanded {
+                        doc.reset_expanded = false;
+                    }
+                    if let Some(pointer) = expand_subtree {
+                        doc.expansion.expand_subtree(pointer);
+                    }
+                    doc.search.end_frame(right.ctx());
+                    doc.expansion.end_frame(right.ctx());
+
+                    // 点击树节点：在文本中定位
+                    if let Some(pointer) = clicked {
+                        doc.select_from_tree(pointer);
+                    }
+
+                    // 目标节点已显示或尝试次数用完后停止自动展开
+                    let reveal_done = match &mut doc.reveal {
+                        Some((_, frames)) if !revealed && *frames > 0 => {
+                            *frames -= 1;
+                            right.ctx().request_repaint();
+                            false
+                        }
+                        Some(_) => true,
+                        None => false,
+                    };
+                    if reveal_done {
+                        doc.reveal = None;
+                    }
 
                     // 应用所有待处理的编辑
-                    self.apply_edits();
+                    doc.apply_edits(indent_spaces);
                 } else {
-                    right.label("无解析结果");
+                    right.label(tr!("无解析结果"));
                 }
             });
         });
+
+        // 修改默认层数后，使用默认层数的标签页按新的层数重新展开
+        if depth_changed {
+            for doc in &mut self.tabs {
+                if doc.tree_expand == TreeExpand::Default {
+                    doc.set_tree_expand(TreeExpand::Default);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,301 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Number, Value};
+
+use crate::edit::JsonEdit;
+use crate::i18n::tr;
+use crate::tree_ops::{join_pointer, split_pointer};
+
+/// 预览中单个值的最大显示长度
+const PREVIEW_LEN: usize = 80;
+
+/// 批量编辑的操作
+pub enum BulkOp {
+    Delete,
+    SetValue(Value),
+    RenameKey(String),
+    /// 把标量值替换为 "***"
+    Redact,
+    Transform(Transform),
+}
+
+/// 对标量值的变换
+#[derive(Clone, Copy, PartialEq)]
+pub enum Transform {
+    Trim,
+    Lowercase,
+    Uppercase,
+    Multiply(f64),
+}
+
+/// 单个节点上的改动及其对应的编辑操作
+pub struct BulkChange {
+    pub pointer: String,
+    pub before: String,
+    /// 改动后的内容，删除时为 None
+    pub after: Option<String>,
+    pub edit: JsonEdit,
+}
+
+pub struct BulkPlan {
+    pub changes: Vec<BulkChange>,
+    /// 不适用该操作或不会产生变化而跳过的节点数
+    pub skipped: usize,
+}
+
+/// 为所有目标节点生成编辑操作
+///
+/// 删除和改名按文档逆序生成，先处理后面和更深的节点，保证前面操作不会让后面的路径失效。
+pub fn plan(root: &Value, targets: &[String], op: &BulkOp) -> BulkPlan {
+    let order = document_order(root);
+    let mut targets: Vec<&String> = targets.iter().filter(|p| order.contains_key(p.as_str())).collect();
+    targets.sort_by_key(|p| order[p.as_str()]);
+    targets.dedup();
+    let total = targets.len();
+
+    // 删除或整体赋值时，祖先已被处理的节点无需再处理
+    if matches!(op, BulkOp::Delete | BulkOp::SetValue(_)) {
+        let set: HashSet<&str> = targets.iter().map(|p| p.as_str()).collect();
+        targets.retain(|p| !has_ancestor_in(p, &set));
+    }
+    if matches!(op, BulkOp::Delete | BulkOp::RenameKey(_)) {
+        targets.reverse();
+    }
+
+    let mut renamed: HashMap<String, HashSet<String>> = HashMap::new();
+    let changes: Vec<BulkChange> = targets
+        .into_iter()
+        .filter_map(|pointer| change(root, pointer, op, &mut renamed))
+        .collect();
+    BulkPlan {
+        skipped: total - changes.len(),
+        changes,
+    }
+}
+
+fn change(
+    root: &Value,
+    pointer: &str,
+    op: &BulkOp,
+    renamed: &mut HashMap<String, HashSet<String>>,
+) -> Option<BulkChange> {
+    let (parent, segment) = split_pointer(pointer)?;
+    let value = root.pointer(pointer)?;
+    let parent_value = root.pointer(parent)?;
+    let before = summarize(value);
+
+    let (after, edit) = match op {
+        BulkOp::Delete => {
+            let edit = if parent_value.is_object() {
+                JsonEdit::DeleteFromObject {
+                    object_pointer: parent.to_string(),
+                    key: segment,
+                }
+            } else {
+                JsonEdit::DeleteFromArray {
+                    array_pointer: parent.to_string(),
+                    idx: segment.parse().ok()?,
+                }
+            };
+            (None, edit)
+        }
+        BulkOp::RenameKey(new_key) => {
+            let map = parent_value.as_object()?;
+            // 新键名已存在（包括本批次中改出来的）时跳过，避免覆盖其他成员
+            let taken = renamed.entry(parent.to_string()).or_default();
+            if segment == *new_key || map.contains_key(new_key) || taken.contains(new_key) {
+                return None;
+            }
+            taken.insert(new_key.clone());
+            let edit = JsonEdit::EditObjectKey {
+                object_pointer: parent.to_string(),
+                old_key: segment.clone(),
+                new_key: new_key.clone(),
+            };
+            return Some(BulkChange {
+                pointer: pointer.to_string(),
+                before: tr!("键 {}", segment),
+                after: Some(tr!("键 {}", new_key)),
+                edit,
+            });
+        }
+        BulkOp::SetValue(new_value) => set(pointer, value, new_value.clone())?,
+        BulkOp::Redact => match value {
+            Value::Null | Value::Object(_) | Value::Array(_) => return None,
+            _ => set(pointer, value, Value::String("***".to_string()))?,
+        },
+        BulkOp::Transform(transform) => set(pointer, value, apply_transform(value, *transform)?)?,
+    };
+    Some(BulkChange {
+        pointer: pointer.to_string(),
+        before,
+        after,
+        edit,
+    })
+}
+
+/// 赋值操作；新值与旧值相同时返回 None
+fn set(pointer: &str, old: &Value, new_value: Value) -> Option<(Option<String>, JsonEdit)> {
+    if *old == new_value {
+        return None;
+    }
+    Some((
+        Some(summarize(&new_value)),
+        JsonEdit::EditValue {
+            pointer: pointer.to_string(),
+            new_value,
+        },
+    ))
+}
+
+/// 对标量值做变换；不适用的类型返回 None
+pub fn apply_transform(value: &Value, transform: Transform) -> Option<Value> {
+    match (value, transform) {
+        (Value::String(s), Transform::Trim) => Some(Value::String(s.trim().to_string())),
+        (Value::String(s), Transform::Lowercase) => Some(Value::String(s.to_lowercase())),
+        (Value::String(s), Transform::Uppercase) => Some(Value::String(s.to_uppercase())),
+        (Value::Number(n), Transform::Multiply(factor)) => {
+            // 整数乘以整数时保持整数
+            if factor.fract() == 0.0 && factor.abs() < i64::MAX as f64
+                && let Some(product) = n.as_i64().and_then(|i| i.checked_mul(factor as i64))
+            {
+                return Some(Value::Number(product.into()));
+            }
+            Number::from_f64(n.as_f64()? * factor).map(Value::Number)
+        }
+        _ => None,
+    }
+}
+
+/// 预览用的简短文本
+pub fn summarize(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() > PREVIEW_LEN {
+        format!("{}…", text.chars().take(PREVIEW_LEN).collect::<String>())
+    } else {
+        text
+    }
+}
+
+fn has_ancestor_in(pointer: &str, set: &HashSet<&str>) -> bool {
+    pointer
+        .match_indices('/')
+        .any(|(idx, _)| idx > 0 && set.contains(&pointer[..idx]))
+}
+
+/// 每个节点在文档中的先序位置
+fn document_order(root: &Value) -> HashMap<String, usize> {
+    let mut order = HashMap::new();
+    let mut stack = vec![(String::new(), root)];
+    while let Some((pointer, value)) = stack.pop() {
+        let idx = order.len();
+        order.insert(pointer.clone(), idx);
+        match value {
+            Value::Object(map) => {
+                for (k, child) in map.iter().rev() {
+                    stack.push((join_pointer(&pointer, k), child));
+                }
+            }
+            Value::Array(arr) => {
+                for (i, child) in arr.iter().enumerate().rev() {
+                    stack.push((format!("{}/{}", pointer, i), child));
+                }
+            }
+            _ => {}
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::edit::apply_edit;
+
+    /// 按顺序应用计划中的编辑，返回结果和跳过的节点数
+    fn run(root: &Value, targets: &[&str], op: BulkOp) -> (Value, usize) {
+        let targets: Vec<String> = targets.iter().map(|p| p.to_string()).collect();
+        let plan = plan(root, &targets, &op);
+        let mut out = root.clone();
+        for change in plan.changes {
+            apply_edit(&mut out, change.edit);
+        }
+        (out, plan.skipped)
+    }
+
+    #[test]
+    fn delete_array_elements_in_any_order() {
+        let root = json!({"list": [0, 1, 2, 3, 4]});
+        let (out, skipped) = run(&root, &["/list/1", "/list/3", "/list/1", "/list/4"], BulkOp::Delete);
+        assert_eq!(out, json!({"list": [0, 2]}));
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn delete_skips_descendants_of_deleted_nodes() {
+        let root = json!({"a": {"b": [1, 2]}, "c": 3});
+        let (out, skipped) = run(&root, &["/a/b/0", "/a", "/a/b", "/missing"], BulkOp::Delete);
+        assert_eq!(out, json!({"c": 3}));
+        // 两个后代算作跳过，不存在的路径不计入
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn rename_skips_existing_and_duplicate_keys() {
+        let root = json!({"users": [{"name": "a"}, {"name": "b", "title": "x"}], "x": {"n1": 1, "n2": 2}});
+        let (out, skipped) = run(&root, &["/users/0/name", "/users/1/name"], BulkOp::RenameKey("title".into()));
+        assert_eq!(out["users"], json!([{"title": "a"}, {"name": "b", "title": "x"}]));
+        assert_eq!(skipped, 1);
+
+        // 同一对象中的两个键不能改成同一个名字
+        let (out, skipped) = run(&root, &["/x/n1", "/x/n2"], BulkOp::RenameKey("n".into()));
+        assert_eq!(out["x"].as_object().unwrap().len(), 2);
+        assert_eq!(skipped, 1);
+        // 数组元素没有键
+        let (_, skipped) = run(&root, &["/users/0"], BulkOp::RenameKey("k".into()));
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn set_and_redact() {
+        let root = json!({"a": 1, "b": null, "c": {"d": "secret"}, "e": true});
+        let (out, skipped) = run(&root, &["/a", "/c", "/c/d"], BulkOp::SetValue(json!(1)));
+        assert_eq!(out, json!({"a": 1, "b": null, "c": 1, "e": true}));
+        // /a 已经是 1，/c/d 在被整体赋值的 /c 之下
+        assert_eq!(skipped, 2);
+
+        let (out, skipped) = run(&root, &["/a", "/b", "/c", "/c/d", "/e"], BulkOp::Redact);
+        assert_eq!(out, json!({"a": "***", "b": null, "c": {"d": "***"}, "e": "***"}));
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn transforms() {
+        let t = |value: Value, transform| apply_transform(&value, transform);
+        assert_eq!(t(json!("  Hi "), Transform::Trim), Some(json!("Hi")));
+        assert_eq!(t(json!("Hi"), Transform::Lowercase), Some(json!("hi")));
+        assert_eq!(t(json!("Hi"), Transform::Uppercase), Some(json!("HI")));
+        assert_eq!(t(json!(3), Transform::Multiply(2.0)), Some(json!(6)));
+        assert!(t(json!(3), Transform::Multiply(2.0)).unwrap().is_i64());
+        assert_eq!(t(json!(3), Transform::Multiply(0.5)), Some(json!(1.5)));
+        assert_eq!(t(json!(1.5), Transform::Multiply(2.0)), Some(json!(3.0)));
+        // 整数溢出时改用浮点数
+        assert_eq!(t(json!(i64::MAX), Transform::Multiply(2.0)), Some(json!(i64::MAX as f64 * 2.0)));
+        assert_eq!(t(json!("3"), Transform::Multiply(2.0)), None);
+        assert_eq!(t(json!(3), Transform::Trim), None);
+
+        let root = json!([" a", "b", 1]);
+        let (out, skipped) = run(&root, &["/0", "/1", "/2"], BulkOp::Transform(Transform::Trim));
+        assert_eq!(out, json!(["a", "b", 1]));
+        assert_eq!(skipped, 2);
+    }
+
+    #[test]
+    fn summarize_truncates() {
+        let long = Value::String("x".repeat(200));
+        assert_eq!(summarize(&long).chars().count(), PREVIEW_LEN + 1);
+        assert_eq!(summarize(&json!({"a": 1})), r#"{"a":1}"#);
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use appkit::theme::Palette;
+use eframe::egui;
+use serde_json::Value;
+
+use crate::bulk::{self, BulkOp, BulkPlan, Transform};
+use crate::edit::JsonEdit;
+use crate::i18n::tr;
+use crate::json_path;
+use crate::value_editor::ValueEditor;
+
+/// 预览列表最多显示的条数
+const MAX_PREVIEW_ROWS: usize = 500;
+
+/// 批量编辑的目标来源
+#[derive(Clone, Copy, PartialEq)]
+enum Source {
+    SearchResults,
+    Path,
+}
+
+/// 批量编辑可选的操作
+#[derive(Clone, Copy, PartialEq)]
+enum OpKind {
+    Delete,
+    SetValue,
+    RenameKey,
+    Redact,
+    Trim,
+    Lowercase,
+    Uppercase,
+    Multiply,
+}
+
+impl OpKind {
+    const ALL: [OpKind; 8] = [
+        OpKind::Delete,
+        OpKind::SetValue,
+        OpKind::RenameKey,
+        OpKind::Redact,
+        OpKind::Trim,
+        OpKind::Lowercase,
+        OpKind::Uppercase,
+        OpKind::Multiply,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            OpKind::Delete => tr!("删除节点"),
+            OpKind::SetValue => tr!("设置为指定值"),
+            OpKind::RenameKey => tr!("重命名键"),
+            OpKind::Redact => tr!("打码（替换为 ***）"),
+            OpKind::Trim => tr!("去除首尾空白"),
+            OpKind::Lowercase => tr!("转为小写"),
+            OpKind::Uppercase => tr!("转为大写"),
+            OpKind::Multiply => tr!("数值乘以"),
+        }
+    }
+}
+
+/// 批量编辑面板：选定目标和操作，预览改动后再应用
+pub struct BulkPanel {
+    source: Source,
+    path: String,
+    op: OpKind,
+    value_editor: ValueEditor,
+    new_key: String,
+    factor: String,
+    preview: Option<BulkPlan>,
+    /// 生成预览时原文的哈希，原文变化后预览作废
+    preview_key: u64,
+    error: Option<String>,
+}
+
+impl Default for BulkPanel {
+    fn default() -> Self {
+        Self {
+            source: Source::SearchResults,
+            path: String::new(),
+            op: OpKind::Delete,
+            value_editor: ValueEditor::from_value(&Value::Null),
+            new_key: String::new(),
+            factor: "1".to_string(),
+            preview: None,
+            preview_key: 0,
+            error: None,
+        }
+    }
+}
+
+/// 逐条列出改动：路径、红色的旧值和绿色的新值
+pub fn show_preview(ui: &mut egui::Ui, plan: &BulkPlan) {
+    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+        for change in plan.changes.iter().take(MAX_PREVIEW_ROWS) {
+            ui.monospace(&change.pointer);
+            ui.horizontal_wrapped(|ui| {
+                ui.colored_label(Palette::of(ui).error, format!("- {}", change.before));
+            });
+            ui.horizontal_wrapped(|ui| match &change.after {
+                Some(after) => {
+                    ui.colored_label(Palette::of(ui).success, format!("+ {}", after));
+                }
+                None => {
+                    ui.weak(tr!("（删除）"));
+                }
+            });
+        }
+        if plan.changes.len() > MAX_PREVIEW_ROWS {
+            ui.weak(tr!("……其余 {} 项未显示", plan.changes.len() - MAX_PREVIEW_ROWS));
+        }
+    });
+}
+
+pub fn text_hash(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl BulkPanel {
+    fn build_op(&self) -> Result<BulkOp, String> {
+        Ok(match self.op {
+            OpKind::Delete => BulkOp::Delete,
+            OpKind::SetValue => BulkOp::SetValue(self.value_editor.build()?),
+            OpKind::RenameKey if self.new_key.is_empty() => return Err(tr!("请输入新键名").to_string()),
+            OpKind::RenameKey => BulkOp::RenameKey(self.new_key.clone()),
+            OpKind::Redact => BulkOp::Redact,
+            OpKind::Trim => BulkOp::Transform(Transform::Trim),
+            OpKind::Lowercase => BulkOp::Transform(Transform::Lowercase),
+            OpKind::Uppercase => BulkOp::Transform(Transform::Uppercase),
+            OpKind::Multiply => {
+                let factor = self.factor.trim().parse().map_err(|_| tr!("倍数不是有效的数字").to_string())?;
+                BulkOp::Transform(Transform::Multiply(factor))
+            }
+        })
+    }
+
+    fn make_preview(&mut self, root: &Value, text: &str, search_hits: &[String]) {
+        let targets = match self.source {
+            Source::SearchResults => Ok(search_hits.to_vec()),
+            Source::Path => json_path::select(root, &self.path),
+        };
+        match targets.and_then(|targets| Ok((targets, self.build_op()?))) {
+            Ok((targets, op)) => {
+                self.preview = Some(bulk::plan(root, &targets, &op));
+                self.preview_key = text_hash(text);
+                self.error = None;
+            }
+            Err(e) => {
+                self.preview = None;
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// 绘制面板内容；点击“应用”时返回生成的编辑操作
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        root: Option<&Value>,
+        text: &str,
+        search_hits: &[String],
+    ) -> Option<Vec<JsonEdit>> {
+        let mut changed = self.preview.is_some() && text_hash(text) != self.preview_key;
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("目标："));
+            changed |= ui
+                .radio_value(&mut self.source, Source::SearchResults, tr!("当前搜索结果（{} 个）", search_hits.len()))
+                .changed();
+            changed |= ui.radio_value(&mut self.source, Source::Path, tr!("路径表达式")).changed();
+        });
+        if self.source == Source::Path {
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut self.path)
+                        .hint_text(tr!("$.users[*].email、$..password 或 /items/0"))
+                        .desired_width(f32::INFINITY),
+                )
+                .changed();
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("操作："));
+            egui::ComboBox::from_id_salt("bulk_op")
+                .selected_text(self.op.label())
+                .show_ui(ui, |ui| {
+                    for op in OpKind::ALL {
+                        changed |= ui.selectable_value(&mut self.op, op, op.label()).changed();
+                    }
+                });
+            match self.op {
+                OpKind::RenameKey => {
+                    changed |= ui.add(egui::TextEdit::singleline(&mut self.new_key).hint_text(tr!("新键名"))).changed();
+                }
+                OpKind::Multiply => {
+                    changed |= ui.add(egui::TextEdit::singleline(&mut self.factor).desired_width(80.0)).changed();
+                }
+                _ => {}
+            }
+        });
+        if self.op == OpKind::SetValue {
+            let before = self.value_editor.build().ok();
+            self.value_editor.show(ui, "bulk_value");
+            changed |= self.value_editor.build().ok() != before;
+        }
+
+        if changed {
+            self.preview = None;
+            self.error = None;
+        }
+
+        let mut apply = false;
+        ui.horizontal(|ui| {
+            let preview = ui.add_enabled(root.is_some(), egui::Button::new(tr!("🔍 预览"))).clicked();
+            if let (true, Some(root)) = (preview, root) {
+                self.make_preview(root, text, search_hits);
+            }
+            let can_apply = self.preview.as_ref().is_some_and(|p| !p.changes.is_empty());
+            if ui.add_enabled(can_apply, egui::Button::new(tr!("✅ 应用"))).clicked() {
+                apply = true;
+            }
+        });
+
+        if let Some(err) = &self.error {
+            ui.colored_label(Palette::of(ui).error, err);
+        }
+
+        if let Some(plan) = &self.preview {
+            ui.separator();
+            ui.label(tr!("将修改 {} 个节点，跳过 {} 个不适用或无变化的节点", plan.changes.len(), plan.skipped));
+            show_preview(ui, plan);
+        }
+
+        if apply {
+            let plan = self.preview.take()?;
+            return Some(plan.changes.into_iter().map(|c| c.edit).collect());
+        }
+        None
+    }
+}
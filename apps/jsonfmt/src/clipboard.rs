@@ -0,0 +1,160 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::embedded::unwrap_container;
+
+/// 两次读取剪贴板的最小间隔
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 在文本中查找内嵌 JSON 时最多尝试的起始位置数，避免大段文本卡顿
+const MAX_EMBEDDED_ATTEMPTS: usize = 64;
+/// 超过此长度的文本只尝试整体解析，不再查找内嵌片段；每次尝试都可能解析到文本末尾，
+/// 在界面线程上对大段文本逐个尝试会明显卡顿
+const MAX_EMBEDDED_SCAN_BYTES: usize = 256 * 1024;
+
+/// 剪贴板监视器：窗口打开期间定期检查剪贴板中新复制的 JSON
+pub struct ClipboardWatcher {
+    clipboard: Option<arboard::Clipboard>,
+    last_hash: Option<u64>,
+    last_check: Instant,
+}
+
+impl ClipboardWatcher {
+    /// 创建监视器，当前剪贴板内容视为已处理
+    pub fn new() -> Self {
+        let mut watcher = Self {
+            clipboard: arboard::Clipboard::new().ok(),
+            last_hash: None,
+            last_check: Instant::now(),
+        };
+        watcher.last_hash = watcher.read_text().map(|text| hash_text(&text));
+        watcher
+    }
+
+    /// 检查剪贴板，若出现新的 JSON 内容则返回解析结果
+    pub fn poll(&mut self) -> Option<Value> {
+        let now = Instant::now();
+        if now.duration_since(self.last_check) < POLL_INTERVAL {
+            return None;
+        }
+        self.last_check = now;
+
+        let text = self.read_text()?;
+        let hash = hash_text(&text);
+        if self.last_hash == Some(hash) {
+            return None;
+        }
+        self.last_hash = Some(hash);
+        extract_json(&text)
+    }
+
+    fn read_text(&mut self) -> Option<String> {
+        self.clipboard.as_mut()?.get_text().ok()
+    }
+}
+
+impl Default for ClipboardWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 从任意文本中提取 JSON 对象或数组
+///
+/// 依次尝试：整段文本、整段为 JSON 字符串（其中包含转义的 JSON）、文本中内嵌的 JSON 片段。
+/// 文本超过 MAX_EMBEDDED_SCAN_BYTES 时不查找内嵌片段。
+pub fn extract_json(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    if let Ok(value) = serde_json::from_str::<Value>(trimmed) {
+        return unwrap_container(value);
+    }
+    if trimmed.len() > MAX_EMBEDDED_SCAN_BYTES {
+        return None;
+    }
+
+    // 查找内嵌片段：以 { [ 或 " 开头，能完整解析出一个值即可
+    let mut attempts = 0;
+    for (idx, ch) in trimmed.char_indices() {
+        if !matches!(ch, '{' | '[' | '"') {
+            continue;
+        }
+        attempts += 1;
+        if attempts > MAX_EMBEDDED_ATTEMPTS {
+            break;
+        }
+        let mut stream = serde_json::Deserializer::from_str(&trimmed[idx..]).into_iter::<Value>();
+        if let Some(Ok(value)) = stream.next()
+            && let Some(found) = unwrap_container(value)
+        {
+            return Some(found);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn whole_text() {
+        assert_eq!(extract_json("  {\"a\": [1, 2]}\n"), Some(json!({"a": [1, 2]})));
+        assert_eq!(extract_json("[]"), Some(json!([])));
+    }
+
+    #[test]
+    fn scalars_and_plain_text_are_ignored() {
+        assert_eq!(extract_json(""), None);
+        assert_eq!(extract_json("42"), None);
+        assert_eq!(extract_json("\"just a string\""), None);
+        assert_eq!(extract_json("hello world"), None);
+    }
+
+    #[test]
+    fn escaped_json_string() {
+        assert_eq!(extract_json(r#""{\"a\":1}""#), Some(json!({"a": 1})));
+        // 两层转义
+        assert_eq!(extract_json(r#""\"[1,2]\"""#), Some(json!([1, 2])));
+    }
+
+    #[test]
+    fn embedded_in_log_line() {
+        let line = r#"2024-01-01 INFO [main] request body={"user": {"id": 7}} took 3ms"#;
+        assert_eq!(extract_json(line), Some(json!({"user": {"id": 7}})));
+        // 第一个候选位置解析失败时继续向后找
+        let line = r#"level=warn msg="bad {" payload=[1, {"x": null}]"#;
+        assert_eq!(extract_json(line), Some(json!([1, {"x": null}])));
+    }
+
+    #[test]
+    fn attempts_are_limited() {
+        let noise = "{ ".repeat(MAX_EMBEDDED_ATTEMPTS);
+        assert_eq!(extract_json(&format!("{}{{\"a\": 1}}", noise)), None);
+        let noise = "{ ".repeat(MAX_EMBEDDED_ATTEMPTS - 1);
+        assert_eq!(extract_json(&format!("{}{{\"a\": 1}}", noise)), Some(json!({"a": 1})));
+    }
+
+    #[test]
+    fn large_text_is_not_scanned() {
+        let padding = "x".repeat(MAX_EMBEDDED_SCAN_BYTES);
+        assert_eq!(extract_json(&format!("{} {{\"a\": 1}}", padding)), None);
+        // 整段是 JSON 时不受长度限制
+        let big = json!({ "a": padding });
+        assert_eq!(extract_json(&big.to_string()), Some(big));
+    }
+}
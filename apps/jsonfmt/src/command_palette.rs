@@ -0,0 +1,210 @@
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use appkit::theme::Palette;
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+
+use crate::commands::{is_bindable, Command, Keymap};
+use crate::i18n::tr;
+
+/// 命令面板最多显示的高度
+const LIST_HEIGHT: f32 = 320.0;
+
+/// 命令面板（Ctrl+Shift+P）：模糊搜索并执行任意命令
+#[derive(Default)]
+pub struct CommandPalette {
+    query: String,
+    /// 当前选中的结果下标
+    selected: usize,
+}
+
+impl CommandPalette {
+    /// 显示命令面板；返回要执行的命令，关闭面板时把 open 置为 false。
+    /// enabled 中没有的命令显示为灰色且不能执行
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        keymap: &Keymap,
+        enabled: &HashSet<Command>,
+        open: &mut bool,
+    ) -> Option<Command> {
+        let mut matches: Vec<(Command, i32)> = Command::ALL
+            .into_iter()
+            .filter(|&c| c != Command::Palette)
+            .filter_map(|c| c.match_score(&self.query).map(|score| (c, score)))
+            .collect();
+        matches.sort_by_key(|&(_, score)| Reverse(score));
+
+        let (up, down, enter) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.key_pressed(Key::Enter),
+            )
+        });
+        if !matches.is_empty() {
+            if down {
+                self.selected = (self.selected + 1) % matches.len();
+            }
+            if up {
+                self.selected = (self.selected + matches.len() - 1) % matches.len();
+            }
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut chosen = None;
+        let modal = egui::Modal::new(egui::Id::new("command_palette")).show(ctx, |ui| {
+            ui.set_width(420.0);
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text(tr!("输入命令名称…"))
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if response.changed() {
+                self.selected = 0;
+            }
+
+            ui.separator();
+            if matches.is_empty() {
+                ui.weak(tr!("没有匹配的命令"));
+            }
+            egui::ScrollArea::vertical().max_height(LIST_HEIGHT).show(ui, |ui| {
+                for (idx, &(command, _)) in matches.iter().enumerate() {
+                    let is_enabled = enabled.contains(&command);
+                    let row = ui
+                        .add_enabled_ui(is_enabled, |ui| {
+                            ui.horizontal(|ui| {
+                                let label = ui.selectable_label(idx == self.selected, command.label());
+                                if let Some(shortcut) = keymap.shortcut(command) {
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        ui.weak(ui.ctx().format_shortcut(&shortcut));
+                                    });
+                                }
+                                label
+                            })
+                            .inner
+                        })
+                        .inner;
+                    if idx == self.selected && (up || down) {
+                        row.scroll_to_me(None);
+                    }
+                    if row.clicked() {
+                        chosen = Some(command);
+                    }
+                }
+            });
+        });
+
+        if enter {
+            chosen = matches
+                .get(self.selected)
+                .map(|&(command, _)| command)
+                .filter(|c| enabled.contains(c));
+        }
+        if chosen.is_some() || modal.should_close() {
+            *open = false;
+        }
+        chosen
+    }
+}
+
+/// 快捷键设置：查看、录制、清除和恢复每个命令的快捷键
+#[derive(Default)]
+pub struct KeymapEditor {
+    /// 正在等待按键的命令
+    recording: Option<Command>,
+    /// 录制时按下的不能作为快捷键的组合，用于提示
+    rejected: Option<KeyboardShortcut>,
+}
+
+impl KeymapEditor {
+    /// 正在录制快捷键时，应用不应响应快捷键
+    pub fn recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, keymap: &mut Keymap) {
+        if let Some(command) = self.recording {
+            // 按 Esc 取消录制，带修饰键的组合或功能键成为新的快捷键，其余按键只提示
+            let pressed = ui.input_mut(|i| {
+                let key = i.events.iter().find_map(|e| match e {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+                    _ => None,
+                });
+                if let Some((key, modifiers)) = key {
+                    i.consume_key(modifiers, key);
+                }
+                key
+            });
+            match pressed {
+                Some((Key::Escape, _)) => self.recording = None,
+                Some((key, modifiers)) => {
+                    let shortcut = KeyboardShortcut::new(modifiers, key);
+                    if is_bindable(&shortcut) {
+                        keymap.set(command, Some(shortcut));
+                        self.recording = None;
+                    } else {
+                        self.rejected = Some(shortcut);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if self.recording.is_none() {
+            self.rejected = None;
+        }
+        ui.label(tr!("点击快捷键后按下新的组合键，按 Esc 取消"));
+        if let Some(shortcut) = &self.rejected {
+            ui.colored_label(
+                Palette::of(ui).error,
+                tr!("{} 在输入文本时会被当作字符，请加上 Ctrl 或 Alt 等修饰键（F1–F12 除外）", ui.ctx().format_shortcut(shortcut)),
+            );
+        }
+        egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+            egui::Grid::new("keymap_grid").num_columns(4).striped(true).show(ui, |ui| {
+                for command in Command::ALL {
+                    ui.label(command.label());
+                    let text = if self.recording == Some(command) {
+                        tr!("请按下快捷键…").to_owned()
+                    } else {
+                        keymap
+                            .shortcut(command)
+                            .map_or_else(|| tr!("未设置").to_owned(), |s| ui.ctx().format_shortcut(&s))
+                    };
+                    if ui.selectable_label(self.recording == Some(command), text).clicked() {
+                        self.recording = Some(command);
+                    }
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(keymap.shortcut(command).is_some(), egui::Button::new(tr!("清除")))
+                            .clicked()
+                        {
+                            keymap.set(command, None);
+                        }
+                        if ui
+                            .add_enabled(!keymap.is_default(command), egui::Button::new(tr!("默认")))
+                            .clicked()
+                        {
+                            keymap.set(command, command.default_shortcut());
+                        }
+                    });
+                    let conflicts = keymap.conflicts(command);
+                    if conflicts.is_empty() {
+                        ui.label("");
+                    } else {
+                        let names: Vec<&str> = conflicts.iter().map(|c| c.label()).collect();
+                        ui.colored_label(Palette::of(ui).error, tr!("与“{}”冲突", names.join(tr!("、"))));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+        ui.separator();
+        if ui.button(tr!("全部恢复默认")).clicked() {
+            keymap.reset_all();
+            self.recording = None;
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+
+/// Ctrl（macOS 上为 Cmd）+ Shift
+const COMMAND_SHIFT: Modifiers = Modifiers {
+    shift: true,
+    ..Modifiers::COMMAND
+};
+
+/// 工具栏、快捷键和命令面板共用的操作
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Command {
+    Format,
+    Minify,
+    Clear,
+    Copy,
+    Open,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    Undo,
+    Redo,
+    FocusSearch,
+    Find,
+    Replace,
+    ExpandAll,
+    CollapseAll,
+    ExpandEmbedded,
+    BulkEdit,
+    Redact,
+    Stats,
+    Export,
+    Merge,
+    Script,
+    FollowFile,
+    WatchClipboard,
+    Appearance,
+    Keybindings,
+    Palette,
+}
+
+impl Command {
+    pub const ALL: [Command; 28] = [
+        Command::Format,
+        Command::Minify,
+        Command::Clear,
+        Command::Copy,
+        Command::Open,
+        Command::NewTab,
+        Command::CloseTab,
+        Command::NextTab,
+        Command::PrevTab,
+        Command::Undo,
+        Command::Redo,
+        Command::FocusSearch,
+        Command::Find,
+        Command::Replace,
+        Command::ExpandAll,
+        Command::CollapseAll,
+        Command::ExpandEmbedded,
+        Command::BulkEdit,
+        Command::Redact,
+        Command::Stats,
+        Command::Export,
+        Command::Merge,
+        Command::Script,
+        Command::FollowFile,
+        Command::WatchClipboard,
+        Command::Appearance,
+        Command::Keybindings,
+        Command::Palette,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::Format => tr!("格式化"),
+            Command::Minify => tr!("压缩"),
+            Command::Clear => tr!("清空"),
+            Command::Copy => tr!("复制原文"),
+            Command::Open => tr!("打开文件"),
+            Command::NewTab => tr!("新建标签页"),
+            Command::CloseTab => tr!("关闭标签页"),
+            Command::NextTab => tr!("下一个标签页"),
+            Command::PrevTab => tr!("上一个标签页"),
+            Command::Undo => tr!("撤销"),
+            Command::Redo => tr!("重做"),
+            Command::FocusSearch => tr!("搜索解析树"),
+            Command::Find => tr!("查找"),
+            Command::Replace => tr!("替换"),
+            Command::ExpandAll => tr!("全部展开"),
+            Command::CollapseAll => tr!("全部折叠"),
+            Command::ExpandEmbedded => tr!("展开内嵌 JSON"),
+            Command::BulkEdit => tr!("批量编辑"),
+            Command::Redact => tr!("脱敏"),
+            Command::Stats => tr!("统计"),
+            Command::Export => tr!("导出"),
+            Command::Merge => tr!("合并文档"),
+            Command::Script => tr!("脚本"),
+            Command::FollowFile => tr!("切换跟随文件"),
+            Command::WatchClipboard => tr!("切换监视剪贴板"),
+            Command::Appearance => tr!("外观设置"),
+            Command::Keybindings => tr!("快捷键设置"),
+            Command::Palette => tr!("命令面板"),
+        }
+    }
+
+    /// 默认快捷键
+    pub fn default_shortcut(self) -> Option<KeyboardShortcut> {
+        let (modifiers, key) = match self {
+            Command::Format => (COMMAND_SHIFT, Key::F),
+            Command::Minify => (COMMAND_SHIFT, Key::M),
+            Command::Copy => (COMMAND_SHIFT, Key::C),
+            Command::Open => (Modifiers::COMMAND, Key::O),
+            Command::NewTab => (Modifiers::COMMAND, Key::T),
+            Command::CloseTab => (Modifiers::COMMAND, Key::W),
+            Command::NextTab => (Modifiers::COMMAND, Key::PageDown),
+            Command::PrevTab => (Modifiers::COMMAND, Key::PageUp),
+            Command::Undo => (Modifiers::COMMAND, Key::Z),
+            Command::Redo => (Modifiers::COMMAND, Key::Y),
+            Command::FocusSearch => (Modifiers::COMMAND, Key::K),
+            Command::Find => (Modifiers::COMMAND, Key::F),
+            Command::Replace => (Modifiers::COMMAND, Key::H),
+            Command::ExpandAll => (COMMAND_SHIFT, Key::E),
+            Command::CollapseAll => (COMMAND_SHIFT, Key::W),
+            Command::Palette => (COMMAND_SHIFT, Key::P),
+            _ => return None,
+        };
+        Some(KeyboardShortcut::new(modifiers, key))
+    }
+
+    /// 文本框有焦点时交给文本框自己处理的命令（文本框有独立的撤销记录）
+    fn yields_to_text(self) -> bool {
+        matches!(self, Command::Undo | Command::Redo)
+    }
+
+    /// 命令面板中的匹配分数：中文名称和英文标识都参与匹配
+    pub fn match_score(self, query: &str) -> Option<i32> {
+        let id = format!("{self:?}");
+        fuzzy_score(query, self.label()).max(fuzzy_score(query, &id))
+    }
+}
+
+/// 快捷键绑定；只保存用户改动过的命令，其余使用默认快捷键
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    /// 值为 None 表示用户取消了该命令的快捷键
+    overrides: HashMap<Command, Option<KeyboardShortcut>>,
+}
+
+impl Keymap {
+    pub fn shortcut(&self, command: Command) -> Option<KeyboardShortcut> {
+        match self.overrides.get(&command) {
+            Some(shortcut) => *shortcut,
+            None => command.default_shortcut(),
+        }
+    }
+
+    pub fn set(&mut self, command: Command, shortcut: Option<KeyboardShortcut>) {
+        if shortcut == command.default_shortcut() {
+            self.overrides.remove(&command);
+        } else {
+            self.overrides.insert(command, shortcut);
+        }
+    }
+
+    pub fn is_default(&self, command: Command) -> bool {
+        !self.overrides.contains_key(&command)
+    }
+
+    pub fn reset_all(&mut self) {
+        self.overrides.clear();
+    }
+
+    /// 与该命令使用相同快捷键的其他命令
+    pub fn conflicts(&self, command: Command) -> Vec<Command> {
+        let Some(shortcut) = self.shortcut(command) else {
+            return Vec::new();
+        };
+        Command::ALL
+            .into_iter()
+            .filter(|&c| c != command && self.shortcut(c) == Some(shortcut))
+            .collect()
+    }
+
+    /// 取出本帧按下的快捷键对应的命令。修饰键多的绑定先匹配，
+    /// 避免 Ctrl+F 抢先消耗 Ctrl+Shift+F；text_focused 时跳过交给文本框处理的命令，
+    /// 以及会吞掉输入字符的快捷键（旧版本可能保存过不带修饰键的绑定）
+    pub fn consume(&self, ctx: &egui::Context, text_focused: bool) -> Option<Command> {
+        let mut bindings: Vec<(Command, KeyboardShortcut)> = Command::ALL
+            .into_iter()
+            .filter(|c| !(text_focused && c.yields_to_text()))
+            .filter_map(|c| self.shortcut(c).map(|s| (c, s)))
+            .filter(|(_, s)| !text_focused || is_bindable(s))
+            .collect();
+        bindings.sort_by_key(|(_, s)| Reverse(modifier_count(s.modifiers)));
+        ctx.input_mut(|i| {
+            bindings
+                .iter()
+                .find(|(_, shortcut)| i.consume_shortcut(shortcut))
+                .map(|&(command, _)| command)
+        })
+    }
+}
+
+/// 能否作为快捷键：不带修饰键或只带 Shift 的普通按键在文本框中会输入字符，
+/// 必须加上 Ctrl、Alt 等修饰键；F1–F12 不会输入字符，可以单独使用
+pub fn is_bindable(shortcut: &KeyboardShortcut) -> bool {
+    let modifiers = shortcut.modifiers;
+    let function_key = matches!(
+        shortcut.logical_key,
+        Key::F1 | Key::F2 | Key::F3 | Key::F4 | Key::F5 | Key::F6 | Key::F7 | Key::F8 | Key::F9 | Key::F10 | Key::F11 | Key::F12
+    );
+    modifiers.alt || modifiers.ctrl || modifiers.command || modifiers.mac_cmd || function_key
+}
+
+fn modifier_count(modifiers: Modifiers) -> usize {
+    [modifiers.alt, modifiers.shift, modifiers.command || modifiers.ctrl || modifiers.mac_cmd]
+        .into_iter()
+        .filter(|&m| m)
+        .count()
+}
+
+/// 模糊匹配：查询中的字符按顺序出现在文本中即算匹配（不区分大小写，忽略空白）。
+/// 返回分数，越大越好：连续命中和在词首命中加分，跳过的字符扣分
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last: Option<usize> = None;
+    for q in query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase) {
+        let found = (pos..text.len()).find(|&i| text[i].to_lowercase().eq(std::iter::once(q)))?;
+        score += 1;
+        if found > 0 && last == Some(found - 1) {
+            score += 5;
+        }
+        let word_start = found == 0
+            || !text[found - 1].is_alphanumeric()
+            || (text[found].is_uppercase() && text[found - 1].is_lowercase());
+        if word_start {
+            score += 3;
+        }
+        score -= (found - pos) as i32;
+        pos = found + 1;
+        last = Some(found);
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use eframe::egui::{Event, RawInput};
+
+    use super::*;
+
+    fn shortcut(modifiers: Modifiers, key: Key) -> KeyboardShortcut {
+        KeyboardShortcut::new(modifiers, key)
+    }
+
+    /// 按下一次快捷键后 consume 取出的命令
+    fn press(keymap: &Keymap, pressed: KeyboardShortcut, text_focused: bool) -> Option<Command> {
+        let ctx = egui::Context::default();
+        let input = RawInput {
+            modifiers: pressed.modifiers,
+            events: vec![Event::Key {
+                key: pressed.logical_key,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: pressed.modifiers,
+            }],
+            ..Default::default()
+        };
+        let mut command = None;
+        let _ = ctx.run(input, |ctx| command = keymap.consume(ctx, text_focused));
+        command
+    }
+
+    #[test]
+    fn commands_and_labels_are_unique() {
+        let commands: HashSet<Command> = Command::ALL.into_iter().collect();
+        assert_eq!(commands.len(), Command::ALL.len());
+        let labels: HashSet<&str> = Command::ALL.into_iter().map(Command::label).collect();
+        assert_eq!(labels.len(), Command::ALL.len());
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_contiguous_and_word_starts() {
+        assert_eq!(fuzzy_score("", "Format"), Some(0));
+        // f 在词首 +4；m 跳过 2 个字符 -1；t 跳过 1 个字符 0
+        assert_eq!(fuzzy_score("fmt", "Format"), Some(3));
+        // f 在词首 +4；o、r 连续命中各 +6
+        assert_eq!(fuzzy_score("for", "Format"), Some(16));
+        assert_eq!(fuzzy_score("F O", "format"), fuzzy_score("fo", "Format"));
+        assert_eq!(fuzzy_score("tf", "Format"), None);
+        assert!(fuzzy_score("ea", "ExpandAll") > fuzzy_score("ea", "Replace"));
+        assert!(fuzzy_score("展开", "全部展开") > fuzzy_score("展开", "展示内嵌开关"));
+        assert_eq!(Command::Format.match_score("format"), fuzzy_score("format", "Format"));
+    }
+
+    #[test]
+    fn bindable_shortcuts_need_a_modifier_or_function_key() {
+        assert!(!is_bindable(&shortcut(Modifiers::NONE, Key::A)));
+        assert!(!is_bindable(&shortcut(Modifiers::SHIFT, Key::A)));
+        assert!(is_bindable(&shortcut(Modifiers::COMMAND, Key::A)));
+        assert!(is_bindable(&shortcut(Modifiers::ALT, Key::A)));
+        assert!(is_bindable(&shortcut(Modifiers::NONE, Key::F5)));
+        assert!(is_bindable(&shortcut(Modifiers::SHIFT, Key::F12)));
+    }
+
+    #[test]
+    fn set_keeps_only_overrides_and_reports_conflicts() {
+        let mut keymap = Keymap::default();
+        let find = shortcut(Modifiers::COMMAND, Key::F);
+        assert!(keymap.conflicts(Command::Find).is_empty());
+
+        keymap.set(Command::Merge, Some(find));
+        assert!(!keymap.is_default(Command::Merge));
+        assert_eq!(keymap.shortcut(Command::Merge), Some(find));
+        assert_eq!(keymap.conflicts(Command::Find), vec![Command::Merge]);
+        assert_eq!(keymap.conflicts(Command::Merge), vec![Command::Find]);
+
+        // 取消快捷键也是一种改动；设回默认值则不再保存
+        keymap.set(Command::Find, None);
+        assert!(!keymap.is_default(Command::Find));
+        assert_eq!(keymap.shortcut(Command::Find), None);
+        assert!(keymap.conflicts(Command::Find).is_empty());
+        keymap.set(Command::Find, Command::Find.default_shortcut());
+        assert!(keymap.is_default(Command::Find));
+
+        keymap.reset_all();
+        assert!(Command::ALL.into_iter().all(|c| keymap.is_default(c)));
+        assert!(Command::ALL.into_iter().all(|c| keymap.conflicts(c).is_empty()));
+    }
+
+    #[test]
+    fn shortcuts_with_more_modifiers_match_first() {
+        let keymap = Keymap::default();
+        assert_eq!(press(&keymap, shortcut(COMMAND_SHIFT, Key::F), false), Some(Command::Format));
+        assert_eq!(press(&keymap, shortcut(Modifiers::COMMAND, Key::F), false), Some(Command::Find));
+        assert_eq!(press(&keymap, shortcut(COMMAND_SHIFT, Key::W), false), Some(Command::CollapseAll));
+        assert_eq!(press(&keymap, shortcut(Modifiers::COMMAND, Key::W), false), Some(Command::CloseTab));
+    }
+
+    #[test]
+    fn text_focus_skips_undo_and_unbindable_shortcuts() {
+        let mut keymap = Keymap::default();
+        let undo = shortcut(Modifiers::COMMAND, Key::Z);
+        assert_eq!(press(&keymap, undo, false), Some(Command::Undo));
+        assert_eq!(press(&keymap, undo, true), None);
+
+        let plain = shortcut(Modifiers::NONE, Key::S);
+        keymap.set(Command::Stats, Some(plain));
+        assert_eq!(press(&keymap, plain, false), Some(Command::Stats));
+        assert_eq!(press(&keymap, plain, true), None);
+    }
+}
@@ -5,139 +5,448 @@ use egui_json_tree::{
 };
 
 use crate::edit::{EditDialog, JsonEdit};
+use crate::embedded::parse_embedded;
+use crate::export_panel::ExportPanel;
+use crate::i18n::tr;
+use crate::json_path::{self, PathSyntax};
+use crate::snippet::{self, SnippetFormat};
+use crate::table_view::TableView;
+use crate::tree_ops::{split_pointer, CutNode, DragNode, Placement};
+use crate::value_editor::ValueEditor;
+
+/// 树节点交互时可修改的状态
+pub struct TreeActions<'a> {
+    pub pending_edits: &'a mut Vec<JsonEdit>,
+    pub edit_dialog: &'a mut Option<EditDialog>,
+    /// 剪切的节点，各标签页共享，可粘贴到其他文档
+    pub cut_buffer: &'a mut Option<CutNode>,
+    /// 以表格查看的数组
+    pub table_view: &'a mut Option<TableView>,
+    /// 需要递归展开的子树
+    pub expand_subtree: &'a mut Option<String>,
+    /// 导出面板，打开时为 Some
+    pub export_panel: &'a mut Option<ExportPanel>,
+    /// 整个文档，用于换算路径写法
+    pub root: &'a serde_json::Value,
+}
+
+/// 展开对象或数组节点
+pub fn expand(context: &mut RenderContext<serde_json::Value>) {
+    set_expanded(context, true);
+}
+
+/// 对象或数组节点当前是否展开，其他节点返回 None
+pub fn is_expanded(context: &RenderContext<serde_json::Value>) -> Option<bool> {
+    match context {
+        RenderContext::Property(ctx) => ctx.collapsing_state.as_ref().map(|state| state.is_open()),
+        RenderContext::ExpandableDelimiter(ctx) => Some(ctx.collapsing_state.is_open()),
+        RenderContext::BaseValue(_) => None,
+    }
+}
+
+/// 展开或折叠对象或数组节点
+pub fn set_expanded(context: &mut RenderContext<serde_json::Value>, open: bool) {
+    match context {
+        RenderContext::Property(ctx) => {
+            if let Some(state) = ctx.collapsing_state.as_mut() {
+                state.set_open(open);
+            }
+        }
+        RenderContext::ExpandableDelimiter(ctx) => ctx.collapsing_state.set_open(open),
+        RenderContext::BaseValue(_) => {}
+    }
+}
+
+/// 若节点是目标节点的祖先，则将其展开
+pub fn reveal_ancestor(context: &mut RenderContext<serde_json::Value>, pointer: &str, target: &str) {
+    let is_ancestor = target.len() > pointer.len()
+        && target.starts_with(pointer)
+        && target.as_bytes()[pointer.len()] == b'/';
+    if is_ancestor {
+        expand(context);
+    }
+}
 
 /// 显示右键菜单
 pub fn show_context_menu(
     ui: &mut egui::Ui,
     context: RenderContext<serde_json::Value>,
     pointer: String,
-    pending_edits: &mut Vec<JsonEdit>,
-    edit_dialog: &mut Option<EditDialog>,
-) {
-    context
+    actions: &mut TreeActions,
+) -> egui::Response {
+    let pending_edits = &mut *actions.pending_edits;
+    let edit_dialog = &mut *actions.edit_dialog;
+    let cut_buffer = &mut *actions.cut_buffer;
+    let table_view = &mut *actions.table_view;
+    let expand_subtree = &mut *actions.expand_subtree;
+    let export_panel = &mut *actions.export_panel;
+    let root = actions.root;
+    let is_child_property = matches!(&context, RenderContext::Property(ctx) if ctx.pointer.parent().is_some());
+    let is_delimiter = matches!(&context, RenderContext::ExpandableDelimiter(_));
+
+    let response = context
         .render_default(ui)
-        .on_hover_cursor(egui::CursorIcon::ContextMenu)
-        .context_menu(|ui| {
-            // 复制功能
-            if !pointer.is_empty() && ui.button("📋 复制路径").clicked() {
-                ui.ctx().copy_text(pointer.clone());
-                ui.close();
+        .on_hover_cursor(egui::CursorIcon::ContextMenu);
+    response.context_menu(|ui| {
+        // 复制功能
+        if !pointer.is_empty() {
+            ui.menu_button(tr!("📋 复制路径"), |ui| {
+                for syntax in PathSyntax::ALL {
+                    let path = json_path::format_path(root, &pointer, syntax);
+                    if ui.button(syntax.label()).on_hover_text(&path).clicked() {
+                        ui.ctx().copy_text(path);
+                        ui.close();
+                    }
+                }
+            });
+        }
+
+        if ui.button(tr!("📄 复制内容")).clicked() {
+            if let Ok(pretty_str) = serde_json::to_string_pretty(context.value()) {
+                ui.ctx().copy_text(pretty_str);
             }
+            ui.close();
+        }
 
-            if ui.button("📄 复制内容").clicked() {
-                if let Ok(pretty_str) = serde_json::to_string_pretty(context.value()) {
-                    ui.ctx().copy_text(pretty_str);
+        ui.menu_button(tr!("📋 复制为"), |ui| {
+            for format in SnippetFormat::ALL {
+                let button = ui.button(format.label()).on_hover_ui(|ui| {
+                    ui.monospace(snippet::preview(&snippet::render(context.value(), format)));
+                });
+                if button.clicked() {
+                    ui.ctx().copy_text(snippet::render(context.value(), format));
+                    ui.close();
                 }
-                ui.close();
             }
+        });
 
-            // 添加功能
-            match context {
-                RenderContext::Property(mut ctx) => {
-                    let has_edit_options = ctx.value.is_object() || ctx.value.is_array() || ctx.pointer.parent().is_some();
-                    if has_edit_options {
-                        ui.separator();
-                    }
+        let is_container = context.value().is_object() || context.value().is_array();
+        if is_container && ui.button(tr!("🌲 展开整个子树")).clicked() {
+            *expand_subtree = Some(pointer.clone());
+            ui.close();
+        }
 
-                    if ctx.value.is_object() && ui.button("➕ 添加到对象").clicked() {
-                        pending_edits.push(JsonEdit::AddToObject {
-                            pointer: pointer.clone(),
-                        });
-                        if let Some(ref mut state) = ctx.collapsing_state {
-                            state.set_open(true);
-                        }
-                        ui.close();
-                    }
+        if ui.button(tr!("📤 导出…")).clicked() {
+            *export_panel = Some(ExportPanel::new(pointer.clone()));
+            ui.close();
+        }
 
-                    if ctx.value.is_array() && ui.button("➕ 添加到数组").clicked() {
-                        pending_edits.push(JsonEdit::AddToArray {
-                            pointer: pointer.clone(),
-                        });
-                        if let Some(ref mut state) = ctx.collapsing_state {
-                            state.set_open(true);
-                        }
-                        ui.close();
+        // 添加功能
+        match context {
+            RenderContext::Property(mut ctx) => {
+                let has_edit_options = ctx.value.is_object() || ctx.value.is_array() || ctx.pointer.parent().is_some();
+                if has_edit_options {
+                    ui.separator();
+                }
+
+                if ctx.value.is_object() && ui.button(tr!("➕ 添加到对象")).clicked() {
+                    pending_edits.push(JsonEdit::AddToObject {
+                        pointer: pointer.clone(),
+                    });
+                    if let Some(ref mut state) = ctx.collapsing_state {
+                        state.set_open(true);
                     }
+                    ui.close();
+                }
 
-                    // 编辑键功能
-                    if let (Some(parent), JsonPointerSegment::Key(key)) = (ctx.pointer.parent(), ctx.property) {
-                        if ui.button("✏ 编辑键").clicked() {
-                            *edit_dialog = Some(EditDialog::EditKey {
-                                object_pointer: parent.to_json_pointer_string(),
-                                old_key: key.to_string(),
-                                input: key.to_string(),
-                            });
-                            ui.close();
-                        }
+                if ctx.value.is_array() && ui.button(tr!("➕ 添加到数组")).clicked() {
+                    pending_edits.push(JsonEdit::AddToArray {
+                        pointer: pointer.clone(),
+                    });
+                    if let Some(ref mut state) = ctx.collapsing_state {
+                        state.set_open(true);
                     }
+                    ui.close();
+                }
+
+                if ctx.value.is_array() && ui.button(tr!("▦ 以表格查看")).clicked() {
+                    *table_view = Some(TableView::new(pointer.clone()));
+                    ui.close();
+                }
 
-                    // 删除功能
-                    if let Some(parent) = ctx.pointer.parent() {
-                        if ui.button("🗑 删除").clicked() {
-                            let edit = match ctx.property {
-                                JsonPointerSegment::Key(key) => JsonEdit::DeleteFromObject {
-                                    object_pointer: parent.to_json_pointer_string(),
-                                    key: key.to_string(),
-                                },
-                                JsonPointerSegment::Index(idx) => JsonEdit::DeleteFromArray {
-                                    array_pointer: parent.to_json_pointer_string(),
-                                    idx,
-                                },
-                            };
-                            pending_edits.push(edit);
-                            ui.close();
-                        }
+                // 编辑键功能
+                if let (Some(parent), JsonPointerSegment::Key(key)) = (ctx.pointer.parent(), ctx.property)
+                    && ui.button(tr!("✏ 编辑键")).clicked()
+                {
+                    *edit_dialog = Some(EditDialog::EditKey {
+                        object_pointer: parent.to_json_pointer_string(),
+                        old_key: key.to_string(),
+                        input: key.to_string(),
+                    });
+                    ui.close();
+                }
+
+                // 转为字符串功能
+                if (ctx.value.is_object() || ctx.value.is_array())
+                    && ctx.pointer.parent().is_some()
+                    && ui.button(tr!("🧵 转为字符串")).clicked()
+                {
+                    pending_edits.push(JsonEdit::Stringify {
+                        pointer: pointer.clone(),
+                    });
+                    ui.close();
+                }
+
+                // 粘贴功能
+                if (ctx.value.is_object() || ctx.value.is_array())
+                    && cut_buffer.is_some()
+                    && ui.button(tr!("📥 粘贴到末尾")).clicked()
+                {
+                    paste(pending_edits, cut_buffer, Placement::Append(pointer.clone()));
+                    if let Some(ref mut state) = ctx.collapsing_state {
+                        state.set_open(true);
                     }
+                    ui.close();
                 }
-                RenderContext::BaseValue(ctx) => {
+
+                if ctx.pointer.parent().is_some() {
+                    let key = match ctx.property {
+                        JsonPointerSegment::Key(key) => Some(key.to_string()),
+                        JsonPointerSegment::Index(_) => None,
+                    };
                     ui.separator();
-                    
-                    // 编辑值功能
-                    if ui.button("✏ 编辑值").clicked() {
-                        *edit_dialog = Some(EditDialog::EditValue {
-                            pointer: pointer.clone(),
-                            input: ctx.value.to_string(),
-                        });
-                        ui.close();
-                    }
+                    show_node_actions(ui, &pointer, key, ctx.value, pending_edits, cut_buffer);
+                }
 
-                    // 基础值的删除功能
-                    if let (Some(parent), Some(segment)) = (ctx.pointer.parent(), ctx.pointer.last()) {
-                        if ui.button("🗑 删除").clicked() {
-                            let edit = match segment {
-                                JsonPointerSegment::Key(key) => JsonEdit::DeleteFromObject {
-                                    object_pointer: parent.to_json_pointer_string(),
-                                    key: key.to_string(),
-                                },
-                                JsonPointerSegment::Index(idx) => JsonEdit::DeleteFromArray {
-                                    array_pointer: parent.to_json_pointer_string(),
-                                    idx: *idx,
-                                },
-                            };
-                            pending_edits.push(edit);
-                            ui.close();
-                        }
-                    }
+                // 删除功能
+                if let Some(parent) = ctx.pointer.parent()
+                    && ui.button(tr!("🗑 删除")).clicked()
+                {
+                    let edit = match ctx.property {
+                        JsonPointerSegment::Key(key) => JsonEdit::DeleteFromObject {
+                            object_pointer: parent.to_json_pointer_string(),
+                            key: key.to_string(),
+                        },
+                        JsonPointerSegment::Index(idx) => JsonEdit::DeleteFromArray {
+                            array_pointer: parent.to_json_pointer_string(),
+                            idx,
+                        },
+                    };
+                    pending_edits.push(edit);
+                    ui.close();
                 }
-                RenderContext::ExpandableDelimiter(ctx) => {
-                    // 在分隔符上也可以添加
+            }
+            RenderContext::BaseValue(ctx) => {
+                ui.separator();
+                
+                // 编辑值功能
+                if ui.button(tr!("✏ 编辑值")).clicked() {
+                    *edit_dialog = Some(EditDialog::EditValue {
+                        pointer: pointer.clone(),
+                        editor: ValueEditor::from_value(ctx.value),
+                    });
+                    ui.close();
+                }
+
+                // 展开内嵌 JSON 功能
+                let is_embedded = ctx.value.as_str().is_some_and(|s| parse_embedded(s).is_some());
+                if is_embedded && ui.button(tr!("🧩 展开内嵌 JSON")).clicked() {
+                    pending_edits.push(JsonEdit::ExpandEmbedded {
+                        pointer: pointer.clone(),
+                    });
+                    ui.close();
+                }
+
+                if let Some(segment) = ctx.pointer.last() {
+                    let key = match segment {
+                        JsonPointerSegment::Key(key) => Some(key.to_string()),
+                        JsonPointerSegment::Index(_) => None,
+                    };
                     ui.separator();
-                    if ctx.value.is_object() && ui.button("➕ 添加到对象").clicked() {
-                        pending_edits.push(JsonEdit::AddToObject {
-                            pointer: pointer.clone(),
-                        });
-                        ctx.collapsing_state.set_open(true);
-                        ui.close();
-                    }
+                    show_node_actions(ui, &pointer, key, ctx.value, pending_edits, cut_buffer);
+                }
 
-                    if ctx.value.is_array() && ui.button("➕ 添加到数组").clicked() {
-                        pending_edits.push(JsonEdit::AddToArray {
-                            pointer: pointer.clone(),
-                        });
-                        ctx.collapsing_state.set_open(true);
-                        ui.close();
-                    }
+                // 基础值的删除功能
+                if let (Some(parent), Some(segment)) = (ctx.pointer.parent(), ctx.pointer.last())
+                    && ui.button(tr!("🗑 删除")).clicked()
+                {
+                    let edit = match segment {
+                        JsonPointerSegment::Key(key) => JsonEdit::DeleteFromObject {
+                            object_pointer: parent.to_json_pointer_string(),
+                            key: key.to_string(),
+                        },
+                        JsonPointerSegment::Index(idx) => JsonEdit::DeleteFromArray {
+                            array_pointer: parent.to_json_pointer_string(),
+                            idx: *idx,
+                        },
+                    };
+                    pending_edits.push(edit);
+                    ui.close();
                 }
             }
+            RenderContext::ExpandableDelimiter(ctx) => {
+                // 在分隔符上也可以添加
+                ui.separator();
+                if ctx.value.is_object() && ui.button(tr!("➕ 添加到对象")).clicked() {
+                    pending_edits.push(JsonEdit::AddToObject {
+                        pointer: pointer.clone(),
+                    });
+                    ctx.collapsing_state.set_open(true);
+                    ui.close();
+                }
+
+                if ctx.value.is_array() && ui.button(tr!("➕ 添加到数组")).clicked() {
+                    pending_edits.push(JsonEdit::AddToArray {
+                        pointer: pointer.clone(),
+                    });
+                    ctx.collapsing_state.set_open(true);
+                    ui.close();
+                }
+
+                if ctx.value.is_array() && ui.button(tr!("▦ 以表格查看")).clicked() {
+                    *table_view = Some(TableView::new(pointer.clone()));
+                    ui.close();
+                }
+
+                if cut_buffer.is_some() && ui.button(tr!("📥 粘贴到末尾")).clicked() {
+                    paste(pending_edits, cut_buffer, Placement::Append(pointer.clone()));
+                    ctx.collapsing_state.set_open(true);
+                    ui.close();
+                }
+            }
+        }
+    });
+
+    // 拖放：拖动键名，放到其他键名上时插入到其前/后，放到括号上时追加到该容器末尾
+    if is_child_property {
+        let dnd = ui.interact(response.rect, response.id.with("dnd"), egui::Sense::drag());
+        dnd.dnd_set_drag_payload(DragNode { pointer: pointer.clone() });
+        if dnd.dnd_hover_payload::<DragNode>().is_some() {
+            let after = drop_after(ui, dnd.rect);
+            let y = if after { dnd.rect.bottom() } else { dnd.rect.top() };
+            ui.painter().hline(dnd.rect.x_range(), y, ui.visuals().selection.stroke);
+        }
+        if let Some(payload) = dnd.dnd_release_payload::<DragNode>() {
+            let to = if drop_after(ui, dnd.rect) {
+                Placement::After(pointer.clone())
+            } else {
+                Placement::Before(pointer.clone())
+            };
+            if payload.pointer != pointer {
+                pending_edits.push(JsonEdit::MoveNode { from: payload.pointer.clone(), to });
+            }
+        }
+    } else if is_delimiter {
+        if response.dnd_hover_payload::<DragNode>().is_some() {
+            ui.painter().rect_stroke(
+                response.rect,
+                2.0,
+                ui.visuals().selection.stroke,
+                egui::StrokeKind::Outside,
+            );
+        }
+        if let Some(payload) = response.dnd_release_payload::<DragNode>() {
+            pending_edits.push(JsonEdit::MoveNode {
+                from: payload.pointer.clone(),
+                to: Placement::Append(pointer.clone()),
+            });
+        }
+    }
+
+    response
+}
+
+/// 鼠标位于目标下半部分时插入到其后面
+fn drop_after(ui: &egui::Ui, rect: egui::Rect) -> bool {
+    ui.input(|i| i.pointer.interact_pos())
+        .is_some_and(|pos| pos.y > rect.center().y)
+}
+
+/// 有父节点的节点通用操作：插入、复制、移动、剪切、粘贴
+fn show_node_actions(
+    ui: &mut egui::Ui,
+    pointer: &str,
+    key: Option<String>,
+    value: &serde_json::Value,
+    pending_edits: &mut Vec<JsonEdit>,
+    cut_buffer: &mut Option<CutNode>,
+) {
+    ui.menu_button(tr!("➕ 插入"), |ui| {
+        if ui.button(tr!("在前面插入")).clicked() {
+            pending_edits.push(JsonEdit::InsertValue {
+                at: Placement::Before(pointer.to_string()),
+                key: None,
+                value: serde_json::Value::Null,
+            });
+            ui.close();
+        }
+        if ui.button(tr!("在后面插入")).clicked() {
+            pending_edits.push(JsonEdit::InsertValue {
+                at: Placement::After(pointer.to_string()),
+                key: None,
+                value: serde_json::Value::Null,
+            });
+            ui.close();
+        }
+    });
+
+    if ui.button(tr!("⧉ 复制节点")).clicked() {
+        pending_edits.push(JsonEdit::Duplicate {
+            pointer: pointer.to_string(),
         });
+        ui.close();
+    }
+
+    ui.horizontal(|ui| {
+        if ui.button(tr!("⬆ 上移")).clicked() {
+            pending_edits.push(JsonEdit::MoveUp {
+                pointer: pointer.to_string(),
+            });
+            ui.close();
+        }
+        if ui.button(tr!("⬇ 下移")).clicked() {
+            pending_edits.push(JsonEdit::MoveDown {
+                pointer: pointer.to_string(),
+            });
+            ui.close();
+        }
+    });
+
+    if ui.button(tr!("✂ 剪切")).clicked() {
+        if let Some(edit) = delete_edit(pointer, key.as_deref()) {
+            pending_edits.push(edit);
+        }
+        *cut_buffer = Some(CutNode {
+            key,
+            value: value.clone(),
+        });
+        ui.close();
+    }
+
+    if cut_buffer.is_some() {
+        if ui.button(tr!("📥 粘贴到前面")).clicked() {
+            paste(pending_edits, cut_buffer, Placement::Before(pointer.to_string()));
+            ui.close();
+        }
+        if ui.button(tr!("📥 粘贴到后面")).clicked() {
+            paste(pending_edits, cut_buffer, Placement::After(pointer.to_string()));
+            ui.close();
+        }
+    }
+}
+
+/// 把剪切的节点粘贴到指定位置，可重复粘贴
+fn paste(pending_edits: &mut Vec<JsonEdit>, cut_buffer: &Option<CutNode>, at: Placement) {
+    if let Some(node) = cut_buffer {
+        pending_edits.push(JsonEdit::InsertValue {
+            at,
+            key: node.key.clone(),
+            value: node.value.clone(),
+        });
+    }
+}
+
+/// 根据路径和键名生成删除操作
+fn delete_edit(pointer: &str, key: Option<&str>) -> Option<JsonEdit> {
+    let (parent, segment) = split_pointer(pointer)?;
+    Some(match key {
+        Some(key) => JsonEdit::DeleteFromObject {
+            object_pointer: parent.to_string(),
+            key: key.to_string(),
+        },
+        None => JsonEdit::DeleteFromArray {
+            array_pointer: parent.to_string(),
+            idx: segment.parse().ok()?,
+        },
+    })
 }
@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+/// 比较两个 JSON 值，返回新值中发生变化（修改或新增）的节点路径
+pub fn changed_pointers(old: &Value, new: &Value) -> HashSet<String> {
+    let mut changed = HashSet::new();
+    collect_changes(old, new, &mut String::new(), &mut changed);
+    changed
+}
+
+fn collect_changes(old: &Value, new: &Value, pointer: &mut String, changed: &mut HashSet<String>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, new_child) in new_map {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&escape_pointer_segment(key));
+                match old_map.get(key) {
+                    Some(old_child) => collect_changes(old_child, new_child, pointer, changed),
+                    None => {
+                        changed.insert(pointer.clone());
+                    }
+                }
+                pointer.truncate(len);
+            }
+        }
+        (Value::Array(old_arr), Value::Array(new_arr)) => {
+            for (idx, new_child) in new_arr.iter().enumerate() {
+                let len = pointer.len();
+                pointer.push('/');
+                pointer.push_str(&idx.to_string());
+                match old_arr.get(idx) {
+                    Some(old_child) => collect_changes(old_child, new_child, pointer, changed),
+                    None => {
+                        changed.insert(pointer.clone());
+                    }
+                }
+                pointer.truncate(len);
+            }
+        }
+        _ => {
+            if old != new {
+                changed.insert(pointer.clone());
+            }
+        }
+    }
+}
+
+/// 按 RFC 6901 转义路径片段
+pub fn escape_pointer_segment(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sorted(set: HashSet<String>) -> Vec<String> {
+        let mut pointers: Vec<String> = set.into_iter().collect();
+        pointers.sort();
+        pointers
+    }
+
+    #[test]
+    fn reports_modified_and_added_leaves() {
+        let old = json!({"a": 1, "b": {"c": "x", "d": true}, "list": [1, 2]});
+        let new = json!({"a": 1, "b": {"c": "y", "d": true, "e": null}, "list": [1, 3, 4]});
+        assert_eq!(
+            sorted(changed_pointers(&old, &new)),
+            ["/b/c", "/b/e", "/list/1", "/list/2"]
+        );
+    }
+
+    #[test]
+    fn removed_nodes_are_not_reported() {
+        let old = json!({"a": 1, "b": [1, 2, 3]});
+        let new = json!({"b": [1, 2]});
+        assert!(changed_pointers(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn type_change_marks_the_whole_node() {
+        let old = json!({"a": {"x": 1}});
+        let new = json!({"a": [1]});
+        assert_eq!(sorted(changed_pointers(&old, &new)), ["/a"]);
+        assert_eq!(sorted(changed_pointers(&json!([1]), &json!({"0": 1}))), [""]);
+    }
+
+    #[test]
+    fn keys_are_escaped() {
+        let old = json!({"a/b": 1, "m~n": 1});
+        let new = json!({"a/b": 2, "m~n": 2});
+        assert_eq!(sorted(changed_pointers(&old, &new)), ["/a~1b", "/m~0n"]);
+        assert_eq!(escape_pointer_segment("~/"), "~0~1");
+    }
+}
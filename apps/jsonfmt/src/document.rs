@@ -0,0 +1,341 @@
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use crate::bulk_panel::BulkPanel;
+use crate::diff::changed_pointers;
+use crate::edit::{apply_edit, EditDialog, EditHistory, JsonEdit};
+use crate::expand_bar::ExpandBar;
+use crate::expansion::ExpansionState;
+use crate::export_panel::ExportPanel;
+use crate::editor::Highlighter;
+use crate::find_bar::FindBar;
+use crate::i18n::tr;
+use crate::patch::patch_text;
+use crate::path_bar::PathBar;
+use crate::preview_ui::ValuePreviews;
+use crate::search_bar::SearchBar;
+use crate::span::{char_to_byte, SpanMap};
+use crate::stats_panel::StatsPanel;
+use crate::table_view::TableView;
+use crate::watch::FileWatcher;
+
+/// 展开树节点时最多尝试的帧数（每帧展开一层）
+const REVEAL_FRAMES: u8 = 32;
+
+/// 树节点没有记录展开状态时的默认展开方式
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TreeExpand {
+    /// 展开到设置中的默认层数
+    Default,
+    /// 展开到指定层数
+    Level(u8),
+    All,
+    None,
+}
+
+/// 单个标签页中的文档状态
+pub struct Document {
+    pub id: u64,
+    pub title: String,
+    pub input: String,
+    pub error: Option<String>,
+    pub last_json: Option<serde_json::Value>,
+    /// 解析树的搜索栏
+    pub search: SearchBar,
+    /// 解析树的路径栏
+    pub path_bar: PathBar,
+    /// 解析树的展开控制
+    pub expand_bar: ExpandBar,
+    /// 树中叶子值的预览（时间戳、颜色、链接等）
+    pub previews: ValuePreviews,
+    pub pending_edits: Vec<JsonEdit>,
+    pub edit_dialog: Option<EditDialog>,
+    /// 批量编辑面板，打开时为 Some
+    pub bulk_panel: Option<BulkPanel>,
+    /// 以表格查看的数组，打开时为 Some
+    pub table_view: Option<TableView>,
+    /// 统计面板，打开时为 Some
+    pub stats_panel: Option<StatsPanel>,
+    /// 导出面板，打开时为 Some
+    pub export_panel: Option<ExportPanel>,
+    pub history: EditHistory,
+    pub file_path: Option<PathBuf>,
+    pub watcher: Option<FileWatcher>,
+    pub changed_pointers: HashSet<String>,
+    /// 树中选中的节点
+    pub selected: Option<String>,
+    /// 需要在树中展开并滚动到的节点，以及剩余的尝试帧数
+    pub reveal: Option<(String, u8)>,
+    /// 需要在文本框中选中的范围（字节偏移）
+    pub text_jump: Option<Range<usize>>,
+    /// 上一帧文本框光标位置（字符下标）
+    pub last_caret: Option<usize>,
+    /// 输入编辑器的着色与折叠状态
+    pub highlighter: Highlighter,
+    /// 文本编辑器的查找/替换栏
+    pub find: FindBar,
+    /// 解析树的默认展开方式
+    pub tree_expand: TreeExpand,
+    /// 下一帧清除树中记录的展开状态，按 tree_expand 重新展开
+    pub reset_expanded: bool,
+    /// 手动展开折叠的状态，编辑后据此恢复
+    pub expansion: ExpansionState,
+    /// 原文节点位置缓存，以原文哈希为键
+    span_cache: Option<(u64, SpanMap)>,
+    /// 最近一次载入时的文本，用于判断是否有未保存的修改
+    saved_input: String,
+}
+
+impl Document {
+    pub fn new(id: u64, title: impl Into<String>) -> Self {
+        Self {
+            id,
+            title: title.into(),
+            input: String::new(),
+            error: None,
+            last_json: None,
+            search: SearchBar::default(),
+            path_bar: PathBar::default(),
+            expand_bar: ExpandBar::default(),
+            previews: ValuePreviews::default(),
+            pending_edits: Vec::new(),
+            edit_dialog: None,
+            bulk_panel: None,
+            table_view: None,
+            stats_panel: None,
+            export_panel: None,
+            history: EditHistory::default(),
+            file_path: None,
+            watcher: None,
+            changed_pointers: HashSet::new(),
+            selected: None,
+            reveal: None,
+            text_jump: None,
+            last_caret: None,
+            highlighter: Highlighter::default(),
+            find: FindBar::default(),
+            tree_expand: TreeExpand::Default,
+            reset_expanded: false,
+            expansion: ExpansionState::default(),
+            span_cache: None,
+            saved_input: String::new(),
+        }
+    }
+
+    /// 标签页显示的标题：优先使用文件名
+    pub fn display_title(&self) -> String {
+        match self.file_path.as_ref().and_then(|p| p.file_name()) {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => self.title.clone(),
+        }
+    }
+
+    /// 输入内容是否在载入后被修改过
+    pub fn is_dirty(&self) -> bool {
+        self.input != self.saved_input
+    }
+
+    /// 将当前内容视为已保存状态
+    pub fn mark_saved(&mut self) {
+        self.saved_input = self.input.clone();
+    }
+
+    /// 应用待处理的编辑操作，只重写原文中发生变化的区域
+    pub fn apply_edits(&mut self, indent_spaces: usize) {
+        if self.pending_edits.is_empty() {
+            return;
+        }
+        let Some(ref mut value) = self.last_json else {
+            self.pending_edits.clear();
+            return;
+        };
+        self.history.record(&self.input, Some(value));
+        let old = value.clone();
+        for edit in self.pending_edits.drain(..) {
+            self.expansion.remap(value, &edit);
+            apply_edit(value, edit);
+        }
+        self.input = patch_text(&self.input, &old, value, indent_spaces);
+    }
+
+    /// 用新文本整体替换原文（例如查找替换），可撤销
+    pub fn replace_text(&mut self, text: String) {
+        self.history.record(&self.input, self.last_json.as_ref());
+        self.input = text;
+        match serde_json::from_str::<serde_json::Value>(&self.input) {
+            Ok(v) => {
+                self.error = None;
+                if v.is_object() || v.is_array() {
+                    self.last_json = Some(v);
+                }
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// 按缩进重新格式化原文；原文不是合法 JSON 时记录错误
+    pub fn format_input(&mut self, indent_spaces: usize) {
+        self.parse_input(Some(indent_spaces));
+    }
+
+    /// 解析原文并更新解析树，返回是否解析成功；format 为 Some 时按该缩进重新排版，
+    /// 否则保留原文的排版。解析失败时原文不变，解析树保持上一次成功的结果
+    pub fn parse_input(&mut self, format: Option<usize>) -> bool {
+        match serde_json::from_str::<serde_json::Value>(&self.input) {
+            Ok(v) => {
+                self.error = None;
+                if let Some(indent_spaces) = format {
+                    self.input = to_string_with_indent(&v, indent_spaces);
+                }
+                if v.is_object() || v.is_array() {
+                    self.last_json = Some(v);
+                }
+                true
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+                false
+            }
+        }
+    }
+
+    /// 把原文压缩为一行
+    pub fn minify_input(&mut self) {
+        match serde_json::from_str::<serde_json::Value>(&self.input) {
+            Ok(v) => {
+                self.error = None;
+                self.input = serde_json::to_string(&v).unwrap_or_default();
+                if v.is_object() || v.is_array() {
+                    self.last_json = Some(v);
+                }
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// 开始或停止跟随打开的文件
+    pub fn set_following(&mut self, follow: bool) {
+        self.watcher = match (&self.file_path, follow) {
+            (Some(path), true) => Some(FileWatcher::new(path.clone())),
+            _ => None,
+        };
+        self.changed_pointers.clear();
+    }
+
+    /// 丢弃树中手动展开、折叠的状态，全部按 expand 重新展开
+    pub fn set_tree_expand(&mut self, expand: TreeExpand) {
+        self.tree_expand = expand;
+        self.reset_expanded = true;
+        self.expansion.clear();
+    }
+
+    /// 撤销最近一次树编辑
+    pub fn undo(&mut self) {
+        self.history.undo(&mut self.input, &mut self.last_json);
+    }
+
+    /// 重做最近一次撤销的树编辑
+    pub fn redo(&mut self) {
+        self.history.redo(&mut self.input, &mut self.last_json);
+    }
+
+    /// 当前原文的节点位置，原文不是合法 JSON 时返回 None
+    pub fn span_map(&mut self) -> Option<&SpanMap> {
+        let mut hasher = DefaultHasher::new();
+        self.input.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.span_cache.as_ref().map(|(h, _)| *h) != Some(hash) {
+            self.span_cache = SpanMap::parse(&self.input).map(|map| (hash, map));
+        }
+        self.span_cache.as_ref().map(|(_, map)| map)
+    }
+
+    /// 在树中选中节点，并在文本框中选中其对应的原文
+    pub fn select_from_tree(&mut self, pointer: String) {
+        self.text_jump = self.span_map().and_then(|map| map.get(&pointer)).map(|n| n.full());
+        self.selected = Some(pointer);
+    }
+
+    /// 文本框光标移动后，选中并展开光标所在的树节点
+    pub fn select_from_caret(&mut self, char_idx: usize) {
+        let offset = char_to_byte(&self.input, char_idx);
+        let Some(pointer) = self.span_map().and_then(|map| map.node_at(offset)).map(|n| n.pointer.clone()) else {
+            return;
+        };
+        if self.selected.as_deref() != Some(pointer.as_str()) {
+            self.reveal_node(pointer);
+        }
+    }
+
+    /// 在树中选中节点，并展开其祖先、滚动到该节点
+    pub fn reveal_node(&mut self, pointer: String) {
+        self.reveal = Some((pointer.clone(), REVEAL_FRAMES));
+        self.selected = Some(pointer);
+    }
+
+    /// 跳转到节点：在树中展开并选中，同时在文本框中选中其原文
+    pub fn jump_to(&mut self, pointer: String) {
+        self.text_jump = self.span_map().and_then(|map| map.get(&pointer)).map(|n| n.full());
+        self.reveal_node(pointer);
+    }
+
+    /// 打开文件并载入内容；format 的含义同 parse_input
+    pub fn open_file(&mut self, path: PathBuf, format: Option<usize>) {
+        match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                self.changed_pointers.clear();
+                self.expansion.clear();
+                self.load_text(&text, format);
+                self.mark_saved();
+                if self.watcher.is_some() {
+                    self.watcher = Some(FileWatcher::new(path.clone()));
+                }
+                self.file_path = Some(path);
+            }
+            Err(e) => self.error = Some(tr!("读取文件失败：{}", e)),
+        }
+    }
+
+    /// 跟随文件变化重新载入，并记录相对上次载入发生变化的节点
+    pub fn reload_followed(&mut self, result: Result<String, String>, format: Option<usize>) {
+        match result {
+            Ok(text) => {
+                let old = self.last_json.clone();
+                if self.load_text(&text, format)
+                    && let (Some(old), Some(new)) = (&old, &self.last_json)
+                {
+                    self.changed_pointers = changed_pointers(old, new);
+                }
+                self.mark_saved();
+            }
+            Err(e) => self.error = Some(tr!("读取文件失败：{}", e)),
+        }
+    }
+
+    /// 将文本填入输入框并解析，返回是否解析成功；format 的含义同 parse_input
+    pub fn load_text(&mut self, text: &str, format: Option<usize>) -> bool {
+        self.input = text.to_string();
+        self.parse_input(format)
+    }
+
+    /// 载入一个已经解析好的值
+    pub fn load_value(&mut self, value: serde_json::Value, indent_spaces: usize) {
+        self.input = to_string_with_indent(&value, indent_spaces);
+        self.error = None;
+        self.last_json = Some(value);
+    }
+}
+
+/// 按指定缩进序列化 JSON 值
+pub fn to_string_with_indent(value: &serde_json::Value, indent_spaces: usize) -> String {
+    let mut buf = Vec::new();
+    let indent = " ".repeat(indent_spaces);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+    let _ = value.serialize(&mut ser);
+    String::from_utf8(buf).unwrap_or_default()
+}
@@ -1,87 +1,172 @@
 use serde_json::Value;
 
+use crate::embedded::{expand_embedded, stringify};
+use crate::tree_ops::{self, Placement};
+use crate::value_editor::ValueEditor;
+
+/// 撤销历史保留的最大步数
+const MAX_HISTORY: usize = 100;
+
 /// JSON 编辑操作
 pub enum JsonEdit {
     DeleteFromObject { object_pointer: String, key: String },
     DeleteFromArray { array_pointer: String, idx: usize },
     AddToObject { pointer: String },
     AddToArray { pointer: String },
-    EditValue { pointer: String, new_value: String },
+    EditValue { pointer: String, new_value: Value },
     EditObjectKey { object_pointer: String, old_key: String, new_key: String },
+    /// 把字符串中内嵌的 JSON（可多层转义）展开为真实子树
+    ExpandEmbedded { pointer: String },
+    /// 把子树压缩序列化为 JSON 字符串
+    Stringify { pointer: String },
+    /// 在指定位置插入值；对象中未指定键名时使用 new_key
+    InsertValue { at: Placement, key: Option<String>, value: Value },
+    /// 复制节点并插入到其后面
+    Duplicate { pointer: String },
+    MoveUp { pointer: String },
+    MoveDown { pointer: String },
+    /// 把节点移动到新位置，可跨容器
+    MoveNode { from: String, to: Placement },
 }
 
 /// 编辑对话框状态
 pub enum EditDialog {
-    EditValue { pointer: String, input: String },
+    EditValue { pointer: String, editor: ValueEditor },
     EditKey { object_pointer: String, old_key: String, input: String },
 }
 
 /// 应用编辑操作到 JSON 值
-pub fn apply_edits(value: &mut Value, edits: &mut Vec<JsonEdit>) -> Option<String> {
-    if edits.is_empty() {
-        return None;
-    }
-
-    for edit in edits.drain(..) {
-        match edit {
-            JsonEdit::DeleteFromArray { array_pointer, idx } => {
-                if let Some(arr) = value.pointer_mut(&array_pointer).and_then(|v| v.as_array_mut()) {
-                    if idx < arr.len() {
-                        arr.remove(idx);
-                    }
-                }
+pub fn apply_edit(value: &mut Value, edit: JsonEdit) {
+    match edit {
+        JsonEdit::DeleteFromArray { array_pointer, idx } => {
+            if let Some(arr) = value.pointer_mut(&array_pointer).and_then(|v| v.as_array_mut())
+                && idx < arr.len()
+            {
+                arr.remove(idx);
             }
-            JsonEdit::DeleteFromObject { object_pointer, key } => {
-                if let Some(obj) = value.pointer_mut(&object_pointer).and_then(|v| v.as_object_mut()) {
-                    obj.remove(&key);
-                }
+        }
+        JsonEdit::DeleteFromObject { object_pointer, key } => {
+            if let Some(obj) = value.pointer_mut(&object_pointer).and_then(|v| v.as_object_mut()) {
+                obj.shift_remove(&key);
             }
-            JsonEdit::AddToObject { pointer } => {
-                if let Some(obj) = value.pointer_mut(&pointer).and_then(|v| v.as_object_mut()) {
-                    let mut counter = 0;
-                    let mut new_key = "new_key".to_string();
-                    while obj.contains_key(&new_key) {
-                        counter += 1;
-                        new_key = format!("new_key_{}", counter);
-                    }
-                    obj.insert(new_key, Value::Null);
+        }
+        JsonEdit::AddToObject { pointer } => {
+            if let Some(obj) = value.pointer_mut(&pointer).and_then(|v| v.as_object_mut()) {
+                let mut counter = 0;
+                let mut new_key = "new_key".to_string();
+                while obj.contains_key(&new_key) {
+                    counter += 1;
+                    new_key = format!("new_key_{}", counter);
                 }
+                obj.insert(new_key, Value::Null);
             }
-            JsonEdit::AddToArray { pointer } => {
-                if let Some(arr) = value.pointer_mut(&pointer).and_then(|v| v.as_array_mut()) {
-                    arr.push(Value::Null);
-                }
+        }
+        JsonEdit::AddToArray { pointer } => {
+            if let Some(arr) = value.pointer_mut(&pointer).and_then(|v| v.as_array_mut()) {
+                arr.push(Value::Null);
             }
-            JsonEdit::EditValue { pointer, new_value } => {
-                if let Some(target) = value.pointer_mut(&pointer) {
-                    match serde_json::from_str(&new_value) {
-                        Ok(new_val) => *target = new_val,
-                        Err(_) => *target = Value::String(new_value),
-                    }
-                }
+        }
+        JsonEdit::EditValue { pointer, new_value } => {
+            if let Some(target) = value.pointer_mut(&pointer) {
+                *target = new_value;
             }
-            JsonEdit::EditObjectKey { object_pointer, old_key, new_key } => {
-                if let Some(obj) = value.pointer_mut(&object_pointer).and_then(|v| v.as_object_mut()) {
-                    // 保持键的顺序：收集所有键值对，替换旧键，然后重建对象
-                    let entries: Vec<(String, Value)> = obj.iter()
-                        .map(|(k, v)| {
-                            if k == &old_key {
-                                (new_key.clone(), v.clone())
-                            } else {
-                                (k.clone(), v.clone())
-                            }
-                        })
-                        .collect();
-                    
-                    obj.clear();
-                    for (k, v) in entries {
-                        obj.insert(k, v);
-                    }
+        }
+        JsonEdit::EditObjectKey { object_pointer, old_key, new_key } => {
+            if let Some(obj) = value.pointer_mut(&object_pointer).and_then(|v| v.as_object_mut()) {
+                // 保持键的顺序：收集所有键值对，替换旧键，然后重建对象
+                let entries: Vec<(String, Value)> = obj.iter()
+                    .map(|(k, v)| {
+                        if k == &old_key {
+                            (new_key.clone(), v.clone())
+                        } else {
+                            (k.clone(), v.clone())
+                        }
+                    })
+                    .collect();
+                
+                obj.clear();
+                for (k, v) in entries {
+                    obj.insert(k, v);
                 }
             }
         }
+        JsonEdit::ExpandEmbedded { pointer } => {
+            if let Some(target) = value.pointer_mut(&pointer) {
+                expand_embedded(target);
+            }
+        }
+        JsonEdit::Stringify { pointer } => {
+            if let Some(target) = value.pointer_mut(&pointer) {
+                *target = stringify(target);
+            }
+        }
+        JsonEdit::InsertValue { at, key, value: new_value } => {
+            tree_ops::insert_value(value, &at, key, new_value);
+        }
+        JsonEdit::Duplicate { pointer } => tree_ops::duplicate(value, &pointer),
+        JsonEdit::MoveUp { pointer } => tree_ops::shift(value, &pointer, true),
+        JsonEdit::MoveDown { pointer } => tree_ops::shift(value, &pointer, false),
+        JsonEdit::MoveNode { from, to } => tree_ops::move_node(value, &from, &to),
     }
+}
+
+/// 编辑前的文档快照
+struct Snapshot {
+    input: String,
+    value: Option<Value>,
+}
+
+/// 撤销/重做历史，每批编辑操作记录一个快照
+#[derive(Default)]
+pub struct EditHistory {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
 
-    // 返回格式化后的 JSON
-    serde_json::to_string_pretty(value).ok()
+impl EditHistory {
+    /// 记录编辑前的状态，并清空重做栈
+    pub fn record(&mut self, input: &str, value: Option<&Value>) {
+        self.undo.push(Snapshot {
+            input: input.to_string(),
+            value: value.cloned(),
+        });
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// 撤销：用上一个快照替换当前状态
+    pub fn undo(&mut self, input: &mut String, value: &mut Option<Value>) -> bool {
+        Self::swap(&mut self.undo, &mut self.redo, input, value)
+    }
+
+    /// 重做：恢复最近一次撤销前的状态
+    pub fn redo(&mut self, input: &mut String, value: &mut Option<Value>) -> bool {
+        Self::swap(&mut self.redo, &mut self.undo, input, value)
+    }
+
+    fn swap(
+        from: &mut Vec<Snapshot>,
+        to: &mut Vec<Snapshot>,
+        input: &mut String,
+        value: &mut Option<Value>,
+    ) -> bool {
+        let Some(snapshot) = from.pop() else {
+            return false;
+        };
+        to.push(Snapshot {
+            input: std::mem::replace(input, snapshot.input),
+            value: std::mem::replace(value, snapshot.value),
+        });
+        true
+    }
 }
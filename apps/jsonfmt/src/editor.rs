@@ -0,0 +1,317 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+use appkit::theme::Palette;
+use eframe::egui::{self, text::LayoutJob, Color32, FontId, TextFormat};
+
+use crate::highlight::{matching_bracket, Analysis, TokenKind};
+
+/// 超过该长度的文本不再着色，避免每帧重建排版过慢
+const MAX_HIGHLIGHT_LEN: usize = 2 * 1024 * 1024;
+
+/// 输入编辑器的配色
+#[derive(Clone, Copy)]
+pub struct SyntaxColors {
+    pub key: Color32,
+    pub string: Color32,
+    pub number: Color32,
+    pub literal: Color32,
+    pub punct: Color32,
+    pub error: Color32,
+    pub bracket_match: Color32,
+    pub find_match: Color32,
+    pub find_current: Color32,
+    pub current_line: Color32,
+    pub indent_guide: Color32,
+}
+
+impl SyntaxColors {
+    /// 以主题配色为基础，叠加层的颜色按深浅色选取
+    pub fn new(palette: &Palette, visuals: &egui::Visuals) -> Self {
+        let base = Self {
+            key: palette.key,
+            string: palette.string,
+            number: palette.number,
+            literal: palette.literal,
+            punct: palette.punct,
+            error: palette.error,
+            bracket_match: Color32::TRANSPARENT,
+            find_match: Color32::TRANSPARENT,
+            find_current: Color32::TRANSPARENT,
+            current_line: Color32::TRANSPARENT,
+            indent_guide: Color32::TRANSPARENT,
+        };
+        if visuals.dark_mode {
+            Self {
+                bracket_match: Color32::from_rgba_unmultiplied(120, 160, 255, 70),
+                find_match: Color32::from_rgba_unmultiplied(230, 180, 40, 60),
+                find_current: Color32::from_rgba_unmultiplied(240, 140, 30, 150),
+                current_line: Color32::from_white_alpha(10),
+                indent_guide: Color32::from_white_alpha(25),
+                ..base
+            }
+        } else {
+            Self {
+                bracket_match: Color32::from_rgba_unmultiplied(60, 110, 230, 60),
+                find_match: Color32::from_rgba_unmultiplied(250, 200, 50, 90),
+                find_current: Color32::from_rgba_unmultiplied(250, 150, 30, 170),
+                current_line: Color32::from_black_alpha(12),
+                indent_guide: Color32::from_black_alpha(30),
+                ..base
+            }
+        }
+    }
+
+    fn token(&self, kind: TokenKind) -> Color32 {
+        match kind {
+            TokenKind::Key => self.key,
+            TokenKind::String => self.string,
+            TokenKind::Number => self.number,
+            TokenKind::Literal => self.literal,
+            TokenKind::Bracket | TokenKind::Punct | TokenKind::Whitespace => self.punct,
+            TokenKind::Error => self.error,
+        }
+    }
+}
+
+/// 每帧变化的装饰：光标位置与查找结果
+#[derive(Default)]
+pub struct Decorations<'a> {
+    /// 光标的字节偏移
+    pub caret: Option<usize>,
+    /// 查找匹配的字节范围，有序且不重叠
+    pub matches: &'a [Range<usize>],
+    /// 当前查找匹配在 matches 中的下标
+    pub current_match: Option<usize>,
+}
+
+/// 在有序且不重叠的区间中定位 pos：返回其所在区间的下标（若有）以及下一个区间边界
+fn locate(ranges: &[Range<usize>], cursor: &mut usize, pos: usize) -> (Option<usize>, usize) {
+    while ranges.get(*cursor).is_some_and(|r| r.end <= pos) {
+        *cursor += 1;
+    }
+    match ranges.get(*cursor) {
+        Some(r) if r.start <= pos => (Some(*cursor), r.end),
+        Some(r) => (None, r.start),
+        None => (None, usize::MAX),
+    }
+}
+
+/// 这一帧的输入事件会修改文本
+pub fn edits_text(event: &egui::Event) -> bool {
+    match event {
+        egui::Event::Text(_) | egui::Event::Paste(_) | egui::Event::Cut => true,
+        egui::Event::Key { key, pressed: true, .. } => matches!(
+            key,
+            egui::Key::Backspace | egui::Key::Delete | egui::Key::Enter | egui::Key::Tab
+        ),
+        _ => false,
+    }
+}
+
+/// 文本变化后重新定位折叠：编辑区域之前的折叠不变，之后的按长度差平移；
+/// 隐藏的内容与编辑区域相交的折叠被展开，新位置不再是左括号的折叠被丢弃
+fn remap_folds(folds: &BTreeSet<usize>, old_text: &str, old: &Analysis, new_text: &str, new: &Analysis) -> BTreeSet<usize> {
+    let (old_bytes, new_bytes) = (old_text.as_bytes(), new_text.as_bytes());
+    let prefix = old_bytes.iter().zip(new_bytes).take_while(|(a, b)| a == b).count();
+    let suffix = old_bytes[prefix..]
+        .iter()
+        .rev()
+        .zip(new_bytes[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    // 旧文本中 prefix..old_end 被替换为新文本中的 prefix..new_end
+    let (old_end, new_end) = (old_text.len() - suffix, new_text.len() - suffix);
+    folds
+        .iter()
+        .filter_map(|&open| {
+            let close = old.pairs.iter().find(|p| p.open == open)?.close;
+            if prefix <= close && old_end > open {
+                return None;
+            }
+            let moved = if open < prefix { open } else { open - old_end + new_end };
+            new.pairs.iter().any(|p| p.open == moved).then_some(moved)
+        })
+        .collect()
+}
+
+/// 输入编辑器的着色、括号匹配与折叠状态
+#[derive(Default)]
+pub struct Highlighter {
+    /// 上次分析的文本及结果；保留原文用于在文本变化后重新定位折叠
+    cache: Option<(String, Arc<Analysis>)>,
+    /// 已折叠区域的左括号位置
+    folds: BTreeSet<usize>,
+}
+
+impl Highlighter {
+    /// 分析文本；文本变化时重新分析，并按编辑位置平移折叠
+    pub fn analyze(&mut self, text: &str) -> Arc<Analysis> {
+        if let Some((cached, analysis)) = &self.cache
+            && cached == text
+        {
+            return analysis.clone();
+        }
+        let analysis = Arc::new(Analysis::new(text));
+        if let Some((old_text, old)) = self.cache.take() {
+            self.folds = remap_folds(&self.folds, &old_text, &old, text, &analysis);
+        }
+        self.cache = Some((text.to_owned(), analysis.clone()));
+        analysis
+    }
+
+    pub fn is_folded(&self, open: usize) -> bool {
+        self.folds.contains(&open)
+    }
+
+    pub fn toggle_fold(&mut self, open: usize) {
+        if !self.folds.remove(&open) {
+            self.folds.insert(open);
+        }
+    }
+
+    /// 展开隐藏内容与 range（字节范围，可为空的光标位置）相接的折叠，
+    /// 在编辑前调用，避免修改看不见的文本
+    pub fn unfold_touching(&mut self, text: &str, range: Range<usize>) {
+        if self.folds.is_empty() {
+            return;
+        }
+        let analysis = self.analyze(text);
+        self.folds.retain(|&open| {
+            analysis
+                .pairs
+                .iter()
+                .find(|p| p.open == open)
+                .is_none_or(|p| range.start > p.close || range.end <= p.open)
+        });
+    }
+
+    /// 被折叠隐藏的字节范围（括号之间的内容），嵌套的折叠只保留最外层
+    fn hidden_ranges(&self, analysis: &Analysis) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for &open in &self.folds {
+            let Some(pair) = analysis.pairs.iter().find(|p| p.open == open) else {
+                continue;
+            };
+            if ranges.last().is_some_and(|last| pair.open < last.end) {
+                continue;
+            }
+            ranges.push(pair.open + 1..pair.close);
+        }
+        ranges
+    }
+
+    /// 为 TextEdit 生成带着色的排版
+    pub fn layout_job(
+        &mut self,
+        text: &str,
+        font_id: FontId,
+        colors: &SyntaxColors,
+        decorations: &Decorations,
+        wrap_width: f32,
+    ) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        job.wrap.max_width = wrap_width;
+        if text.len() > MAX_HIGHLIGHT_LEN {
+            job.append(text, 0.0, TextFormat::simple(font_id, colors.punct));
+            return job;
+        }
+
+        let analysis = self.analyze(text);
+        let hidden = self.hidden_ranges(&analysis);
+        let matched = decorations.caret.and_then(|c| matching_bracket(&analysis.pairs, c));
+        // 查找结果可能来自编辑前的文本，越界或不在字符边界上时本帧不高亮
+        let matches = if decorations
+            .matches
+            .iter()
+            .all(|r| r.end <= text.len() && text.is_char_boundary(r.start) && text.is_char_boundary(r.end))
+        {
+            decorations.matches
+        } else {
+            &[]
+        };
+        let hidden_format = TextFormat {
+            font_id: FontId::new(1.0, font_id.family.clone()),
+            color: Color32::TRANSPARENT,
+            line_height: Some(0.0),
+            ..Default::default()
+        };
+
+        let (mut next_hidden, mut next_match) = (0, 0);
+        for token in &analysis.tokens {
+            let mut format = TextFormat::simple(font_id.clone(), colors.token(token.kind));
+            if matched.is_some_and(|m| m.open == token.range.start || m.close == token.range.start) {
+                format.background = colors.bracket_match;
+            }
+            // 按折叠区域和查找结果切分词法单元
+            let mut pos = token.range.start;
+            while pos < token.range.end {
+                let (hidden_at, hidden_end) = locate(&hidden, &mut next_hidden, pos);
+                let (match_at, match_end) = locate(matches, &mut next_match, pos);
+                let end = token.range.end.min(hidden_end).min(match_end);
+                let segment_format = if hidden_at.is_some() {
+                    hidden_format.clone()
+                } else if let Some(i) = match_at {
+                    let mut f = format.clone();
+                    f.background = if decorations.current_match == Some(i) {
+                        colors.find_current
+                    } else {
+                        colors.find_match
+                    };
+                    f
+                } else {
+                    format.clone()
+                };
+                job.append(&text[pos..end], 0.0, segment_format);
+                pos = end;
+            }
+        }
+        job
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remap(folds: &[usize], old_text: &str, new_text: &str) -> Vec<usize> {
+        let folds = folds.iter().copied().collect();
+        let (old, new) = (Analysis::new(old_text), Analysis::new(new_text));
+        remap_folds(&folds, old_text, &old, new_text, &new).into_iter().collect()
+    }
+
+    #[test]
+    fn folds_before_an_edit_stay_put() {
+        let old = "{\"a\": [1, 2], \"b\": 1}";
+        let new = "{\"a\": [1, 2], \"b\": 100}";
+        assert_eq!(remap(&[6], old, new), [6]);
+    }
+
+    #[test]
+    fn folds_after_an_edit_shift_by_the_delta() {
+        let old = "{\"a\": 1, \"b\": [1, 2]}";
+        let new = "{\"a\": 123, \"b\": [1, 2]}";
+        assert_eq!(remap(&[14], old, new), [16]);
+    }
+
+    #[test]
+    fn edits_inside_a_fold_unfold_it_and_its_ancestors() {
+        let old = "{\"a\": [1, 2]}";
+        let new = "{\"a\": [1, 2, 3]}";
+        assert_eq!(remap(&[0, 6], old, new), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn unfold_touching_caret_inside_hidden_range() {
+        let text = "{\"a\": [1, 2], \"b\": [3]}";
+        let mut highlighter = Highlighter::default();
+        highlighter.analyze(text);
+        highlighter.toggle_fold(6);
+        highlighter.toggle_fold(19);
+        // 光标在第一个数组的右括号前
+        highlighter.unfold_touching(text, 11..11);
+        assert!(!highlighter.is_folded(6));
+        assert!(highlighter.is_folded(19));
+    }
+}
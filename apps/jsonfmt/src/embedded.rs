@@ -0,0 +1,100 @@
+use serde_json::Value;
+
+/// 字符串中嵌套转义 JSON 的最大展开层数
+const MAX_UNESCAPE_DEPTH: usize = 8;
+
+/// 尝试把字符串内容解析为内嵌的 JSON 对象或数组，支持多层转义
+pub fn parse_embedded(s: &str) -> Option<Value> {
+    let trimmed = s.trim();
+    if !matches!(trimmed.chars().next(), Some('{' | '[' | '"')) {
+        return None;
+    }
+    let value = serde_json::from_str::<Value>(trimmed).ok()?;
+    unwrap_container(value)
+}
+
+/// 若值为对象/数组则直接返回；若为字符串，则尝试逐层展开其中转义的 JSON
+pub fn unwrap_container(value: Value) -> Option<Value> {
+    let mut current = value;
+    for _ in 0..MAX_UNESCAPE_DEPTH {
+        match current {
+            Value::Object(_) | Value::Array(_) => return Some(current),
+            Value::String(s) => current = serde_json::from_str::<Value>(s.trim()).ok()?,
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// 递归展开值中所有内嵌 JSON 字符串，返回展开的数量
+pub fn expand_embedded(value: &mut Value) -> usize {
+    let mut count = 0;
+    if let Value::String(s) = value {
+        match parse_embedded(s) {
+            Some(parsed) => {
+                *value = parsed;
+                count += 1;
+            }
+            None => return 0,
+        }
+    }
+    match value {
+        Value::Object(map) => {
+            for child in map.values_mut() {
+                count += expand_embedded(child);
+            }
+        }
+        Value::Array(arr) => {
+            for child in arr.iter_mut() {
+                count += expand_embedded(child);
+            }
+        }
+        _ => {}
+    }
+    count
+}
+
+/// 把值压缩序列化为 JSON 字符串值
+pub fn stringify(value: &Value) -> Value {
+    Value::String(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// 把值序列化成字符串 times 次，得到多层转义的 JSON
+    fn escaped(value: Value, times: usize) -> Value {
+        (0..times).fold(value, |v, _| stringify(&v))
+    }
+
+    #[test]
+    fn expands_nested_and_multi_level_strings() {
+        let inner = json!({"b": escaped(json!([1, 2]), 2)});
+        let mut value = json!({"a": escaped(inner, 3), "n": 1, "s": "{not json"});
+        assert_eq!(expand_embedded(&mut value), 2);
+        assert_eq!(value, json!({"a": {"b": [1, 2]}, "n": 1, "s": "{not json"}));
+    }
+
+    #[test]
+    fn stops_at_max_depth() {
+        let mut value = json!([escaped(json!({}), MAX_UNESCAPE_DEPTH), escaped(json!({}), MAX_UNESCAPE_DEPTH + 1)]);
+        assert_eq!(expand_embedded(&mut value), 1);
+        assert_eq!(value[0], json!({}));
+        assert!(value[1].is_string());
+    }
+
+    #[test]
+    fn only_containers_are_expanded() {
+        assert_eq!(parse_embedded(" [1] "), Some(json!([1])));
+        assert_eq!(parse_embedded("\"\\\"x\\\"\""), None);
+        assert_eq!(parse_embedded("\"42\""), None);
+        assert_eq!(parse_embedded("42"), None);
+        assert_eq!(parse_embedded("true"), None);
+        let mut value = json!("\"hello\"");
+        assert_eq!(expand_embedded(&mut value), 0);
+        assert_eq!(value, json!("\"hello\""));
+    }
+}
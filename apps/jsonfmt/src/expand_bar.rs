@@ -0,0 +1,50 @@
+use eframe::egui;
+
+use crate::document::TreeExpand;
+use crate::i18n::tr;
+
+/// 展开层数的上限
+const MAX_DEPTH: u8 = 32;
+
+/// 解析树上方的展开控制：全部展开/折叠、展开到指定层数和默认展开层数
+pub struct ExpandBar {
+    /// “展开到”使用的层数
+    level: u8,
+}
+
+impl Default for ExpandBar {
+    fn default() -> Self {
+        Self { level: 1 }
+    }
+}
+
+impl ExpandBar {
+    /// 绘制展开控制，返回新的展开方式；修改默认层数时返回 TreeExpand::Default
+    pub fn show(&mut self, ui: &mut egui::Ui, default_depth: &mut u8) -> Option<TreeExpand> {
+        let mut expand = None;
+        ui.horizontal_wrapped(|ui| {
+            if ui.small_button(tr!("全部展开")).clicked() {
+                expand = Some(TreeExpand::All);
+            }
+            if ui.small_button(tr!("全部折叠")).clicked() {
+                expand = Some(TreeExpand::None);
+            }
+            ui.separator();
+            if ui.small_button(tr!("展开到")).clicked() {
+                expand = Some(TreeExpand::Level(self.level));
+            }
+            ui.add(egui::DragValue::new(&mut self.level).range(0..=MAX_DEPTH).suffix(tr!(" 层")))
+                .on_hover_text(tr!("0 层只展开根节点"));
+            ui.separator();
+            ui.label(tr!("默认："));
+            if ui
+                .add(egui::DragValue::new(default_depth).range(0..=MAX_DEPTH).suffix(tr!(" 层")))
+                .on_hover_text(tr!("打开文档时默认展开的层数"))
+                .changed()
+            {
+                expand = Some(TreeExpand::Default);
+            }
+        });
+        expand
+    }
+}
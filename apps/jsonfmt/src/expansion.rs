@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+
+use eframe::egui;
+use serde_json::Value;
+
+use crate::edit::JsonEdit;
+use crate::tree_ops::{join_pointer, resolve, split_pointer, unique_key, Placement};
+
+/// 编辑后恢复展开状态、递归展开子树时持续的帧数（每帧展开一层）
+const RESTORE_FRAMES: u8 = 32;
+
+/// pointer 是否为 prefix 本身或其后代
+fn within(pointer: &str, prefix: &str) -> bool {
+    pointer.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// pointer 是 parent 的后代时，返回其在 parent 下的第一段（未反转义）和其余部分
+fn child_of<'a>(pointer: &'a str, parent: &str) -> Option<(&'a str, &'a str)> {
+    let rest = pointer.strip_prefix(parent)?.strip_prefix('/')?;
+    Some(rest.split_once('/').map_or((rest, ""), |(head, _)| (head, &rest[head.len()..])))
+}
+
+/// 解析树的展开状态。egui_json_tree 按路径记录展开状态，编辑使数组下标或键名变化后，
+/// 状态会落到错误的节点上；这里记录渲染过的每个容器节点是否展开，编辑时随之改写路径，
+/// 之后节点首次渲染时把状态写回树中
+#[derive(Default)]
+pub struct ExpansionState {
+    open: HashMap<String, bool>,
+    /// 编辑后尚未写回树中的状态，每个节点只写回一次，不影响之后的手动展开折叠
+    pending: HashMap<String, bool>,
+    /// 需要递归展开的子树
+    subtree: Option<String>,
+    /// 剩余的强制恢复帧数
+    frames: u8,
+}
+
+impl ExpansionState {
+    /// 记录渲染时看到的节点展开状态
+    pub fn observe(&mut self, pointer: &str, open: bool) {
+        if self.open.get(pointer) != Some(&open) {
+            self.open.insert(pointer.to_owned(), open);
+        }
+    }
+
+    /// 本帧需要强制设置的展开状态
+    pub fn forced(&mut self, pointer: &str) -> Option<bool> {
+        if self.frames == 0 {
+            return None;
+        }
+        if self.subtree.as_deref().is_some_and(|root| within(pointer, root)) {
+            return Some(true);
+        }
+        self.pending.remove(pointer)
+    }
+
+    /// 递归展开子树：之后每帧展开一层
+    pub fn expand_subtree(&mut self, pointer: String) {
+        self.subtree = Some(pointer);
+        self.frames = RESTORE_FRAMES;
+    }
+
+    /// 丢弃所有记录，例如重置展开状态或载入新文档后
+    pub fn clear(&mut self) {
+        self.open.clear();
+        self.pending.clear();
+        self.subtree = None;
+        self.frames = 0;
+    }
+
+    /// 每帧结束时调用
+    pub fn end_frame(&mut self, ctx: &egui::Context) {
+        if self.frames > 0 {
+            self.frames -= 1;
+            ctx.request_repaint();
+            if self.frames == 0 {
+                self.subtree = None;
+                self.pending.clear();
+            }
+        }
+    }
+
+    /// 在编辑应用到 root 之前调用，按编辑操作改写记录的路径
+    pub fn remap(&mut self, root: &Value, edit: &JsonEdit) {
+        let is_array = |pointer: &str| root.pointer(pointer).is_some_and(Value::is_array);
+        match edit {
+            JsonEdit::DeleteFromArray { array_pointer, idx } => {
+                self.remove(&format!("{}/{}", array_pointer, idx), true);
+            }
+            JsonEdit::DeleteFromObject { object_pointer, key } => {
+                self.remove(&join_pointer(object_pointer, key), false);
+            }
+            JsonEdit::EditObjectKey { object_pointer, old_key, new_key } => {
+                self.rename(&join_pointer(object_pointer, old_key), &join_pointer(object_pointer, new_key));
+            }
+            JsonEdit::InsertValue { at, .. } => {
+                if let Some((parent, idx)) = resolve(root, at).filter(|(p, _)| is_array(p)) {
+                    self.insert(&parent, idx);
+                }
+            }
+            JsonEdit::Duplicate { pointer } => {
+                let Some((parent, segment)) = split_pointer(pointer) else {
+                    return;
+                };
+                if let (true, Ok(idx)) = (is_array(parent), segment.parse::<usize>()) {
+                    self.insert(parent, idx + 1);
+                }
+            }
+            JsonEdit::MoveUp { pointer } | JsonEdit::MoveDown { pointer } => {
+                let Some((parent, segment)) = split_pointer(pointer) else {
+                    return;
+                };
+                let (true, Ok(idx)) = (is_array(parent), segment.parse::<usize>()) else {
+                    return;
+                };
+                let len = root.pointer(parent).and_then(Value::as_array).map_or(0, Vec::len);
+                let other = if matches!(edit, JsonEdit::MoveUp { .. }) { idx.checked_sub(1) } else { Some(idx + 1) };
+                if let Some(other) = other.filter(|&o| o < len) {
+                    self.swap(parent, idx, other);
+                }
+            }
+            JsonEdit::MoveNode { from, to } => self.move_node(root, from, to),
+            JsonEdit::EditValue { pointer, .. } | JsonEdit::Stringify { pointer } => {
+                // 子树被替换，原有后代的状态不再适用
+                let prefix = format!("{}/", pointer);
+                self.open.retain(|p, _| !p.starts_with(&prefix));
+            }
+            JsonEdit::AddToObject { .. } | JsonEdit::AddToArray { .. } | JsonEdit::ExpandEmbedded { .. } => {}
+        }
+        self.pending = self.open.clone();
+        self.frames = RESTORE_FRAMES;
+    }
+
+    /// 用 f 改写每条记录的路径，返回 None 的记录被丢弃
+    fn rewrite(&mut self, f: impl Fn(&str) -> Option<String>) {
+        self.open = std::mem::take(&mut self.open)
+            .into_iter()
+            .filter_map(|(p, open)| f(&p).map(|p| (p, open)))
+            .collect();
+    }
+
+    /// 节点被删除：丢弃其子树；数组中其后的元素下标前移
+    fn remove(&mut self, pointer: &str, in_array: bool) {
+        let Some((parent, segment)) = split_pointer(pointer) else {
+            return;
+        };
+        let removed = segment.parse::<usize>().ok().filter(|_| in_array);
+        self.rewrite(|p| {
+            if within(p, pointer) {
+                return None;
+            }
+            match (removed, child_of(p, parent)) {
+                (Some(removed), Some((head, rest))) => match head.parse::<usize>() {
+                    Ok(i) if i > removed => Some(format!("{}/{}{}", parent, i - 1, rest)),
+                    _ => Some(p.to_owned()),
+                },
+                _ => Some(p.to_owned()),
+            }
+        });
+    }
+
+    /// 在数组 parent 的 idx 处插入元素：其后的元素下标后移
+    fn insert(&mut self, parent: &str, idx: usize) {
+        self.rewrite(|p| match child_of(p, parent).map(|(head, rest)| (head.parse::<usize>(), rest)) {
+            Some((Ok(i), rest)) if i >= idx => Some(format!("{}/{}{}", parent, i + 1, rest)),
+            _ => Some(p.to_owned()),
+        });
+    }
+
+    /// 交换数组中两个元素的状态
+    fn swap(&mut self, parent: &str, a: usize, b: usize) {
+        self.rewrite(|p| match child_of(p, parent).map(|(head, rest)| (head.parse::<usize>(), rest)) {
+            Some((Ok(i), rest)) if i == a => Some(format!("{}/{}{}", parent, b, rest)),
+            Some((Ok(i), rest)) if i == b => Some(format!("{}/{}{}", parent, a, rest)),
+            _ => Some(p.to_owned()),
+        });
+    }
+
+    /// 把 from 子树的状态改挂到 to 下
+    fn rename(&mut self, from: &str, to: &str) {
+        self.rewrite(|p| match p.strip_prefix(from) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => Some(format!("{}{}", to, rest)),
+            _ => Some(p.to_owned()),
+        });
+    }
+
+    /// 节点移动：等价于先删除再在目标位置插入，子树的状态随之移动
+    fn move_node(&mut self, root: &Value, from: &str, to: &Placement) {
+        let (Some((from_parent, segment)), Some((to_parent, mut to_idx))) = (split_pointer(from), resolve(root, to)) else {
+            return;
+        };
+        if within(&to_parent, from) {
+            return;
+        }
+        let from_is_array = root.pointer(from_parent).is_some_and(Value::is_array);
+        let to_is_array = root.pointer(&to_parent).is_some_and(Value::is_array);
+        // 与 tree_ops::move_node 一致：移入其他对象时键名冲突会改名，数组元素使用默认键名
+        let key = match root.pointer(&to_parent).and_then(Value::as_object) {
+            Some(map) if to_parent != from_parent => {
+                unique_key(map, if from_is_array { "new_key" } else { &segment })
+            }
+            _ => segment.clone(),
+        };
+        let moved: Vec<(String, bool)> = self
+            .open
+            .iter()
+            .filter_map(|(p, &open)| p.strip_prefix(from).filter(|r| r.is_empty() || r.starts_with('/')).map(|r| (r.to_owned(), open)))
+            .collect();
+
+        self.remove(from, from_is_array);
+        // 删除源节点后目标容器的路径和下标可能前移
+        let mut to_parent = to_parent;
+        if let (true, Ok(removed)) = (from_is_array, segment.parse::<usize>()) {
+            if to_parent == from_parent && removed < to_idx {
+                to_idx -= 1;
+            }
+            let shifted = child_of(&to_parent, from_parent)
+                .and_then(|(head, rest)| head.parse::<usize>().ok().map(|i| (i, rest)))
+                .filter(|&(i, _)| i > removed);
+            if let Some((i, rest)) = shifted {
+                to_parent = format!("{}/{}{}", from_parent, i - 1, rest);
+            }
+        }
+        let target = if to_is_array {
+            self.insert(&to_parent, to_idx);
+            format!("{}/{}", to_parent, to_idx)
+        } else {
+            join_pointer(&to_parent, &key)
+        };
+        for (rest, open) in moved {
+            self.open.insert(format!("{}{}", target, rest), open);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::edit::apply_edit;
+
+    /// 记录 open 中的节点为展开，应用编辑后检查每条记录仍指向同一个节点（以 id 标识），
+    /// 返回改写后的路径
+    fn remapped(root: &Value, open: &[&str], edit: JsonEdit) -> Vec<String> {
+        let mut state = ExpansionState::default();
+        for pointer in open {
+            state.observe(pointer, true);
+        }
+        state.remap(root, &edit);
+        let mut new_root = root.clone();
+        apply_edit(&mut new_root, edit);
+
+        let ids: HashMap<&Value, Vec<&str>> = open.iter().fold(HashMap::new(), |mut ids, p| {
+            ids.entry(&root.pointer(p).unwrap()["id"]).or_default().push(p);
+            ids
+        });
+        let mut pointers: Vec<String> = state.open.into_keys().collect();
+        pointers.sort();
+        for pointer in &pointers {
+            let node = new_root.pointer(pointer).unwrap_or_else(|| panic!("{pointer} 不存在"));
+            assert!(ids.contains_key(&node["id"]), "{pointer} 指向了其他节点");
+        }
+        pointers
+    }
+
+    fn doc() -> Value {
+        json!({
+            "id": 0,
+            "list": [{"id": 1}, {"id": 2, "sub": {"id": 3}}, {"id": 4}],
+            "obj": {"id": 5, "a/b": {"id": 6}, "k": {"id": 7}},
+            "other": {"id": 8, "k": {"id": 9}}
+        })
+    }
+
+    #[test]
+    fn delete_shifts_later_indices() {
+        let open = ["/list/0", "/list/1", "/list/1/sub", "/list/2"];
+        let edit = JsonEdit::DeleteFromArray { array_pointer: "/list".into(), idx: 0 };
+        assert_eq!(remapped(&doc(), &open, edit), ["/list/0", "/list/0/sub", "/list/1"]);
+
+        let edit = JsonEdit::DeleteFromObject { object_pointer: "/obj".into(), key: "a/b".into() };
+        assert_eq!(remapped(&doc(), &["/obj", "/obj/a~1b", "/obj/k"], edit), ["/obj", "/obj/k"]);
+    }
+
+    #[test]
+    fn insert_and_duplicate_shift_indices() {
+        let open = ["/list/0", "/list/1/sub"];
+        let edit = JsonEdit::InsertValue {
+            at: Placement::Before("/list/1".into()),
+            key: None,
+            value: json!({"id": 10}),
+        };
+        assert_eq!(remapped(&doc(), &open, edit), ["/list/0", "/list/2/sub"]);
+
+        let edit = JsonEdit::Duplicate { pointer: "/list/0".into() };
+        assert_eq!(remapped(&doc(), &open, edit), ["/list/0", "/list/2/sub"]);
+    }
+
+    #[test]
+    fn move_up_and_down_swap_states() {
+        let open = ["/list/1", "/list/1/sub"];
+        let edit = JsonEdit::MoveUp { pointer: "/list/1".into() };
+        assert_eq!(remapped(&doc(), &open, edit), ["/list/0", "/list/0/sub"]);
+        let edit = JsonEdit::MoveDown { pointer: "/list/2".into() };
+        assert_eq!(remapped(&doc(), &["/list/2"], edit), ["/list/2"]);
+    }
+
+    #[test]
+    fn rename_moves_subtree() {
+        let edit = JsonEdit::EditObjectKey {
+            object_pointer: "/obj".into(),
+            old_key: "a/b".into(),
+            new_key: "c~d".into(),
+        };
+        assert_eq!(remapped(&doc(), &["/obj/a~1b", "/obj/k"], edit), ["/obj/c~0d", "/obj/k"]);
+    }
+
+    #[test]
+    fn move_node_follows_the_node() {
+        let open = ["/list/0", "/list/1", "/list/1/sub", "/list/2"];
+        let edit = JsonEdit::MoveNode { from: "/list/0".into(), to: Placement::After("/list/2".into()) };
+        assert_eq!(remapped(&doc(), &open, edit), ["/list/0", "/list/0/sub", "/list/1", "/list/2"]);
+
+        let edit = JsonEdit::MoveNode { from: "/list/1/sub".into(), to: Placement::Before("/list/0".into()) };
+        assert_eq!(remapped(&doc(), &open, edit), ["/list/0", "/list/1", "/list/2", "/list/3"]);
+
+        let edit = JsonEdit::MoveNode { from: "/obj/a~1b".into(), to: Placement::Append("/list".into()) };
+        assert_eq!(remapped(&doc(), &["/obj/a~1b", "/list/2"], edit), ["/list/2", "/list/3"]);
+    }
+
+    #[test]
+    fn move_into_object_uses_the_final_key() {
+        // 键名冲突时改名
+        let edit = JsonEdit::MoveNode { from: "/obj/k".into(), to: Placement::Append("/other".into()) };
+        assert_eq!(remapped(&doc(), &["/obj/k", "/other/k"], edit), ["/other/k", "/other/k_1"]);
+        // 数组元素移入对象时使用默认键名
+        let edit = JsonEdit::MoveNode { from: "/list/2".into(), to: Placement::Before("/obj/k".into()) };
+        assert_eq!(remapped(&doc(), &["/list/2"], edit), ["/obj/new_key"]);
+    }
+
+    #[test]
+    fn edit_value_drops_descendants() {
+        let edit = JsonEdit::EditValue { pointer: "/list/1".into(), new_value: json!({"id": 2}) };
+        assert_eq!(remapped(&doc(), &["/list/1", "/list/1/sub"], edit), ["/list/1"]);
+    }
+
+    #[test]
+    fn within_and_child_of() {
+        assert!(within("/a/b", "/a"));
+        assert!(within("/a", "/a"));
+        assert!(!within("/ab", "/a"));
+        assert_eq!(child_of("/a/3/x", "/a"), Some(("3", "/x")));
+        assert_eq!(child_of("/a", "/a"), None);
+        assert_eq!(child_of("/ab/1", "/a"), None);
+    }
+}
@@ -0,0 +1,259 @@
+use appkit::theme::Palette;
+use eframe::egui::Color32;
+use serde_json::Value;
+
+use crate::document::to_string_with_indent;
+use crate::highlight::{tokenize, TokenKind};
+use crate::i18n::tr;
+
+/// Markdown 中按成员拆分为折叠区块的最大层数，更深的子树直接放在代码块里
+const SECTION_DEPTH: usize = 3;
+
+/// 导出格式
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    /// 带语法着色的独立 HTML 页面
+    Html,
+    /// 按成员拆分为 `<details>` 折叠区块的 Markdown
+    Markdown,
+    /// 树视图的截图
+    Png,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Html, ExportFormat::Markdown, ExportFormat::Png];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "HTML",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Png => tr!("PNG 图片"),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Png => "png",
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn css_color(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+/// 词法单元的 CSS 类名
+fn css_rules(palette: &Palette) -> String {
+    format!(
+        ".k{{color:{}}} .s{{color:{}}} .n{{color:{}}} .l{{color:{}}} .p{{color:{}}}",
+        css_color(palette.key),
+        css_color(palette.string),
+        css_color(palette.number),
+        css_color(palette.literal),
+        css_color(palette.punct),
+    )
+}
+
+/// 导出为独立的 HTML 页面，按系统深浅色分别使用 light、dark 配色
+pub fn to_html(value: &Value, title: &str, indent_spaces: usize, light: &Palette, dark: &Palette) -> String {
+    let text = to_string_with_indent(value, indent_spaces);
+    let mut code = String::with_capacity(text.len() * 2);
+    for token in tokenize(&text) {
+        let class = match token.kind {
+            TokenKind::Key => "k",
+            TokenKind::String => "s",
+            TokenKind::Number => "n",
+            TokenKind::Literal => "l",
+            TokenKind::Bracket | TokenKind::Punct => "p",
+            TokenKind::Whitespace | TokenKind::Error => "",
+        };
+        let piece = escape_html(&text[token.range]);
+        if class.is_empty() {
+            code.push_str(&piece);
+        } else {
+            code.push_str(&format!("<span class=\"{}\">{}</span>", class, piece));
+        }
+    }
+    let title = escape_html(title);
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ margin: 0; padding: 16px 24px; background: #ffffff; color: #1e1e1e; font-family: sans-serif; }}
+pre {{ font-family: ui-monospace, Consolas, "Courier New", monospace; font-size: 13px; line-height: 1.45; }}
+{light}
+@media (prefers-color-scheme: dark) {{
+body {{ background: #1e1e1e; color: #d4d4d4; }}
+{dark}
+}}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<pre><code>{code}</code></pre>
+</body>
+</html>
+"#,
+        light = css_rules(light),
+        dark = css_rules(dark),
+    )
+}
+
+/// 导出为 Markdown：对象和数组的每个成员是一个可折叠的 `<details>` 区块
+pub fn to_markdown(value: &Value, title: &str, indent_spaces: usize) -> String {
+    let mut out = format!("# {}\n\n", title);
+    if has_nested(value) {
+        write_sections(value, 0, indent_spaces, &mut out);
+    } else {
+        write_code_block(value, indent_spaces, &mut out);
+    }
+    out
+}
+
+/// 成员中含有非空的对象或数组
+fn has_nested(value: &Value) -> bool {
+    children(value).iter().any(|(_, child)| is_container(child))
+}
+
+fn is_container(value: &Value) -> bool {
+    match value {
+        Value::Array(arr) => !arr.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => false,
+    }
+}
+
+/// 对象的键名或数组的下标，与成员一一对应
+fn children(value: &Value) -> Vec<(String, &Value)> {
+    match value {
+        Value::Object(map) => map.iter().map(|(k, v)| (k.clone(), v)).collect(),
+        Value::Array(arr) => arr.iter().enumerate().map(|(i, v)| (format!("[{}]", i), v)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn summary(value: &Value) -> String {
+    match value {
+        Value::Object(map) => tr!("对象，{} 个成员", map.len()),
+        Value::Array(arr) => tr!("数组，{} 个元素", arr.len()),
+        _ => String::new(),
+    }
+}
+
+/// 简单值写成列表项，对象和数组写成折叠区块
+fn write_sections(value: &Value, depth: usize, indent_spaces: usize, out: &mut String) {
+    let mut in_list = false;
+    for (name, child) in children(value) {
+        if !is_container(child) {
+            out.push_str(&format!("- {}: {}\n", inline_code(&name), inline_code(&child.to_string())));
+            in_list = true;
+            continue;
+        }
+        // 列表后必须空一行，否则 HTML 标签会被当作列表项的一部分
+        if in_list {
+            out.push('\n');
+            in_list = false;
+        }
+        out.push_str(&format!(
+            "<details>\n<summary><code>{}</code> — {}</summary>\n\n",
+            escape_html(&name),
+            summary(child)
+        ));
+        if depth + 1 < SECTION_DEPTH && has_nested(child) {
+            write_sections(child, depth + 1, indent_spaces, out);
+        } else {
+            write_code_block(child, indent_spaces, out);
+        }
+        out.push_str("</details>\n\n");
+    }
+    if in_list {
+        out.push('\n');
+    }
+}
+
+/// 行内代码；内容含反引号时用更长的反引号包围
+fn inline_code(text: &str) -> String {
+    let fence = "`".repeat(longest_backtick_run(text) + 1);
+    if text.starts_with('`') || text.ends_with('`') {
+        format!("{} {} {}", fence, text, fence)
+    } else {
+        format!("{}{}{}", fence, text, fence)
+    }
+}
+
+/// 代码块的围栏比内容中最长的连续反引号更长
+fn write_code_block(value: &Value, indent_spaces: usize, out: &mut String) {
+    let text = to_string_with_indent(value, indent_spaces);
+    let fence = "`".repeat((longest_backtick_run(&text) + 1).max(3));
+    out.push_str(&format!("{}json\n{}\n{}\n\n", fence, text, fence));
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn fences_are_longer_than_backtick_runs() {
+        assert_eq!(inline_code("a"), "`a`");
+        assert_eq!(inline_code("a`b"), "``a`b``");
+        assert_eq!(inline_code("`a"), "`` `a ``");
+        let md = to_markdown(&json!(["x````y"]), "T", 2);
+        assert_eq!(md, "# T\n\n`````json\n[\n  \"x````y\"\n]\n`````\n\n");
+        let md = to_markdown(&json!({"k": "`"}), "T", 2);
+        assert_eq!(md, "# T\n\n```json\n{\n  \"k\": \"`\"\n}\n```\n\n");
+    }
+
+    #[test]
+    fn markdown_nests_details_up_to_section_depth() {
+        let value = json!({
+            "n": 1,
+            "<a>": {"b": {"c": {"d": [1]}}, "e": []},
+            "list": [{"x": "``"}]
+        });
+        let md = to_markdown(&value, "Doc", 2);
+        let expected = "# Doc\n\n\
+            - `n`: `1`\n\n\
+            <details>\n<summary><code>&lt;a&gt;</code> — 对象，2 个成员</summary>\n\n\
+            <details>\n<summary><code>b</code> — 对象，1 个成员</summary>\n\n\
+            <details>\n<summary><code>c</code> — 对象，1 个成员</summary>\n\n\
+            ```json\n{\n  \"d\": [\n    1\n  ]\n}\n```\n\n\
+            </details>\n\n\
+            </details>\n\n\
+            - `e`: `[]`\n\n\
+            </details>\n\n\
+            <details>\n<summary><code>list</code> — 数组，1 个元素</summary>\n\n\
+            <details>\n<summary><code>[0]</code> — 对象，1 个成员</summary>\n\n\
+            ```json\n{\n  \"x\": \"``\"\n}\n```\n\n\
+            </details>\n\n\
+            </details>\n\n";
+        assert_eq!(md, expected);
+        assert_eq!(md.matches("<details>").count(), md.matches("</details>").count());
+    }
+
+    #[test]
+    fn html_escapes_title_keys_and_strings() {
+        let value = json!({"<k>": "a & \"b\" </code>"});
+        let html = to_html(&value, "<script>x</script>", 2, &Palette::LIGHT, &Palette::DARK);
+        assert!(html.contains("<title>&lt;script&gt;x&lt;/script&gt;</title>"));
+        assert!(html.contains("<span class=\"k\">&quot;&lt;k&gt;&quot;</span>"));
+        assert!(html.contains("<span class=\"s\">&quot;a &amp; \\&quot;b\\&quot; &lt;/code&gt;&quot;</span>"));
+        assert!(!html.contains("<script>"));
+        assert_eq!(html.matches("</code>").count(), 1);
+        assert!(html.contains(&format!(".k{{color:{}}}", css_color(Palette::DARK.key))));
+    }
+}
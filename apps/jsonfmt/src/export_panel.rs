@@ -0,0 +1,281 @@
+use std::path::{Path, PathBuf};
+
+use appkit::theme::Palette;
+use eframe::egui;
+use egui_json_tree::{DefaultExpand, JsonTree, JsonTreeStyle};
+use serde_json::Value;
+
+use crate::app::tree_visuals;
+use crate::bulk_panel::text_hash;
+use crate::export::{self, ExportFormat};
+use crate::i18n::tr;
+use crate::redact::Redactor;
+use crate::settings::Settings;
+
+/// 预览区域的最大高度
+const PREVIEW_HEIGHT: f32 = 360.0;
+
+/// 截图命令的标记，用于从输入事件中认出导出面板请求的截图
+struct ExportShot;
+
+/// 导出面板：把文档或子树（可先脱敏）导出为 HTML、Markdown 或树视图的 PNG 截图
+pub struct ExportPanel {
+    /// 导出的子树，空字符串表示整个文档
+    pointer: String,
+    format: ExportFormat,
+    /// 导出前使用的脱敏配置下标，None 表示不脱敏
+    redact: Option<usize>,
+    /// 待导出的值及生成时的输入哈希；取值失败时为错误信息
+    cache: Option<((u64, u64), Result<Value, String>)>,
+    /// 上次保存或复制的结果
+    message: Option<Result<String, String>>,
+    /// 预览区域在屏幕上的位置，截图时按它裁剪
+    preview_rect: egui::Rect,
+    /// 等待截图写入的文件
+    capture: Option<PathBuf>,
+}
+
+impl ExportPanel {
+    pub fn new(pointer: String) -> Self {
+        Self {
+            pointer,
+            format: ExportFormat::Html,
+            redact: None,
+            cache: None,
+            message: None,
+            preview_rect: egui::Rect::NOTHING,
+            capture: None,
+        }
+    }
+
+    /// 取出待导出的值：子树，按需脱敏
+    fn export_value(&self, root: &Value, settings: &Settings) -> Result<Value, String> {
+        let value = root
+            .pointer(&self.pointer)
+            .ok_or_else(|| tr!("路径 {} 不存在", self.pointer))?;
+        match self.redact.and_then(|i| settings.redaction_profiles.get(i)) {
+            Some(profile) => Ok(Redactor::new(profile)?.redacted(value)),
+            None => Ok(value.clone()),
+        }
+    }
+
+    /// 输入、导出范围或脱敏配置变化时重新生成待导出的值
+    fn refresh(&mut self, root: &Value, text: &str, settings: &Settings) {
+        let profile = self
+            .redact
+            .and_then(|i| settings.redaction_profiles.get(i))
+            .and_then(|p| serde_json::to_string(p).ok())
+            .unwrap_or_default();
+        let key = (text_hash(text), text_hash(&format!("{}\n{}", self.pointer, profile)));
+        if self.cache.as_ref().is_none_or(|(k, _)| *k != key) {
+            self.cache = Some((key, self.export_value(root, settings)));
+        }
+    }
+
+    /// 导出内容的标题：文档名，导出子树时附上路径
+    fn heading(&self, title: &str) -> String {
+        if self.pointer.is_empty() {
+            title.to_owned()
+        } else {
+            format!("{} — {}", title, self.pointer)
+        }
+    }
+
+    /// HTML 或 Markdown 文本
+    fn render(&self, value: &Value, title: &str, settings: &Settings, indent_spaces: usize) -> String {
+        let heading = self.heading(title);
+        match self.format {
+            ExportFormat::Markdown => export::to_markdown(value, &heading, indent_spaces),
+            _ => export::to_html(value, &heading, indent_spaces, &settings.theme.light, &settings.theme.dark),
+        }
+    }
+
+    /// 选择保存位置；PNG 先请求截图，收到截图后再写入
+    fn save(&mut self, ctx: &egui::Context, value: &Value, title: &str, settings: &Settings, indent_spaces: usize) {
+        let extension = self.format.extension();
+        let stem = Path::new(title).file_stem().map_or_else(|| title.to_owned(), |s| s.to_string_lossy().into_owned());
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(self.format.label(), &[extension])
+            .set_file_name(format!("{}.{}", stem, extension))
+            .save_file()
+        else {
+            return;
+        };
+        if self.format == ExportFormat::Png {
+            self.capture = Some(path);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Screenshot(egui::UserData::new(ExportShot)));
+            return;
+        }
+        let text = self.render(value, title, settings, indent_spaces);
+        self.message = Some(
+            std::fs::write(&path, text)
+                .map(|_| tr!("已保存到 {}", path.display()))
+                .map_err(|e| tr!("保存失败：{}", e)),
+        );
+    }
+
+    /// 收到截图后裁剪出预览区域并写入 PNG
+    fn poll_capture(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.capture.clone() else {
+            return;
+        };
+        let shot = ctx.input(|i| {
+            i.raw.events.iter().find_map(|e| match e {
+                egui::Event::Screenshot { user_data, image, .. }
+                    if user_data.data.as_ref().is_some_and(|d| d.is::<ExportShot>()) =>
+                {
+                    Some(image.clone())
+                }
+                _ => None,
+            })
+        });
+        let Some(image) = shot else {
+            ctx.request_repaint();
+            return;
+        };
+        // 预览区域有一部分在窗口外时截图不完整，宁可不导出
+        let pixels_per_point = ctx.pixels_per_point();
+        let screen = egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(image.width() as f32, image.height() as f32) / pixels_per_point,
+        );
+        self.capture = None;
+        if !self.preview_rect.is_positive() || !screen.contains_rect(self.preview_rect) {
+            self.message = Some(Err(tr!("预览区域超出窗口，无法截取完整内容").to_owned()));
+            return;
+        }
+        let image = image.region(&self.preview_rect, Some(pixels_per_point));
+        let rgba: Vec<u8> = image.pixels.iter().flat_map(|c| c.to_srgba_unmultiplied()).collect();
+        let result = image::save_buffer(
+            &path,
+            &rgba,
+            image.size[0] as u32,
+            image.size[1] as u32,
+            image::ExtendedColorType::Rgba8,
+        );
+        self.message = Some(
+            result
+                .map(|_| tr!("已保存到 {}", path.display()))
+                .map_err(|e| tr!("保存失败：{}", e)),
+        );
+    }
+
+    /// 绘制面板内容；title 为文档名
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        root: Option<&Value>,
+        text: &str,
+        title: &str,
+        settings: &Settings,
+        indent_spaces: usize,
+    ) {
+        self.poll_capture(ui.ctx());
+        let Some(root) = root else {
+            ui.label(tr!("没有可导出的 JSON"));
+            return;
+        };
+        self.refresh(root, text, settings);
+
+        egui::Grid::new("export_options").num_columns(2).show(ui, |ui| {
+            ui.label(tr!("范围："));
+            ui.horizontal(|ui| {
+                if self.pointer.is_empty() {
+                    ui.label(tr!("整个文档"));
+                } else {
+                    ui.monospace(&self.pointer);
+                    if ui.small_button(tr!("导出整个文档")).clicked() {
+                        self.pointer.clear();
+                    }
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr!("格式："));
+            ui.horizontal(|ui| {
+                for format in ExportFormat::ALL {
+                    ui.selectable_value(&mut self.format, format, format.label());
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr!("脱敏："));
+            let selected = self
+                .redact
+                .and_then(|i| settings.redaction_profiles.get(i))
+                .map_or(tr!("不脱敏"), |p| p.name.as_str());
+            egui::ComboBox::from_id_salt("export_redact")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.redact, None, tr!("不脱敏"));
+                    for (i, profile) in settings.redaction_profiles.iter().enumerate() {
+                        ui.selectable_value(&mut self.redact, Some(i), profile.name.as_str());
+                    }
+                })
+                .response
+                .on_hover_text(tr!("导出前按所选的脱敏配置处理，不影响文档本身"));
+            ui.end_row();
+        });
+        // 选项可能改变了待导出的值
+        self.refresh(root, text, settings);
+
+        // 暂时取出缓存，避免绘制时借用 self
+        let Some((key, result)) = self.cache.take() else {
+            return;
+        };
+        let value = match result {
+            Ok(value) => value,
+            Err(e) => {
+                ui.colored_label(Palette::of(ui).error, &e);
+                self.cache = Some((key, Err(e)));
+                return;
+            }
+        };
+
+        ui.separator();
+        ui.label(tr!("预览：")).on_hover_text(tr!("PNG 图片截取预览区域，内容须完整显示在预览区域内"));
+        let palette = Palette::of(ui);
+        let output = egui::ScrollArea::both()
+            .id_salt("export_preview")
+            .max_height(PREVIEW_HEIGHT)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                JsonTree::new("export_preview_tree", &value)
+                    .default_expand(DefaultExpand::ToLevel(settings.tree_depth))
+                    .style(JsonTreeStyle::new().visuals(tree_visuals(&palette)))
+                    .show(ui);
+            });
+        self.preview_rect = output.inner_rect;
+        // 内容超出预览区域时截图不完整，不允许导出 PNG；留一点余量避免浮点误差
+        let overflow = output.content_size - output.inner_rect.size();
+        let png_blocked = self.format == ExportFormat::Png && (overflow.x > 0.5 || overflow.y > 0.5);
+        if png_blocked {
+            ui.colored_label(palette.error, tr!("内容超出预览区域，PNG 只能导出完整可见的内容：请折叠节点、缩小导出范围或放大窗口"));
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            let saving = self.capture.is_some();
+            if ui
+                .add_enabled(!saving && !png_blocked, egui::Button::new(tr!("💾 保存…")))
+                .clicked()
+            {
+                self.save(ui.ctx(), &value, title, settings, indent_spaces);
+            }
+            if self.format != ExportFormat::Png && ui.button(tr!("📋 复制")).clicked() {
+                ui.ctx().copy_text(self.render(&value, title, settings, indent_spaces));
+                self.message = Some(Ok(tr!("已复制").to_owned()));
+            }
+            match &self.message {
+                Some(Ok(message)) => {
+                    ui.colored_label(palette.success, message);
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(palette.error, e);
+                }
+                None => {}
+            }
+        });
+        self.cache = Some((key, Ok(value)));
+    }
+}
@@ -0,0 +1,231 @@
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::highlight::{tokenize, TokenKind};
+use crate::i18n::tr;
+
+/// 查找与替换的作用范围
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FindScope {
+    /// 整个文本
+    #[default]
+    All,
+    /// 只在对象的键名中
+    Keys,
+    /// 只在字符串值中
+    StringValues,
+}
+
+impl FindScope {
+    pub const ALL: [FindScope; 3] = [FindScope::All, FindScope::Keys, FindScope::StringValues];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FindScope::All => tr!("全部文本"),
+            FindScope::Keys => tr!("仅键名"),
+            FindScope::StringValues => tr!("仅字符串值"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// 把查找内容视为正则表达式，替换内容中可用 $1、${name} 引用捕获组
+    pub regex: bool,
+    pub scope: FindScope,
+}
+
+/// 根据选项编译查找内容
+pub fn compile(query: &str, options: &FindOptions) -> Result<Regex, String> {
+    let pattern = if options.regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    let pattern = if options.whole_word {
+        format!(r"\b(?:{})\b", pattern)
+    } else {
+        pattern
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// 查找范围对应的文本区域；键名和字符串值只取引号内的部分
+fn regions(text: &str, scope: FindScope) -> Vec<Range<usize>> {
+    let kind = match scope {
+        FindScope::All => return std::iter::once(0..text.len()).collect(),
+        FindScope::Keys => TokenKind::Key,
+        FindScope::StringValues => TokenKind::String,
+    };
+    tokenize(text)
+        .into_iter()
+        .filter(|t| t.kind == kind && t.range.len() >= 2 && text[t.range.clone()].ends_with('"'))
+        .map(|t| t.range.start + 1..t.range.end - 1)
+        .collect()
+}
+
+/// 查找所有匹配（字节范围），忽略空匹配
+pub fn find_all(text: &str, re: &Regex, scope: FindScope) -> Vec<Range<usize>> {
+    regions(text, scope)
+        .into_iter()
+        .flat_map(|region| {
+            re.find_iter(&text[region.clone()])
+                .filter(|m| !m.is_empty())
+                .map(move |m| region.start + m.start()..region.start + m.end())
+        })
+        .collect()
+}
+
+/// 替换匹配，返回新文本和替换的个数；only 指定时只替换该位置的匹配
+///
+/// 限定在键名或字符串值中替换时，替换结果必须仍是合法的 JSON 字符串，否则整体放弃。
+pub fn replace(
+    text: &str,
+    re: &Regex,
+    options: &FindOptions,
+    replacement: &str,
+    only: Option<&Range<usize>>,
+) -> Result<(String, usize), String> {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut count = 0;
+    for region in regions(text, options.scope) {
+        let haystack = &text[region.clone()];
+        let mut piece = String::new();
+        let mut piece_last = 0;
+        for caps in re.captures_iter(haystack) {
+            let Some(m) = caps.get(0).filter(|m| !m.is_empty()) else {
+                continue;
+            };
+            let range = region.start + m.start()..region.start + m.end();
+            if only.is_some_and(|only| *only != range) {
+                continue;
+            }
+            piece.push_str(&haystack[piece_last..m.start()]);
+            if options.regex {
+                caps.expand(replacement, &mut piece);
+            } else {
+                piece.push_str(replacement);
+            }
+            piece_last = m.end();
+            count += 1;
+        }
+        if piece_last == 0 {
+            continue;
+        }
+        piece.push_str(&haystack[piece_last..]);
+        if options.scope != FindScope::All && serde_json::from_str::<String>(&format!("\"{}\"", piece)).is_err() {
+            return Err(tr!("替换后 \"{}\" 不是合法的 JSON 字符串，请检查引号和转义", piece));
+        }
+        out.push_str(&text[last..region.start]);
+        out.push_str(&piece);
+        last = region.end;
+    }
+    out.push_str(&text[last..]);
+    Ok((out, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = r#"{"name": "name", "tags": ["Name", "renamed"], "count": 1}"#;
+
+    fn options(scope: FindScope) -> FindOptions {
+        FindOptions {
+            case_sensitive: true,
+            scope,
+            ..FindOptions::default()
+        }
+    }
+
+    fn replace_all(query: &str, options: &FindOptions, replacement: &str) -> Result<(String, usize), String> {
+        let re = compile(query, options).unwrap();
+        replace(TEXT, &re, options, replacement, None)
+    }
+
+    #[test]
+    fn find_in_scope() {
+        let re = compile("name", &options(FindScope::All)).unwrap();
+        let texts = |scope| -> Vec<usize> { find_all(TEXT, &re, scope).into_iter().map(|r| r.start).collect() };
+        assert_eq!(texts(FindScope::All).len(), 3);
+        assert_eq!(texts(FindScope::Keys), [2]);
+        assert_eq!(texts(FindScope::StringValues), [10, TEXT.find("renamed").unwrap() + 2]);
+    }
+
+    #[test]
+    fn replace_only_keys_or_values() {
+        let (out, count) = replace_all("name", &options(FindScope::Keys), "title").unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(out, r#"{"title": "name", "tags": ["Name", "renamed"], "count": 1}"#);
+
+        let (out, count) = replace_all("name", &options(FindScope::StringValues), "x").unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(out, r#"{"name": "x", "tags": ["Name", "rexd"], "count": 1}"#);
+
+        // 数字不在字符串值范围内
+        let (_, count) = replace_all("1", &options(FindScope::StringValues), "2").unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn case_and_whole_word() {
+        let insensitive = FindOptions {
+            whole_word: true,
+            ..FindOptions::default()
+        };
+        let (out, count) = replace_all("name", &insensitive, "id").unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(out, r#"{"id": "id", "tags": ["id", "renamed"], "count": 1}"#);
+    }
+
+    #[test]
+    fn regex_captures() {
+        let regex = FindOptions {
+            regex: true,
+            ..options(FindScope::StringValues)
+        };
+        let (out, _) = replace_all(r"re(\w+)", &regex, "${1}_again").unwrap();
+        assert_eq!(out, r#"{"name": "name", "tags": ["Name", "named_again"], "count": 1}"#);
+        // 非正则模式下 $1 按原样插入
+        let (out, _) = replace_all("renamed", &options(FindScope::All), "$1").unwrap();
+        assert!(out.contains(r#""$1""#));
+        assert!(compile("(", &regex).is_err());
+    }
+
+    #[test]
+    fn replace_single_match() {
+        let options = options(FindScope::All);
+        let re = compile("name", &options).unwrap();
+        let second = find_all(TEXT, &re, options.scope)[1].clone();
+        let (out, count) = replace(TEXT, &re, &options, "N", Some(&second)).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(out, r#"{"name": "N", "tags": ["Name", "renamed"], "count": 1}"#);
+    }
+
+    #[test]
+    fn scoped_replace_must_stay_valid_string() {
+        assert!(replace_all("name", &options(FindScope::Keys), "a\"b").is_err());
+        assert!(replace_all("name", &options(FindScope::StringValues), "a\\").is_err());
+        let (out, _) = replace_all("name", &options(FindScope::Keys), "a\\\"b").unwrap();
+        assert!(out.starts_with(r#"{"a\"b": "name""#));
+        // 全文范围不做检查
+        assert!(replace_all("name", &options(FindScope::All), "\"").is_ok());
+    }
+
+    #[test]
+    fn escaped_quotes_inside_strings() {
+        let text = r#"{"k": "say \"hi\""}"#;
+        let options = options(FindScope::StringValues);
+        let re = compile("hi", &options).unwrap();
+        let (out, count) = replace(text, &re, &options, "bye", None).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(out, r#"{"k": "say \"bye\""}"#);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+use appkit::theme::Palette;
+use eframe::egui;
+
+use crate::find::{self, FindOptions, FindScope};
+use crate::i18n::tr;
+
+/// 查找栏上的替换操作
+pub enum FindAction {
+    ReplaceCurrent,
+    ReplaceAll,
+}
+
+/// 文本编辑器的查找/替换栏（Ctrl+F / Ctrl+H）
+#[derive(Default)]
+pub struct FindBar {
+    pub visible: bool,
+    pub show_replace: bool,
+    pub query: String,
+    pub replacement: String,
+    pub options: FindOptions,
+    /// 当前匹配的下标
+    current: usize,
+    matches: Vec<Range<usize>>,
+    /// 查找内容或替换结果的错误
+    error: Option<String>,
+    /// 上次替换的结果提示
+    message: Option<String>,
+    /// 匹配结果缓存的键：原文、查找内容、选项和是否打开的哈希
+    cache_key: u64,
+    /// 需要把文本框滚动到当前匹配
+    scroll_pending: bool,
+    focus_pending: bool,
+}
+
+impl FindBar {
+    /// 打开查找栏；replace 为 true 时同时显示替换
+    pub fn open(&mut self, replace: bool) {
+        self.visible = true;
+        self.show_replace = replace;
+        self.focus_pending = true;
+    }
+
+    /// 原文、查找内容或选项变化后重新查找
+    pub fn refresh(&mut self, text: &str) {
+        let mut hasher = DefaultHasher::new();
+        (text, &self.query, &self.options, self.visible).hash(&mut hasher);
+        let key = hasher.finish();
+        if key == self.cache_key {
+            return;
+        }
+        self.cache_key = key;
+        self.error = None;
+        self.matches = if !self.visible || self.query.is_empty() {
+            Vec::new()
+        } else {
+            match find::compile(&self.query, &self.options) {
+                Ok(re) => find::find_all(text, &re, self.options.scope),
+                Err(e) => {
+                    self.error = Some(e);
+                    Vec::new()
+                }
+            }
+        };
+        self.current = self.current.min(self.matches.len().saturating_sub(1));
+    }
+
+    /// 所有匹配及当前匹配的下标，用于在文本框中高亮
+    pub fn highlights(&self) -> (&[Range<usize>], Option<usize>) {
+        if self.visible {
+            (&self.matches, (!self.matches.is_empty()).then_some(self.current))
+        } else {
+            (&[], None)
+        }
+    }
+
+    /// 取出待滚动到的匹配位置
+    pub fn take_scroll(&mut self) -> Option<Range<usize>> {
+        if !std::mem::take(&mut self.scroll_pending) {
+            return None;
+        }
+        self.matches.get(self.current).cloned()
+    }
+
+    fn step(&mut self, forward: bool) {
+        let len = self.matches.len();
+        if len == 0 {
+            return;
+        }
+        self.current = if forward { (self.current + 1) % len } else { (self.current + len - 1) % len };
+        self.scroll_pending = true;
+    }
+
+    /// 执行替换，返回新文本；替换结果不合法时记录错误并返回 None
+    pub fn replace(&mut self, text: &str, action: FindAction) -> Option<String> {
+        let re = match find::compile(&self.query, &self.options) {
+            Ok(re) => re,
+            Err(e) => {
+                self.error = Some(e);
+                return None;
+            }
+        };
+        let only = match action {
+            FindAction::ReplaceCurrent => Some(self.matches.get(self.current)?),
+            FindAction::ReplaceAll => None,
+        };
+        match find::replace(text, &re, &self.options, &self.replacement, only) {
+            Ok((_, 0)) => None,
+            Ok((new_text, count)) => {
+                self.message = Some(tr!("已替换 {} 处", count));
+                // 替换当前匹配后停留在原位置，即下一个匹配
+                self.scroll_pending = true;
+                Some(new_text)
+            }
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    /// 绘制查找栏，返回用户触发的替换操作
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<FindAction> {
+        let mut action = None;
+        let (enter, shift) = ui.input(|i| (i.key_pressed(egui::Key::Enter), i.modifiers.shift));
+
+        ui.horizontal(|ui| {
+            let query = ui.add(
+                egui::TextEdit::singleline(&mut self.query)
+                    .hint_text(tr!("查找"))
+                    .desired_width(180.0),
+            );
+            if std::mem::take(&mut self.focus_pending) {
+                query.request_focus();
+            }
+            if query.changed() {
+                self.message = None;
+                self.current = 0;
+                self.scroll_pending = true;
+            }
+            // 回车查找下一个，Shift+回车查找上一个
+            if query.lost_focus() && enter {
+                self.step(!shift);
+                query.request_focus();
+            }
+
+            if ui.button("⬆").on_hover_text(tr!("上一个 (Shift+Enter)")).clicked() {
+                self.step(false);
+            }
+            if ui.button("⬇").on_hover_text(tr!("下一个 (Enter)")).clicked() {
+                self.step(true);
+            }
+            if self.matches.is_empty() {
+                ui.label(if self.query.is_empty() { "" } else { tr!("无结果") });
+            } else {
+                ui.label(format!("{}/{}", self.current + 1, self.matches.len()));
+            }
+
+            ui.toggle_value(&mut self.options.case_sensitive, "Aa").on_hover_text(tr!("区分大小写"));
+            ui.toggle_value(&mut self.options.whole_word, "ab").on_hover_text(tr!("全字匹配"));
+            ui.toggle_value(&mut self.options.regex, ".*").on_hover_text(tr!("正则表达式"));
+            egui::ComboBox::from_id_salt("find_scope")
+                .selected_text(self.options.scope.label())
+                .show_ui(ui, |ui| {
+                    for scope in FindScope::ALL {
+                        ui.selectable_value(&mut self.options.scope, scope, scope.label());
+                    }
+                });
+            ui.toggle_value(&mut self.show_replace, tr!("替换"));
+            if ui.button("✖").on_hover_text(tr!("关闭 (Esc)")).clicked() {
+                self.visible = false;
+            }
+        });
+
+        if self.show_replace {
+            ui.horizontal(|ui| {
+                let hint = if self.options.regex { tr!("替换为（可用 $1、${name}）") } else { tr!("替换为") };
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.replacement)
+                        .hint_text(hint)
+                        .desired_width(180.0),
+                );
+                let has_matches = !self.matches.is_empty();
+                if ui.add_enabled(has_matches, egui::Button::new(tr!("替换"))).clicked() {
+                    action = Some(FindAction::ReplaceCurrent);
+                }
+                if ui.add_enabled(has_matches, egui::Button::new(tr!("全部替换"))).clicked() {
+                    action = Some(FindAction::ReplaceAll);
+                }
+                if let Some(message) = &self.message {
+                    ui.label(message);
+                }
+            });
+        }
+
+        if let Some(err) = &self.error {
+            ui.colored_label(Palette::of(ui).error, err);
+        }
+        action
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// 词法单元类型
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenKind {
+    Key,
+    String,
+    Number,
+    Literal,
+    Bracket,
+    Punct,
+    Whitespace,
+    /// 无法识别的内容（例如输入到一半的 JSON）
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub range: Range<usize>,
+}
+
+/// 把文本切分为词法单元，不要求是合法 JSON，用于语法着色
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let bytes = text.as_bytes();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let kind = match bytes[pos] {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                while matches!(bytes.get(pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+                    pos += 1;
+                }
+                TokenKind::Whitespace
+            }
+            b'{' | b'}' | b'[' | b']' => {
+                pos += 1;
+                TokenKind::Bracket
+            }
+            b':' | b',' => {
+                pos += 1;
+                TokenKind::Punct
+            }
+            b'"' => {
+                pos += 1;
+                while let Some(&b) = bytes.get(pos) {
+                    pos += if b == b'\\' { 2 } else { 1 };
+                    if b == b'"' || b == b'\n' {
+                        break;
+                    }
+                }
+                pos = pos.min(bytes.len());
+                TokenKind::String
+            }
+            b'-' | b'0'..=b'9' => {
+                while matches!(bytes.get(pos), Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9')) {
+                    pos += 1;
+                }
+                TokenKind::Number
+            }
+            _ => {
+                while let Some(&b) = bytes.get(pos) {
+                    if !b.is_ascii_alphanumeric() && b < 0x80 && pos > start {
+                        break;
+                    }
+                    pos += 1;
+                }
+                // 保证落在字符边界上
+                while !text.is_char_boundary(pos) {
+                    pos += 1;
+                }
+                match &text[start..pos] {
+                    "true" | "false" | "null" => TokenKind::Literal,
+                    _ => TokenKind::Error,
+                }
+            }
+        };
+        tokens.push(Token {
+            kind,
+            range: start..pos,
+        });
+    }
+
+    // 后面紧跟冒号的字符串是键
+    let mut next_significant = None;
+    for token in tokens.iter_mut().rev() {
+        if token.kind == TokenKind::String && next_significant == Some(b':') {
+            token.kind = TokenKind::Key;
+        }
+        if token.kind != TokenKind::Whitespace {
+            next_significant = bytes.get(token.range.start).copied();
+        }
+    }
+    tokens
+}
+
+/// 一对匹配的括号（左括号和右括号的字节偏移）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BracketPair {
+    pub open: usize,
+    pub close: usize,
+}
+
+/// 找出所有匹配的括号对，按左括号位置排序
+pub fn bracket_pairs(text: &str, tokens: &[Token]) -> Vec<BracketPair> {
+    let bytes = text.as_bytes();
+    let mut stack: Vec<usize> = Vec::new();
+    let mut pairs = Vec::new();
+    for token in tokens.iter().filter(|t| t.kind == TokenKind::Bracket) {
+        let pos = token.range.start;
+        match bytes[pos] {
+            b'{' | b'[' => stack.push(pos),
+            close => {
+                let expected = if close == b'}' { b'{' } else { b'[' };
+                if stack.last().is_some_and(|&open| bytes[open] == expected)
+                    && let Some(open) = stack.pop()
+                {
+                    pairs.push(BracketPair { open, close: pos });
+                }
+            }
+        }
+    }
+    pairs.sort_by_key(|p| p.open);
+    pairs
+}
+
+/// 光标旁边的括号及其匹配括号（光标前一个字符优先）
+pub fn matching_bracket(pairs: &[BracketPair], caret: usize) -> Option<BracketPair> {
+    let at = |pos: usize| pairs.iter().find(|p| p.open == pos || p.close == pos).copied();
+    caret.checked_sub(1).and_then(at).or_else(|| at(caret))
+}
+
+/// 一段文本的分析结果：词法单元、括号配对、行首位置和可折叠区域
+pub struct Analysis {
+    pub tokens: Vec<Token>,
+    pub pairs: Vec<BracketPair>,
+    /// 每个逻辑行的起始字节偏移
+    pub line_starts: Vec<usize>,
+    /// 行号（从 0 开始）到该行可折叠的括号对：左括号在该行、右括号在后面的行
+    folds: HashMap<usize, BracketPair>,
+}
+
+impl Analysis {
+    pub fn new(text: &str) -> Self {
+        let tokens = tokenize(text);
+        let pairs = bracket_pairs(text, &tokens);
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut analysis = Self {
+            tokens,
+            pairs: Vec::new(),
+            line_starts,
+            folds: HashMap::new(),
+        };
+        for pair in &pairs {
+            let line = analysis.line_of(pair.open);
+            if line != analysis.line_of(pair.close) {
+                // 同一行有多个左括号时取第一个
+                analysis.folds.entry(line).or_insert(*pair);
+            }
+        }
+        analysis.pairs = pairs;
+        analysis
+    }
+
+    /// 字节偏移所在的行号（从 0 开始）
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset).saturating_sub(1)
+    }
+
+    /// 指定行可折叠的括号对
+    pub fn fold_at(&self, line: usize) -> Option<BracketPair> {
+        self.folds.get(&line).copied()
+    }
+}
@@ -0,0 +1,368 @@
+pub use appkit::i18n::Catalog;
+pub(crate) use appkit::tr;
+
+/// 按当前语言取非字面量的文本，例如常量表中的名称
+pub fn t(zh: &str) -> &str {
+    CATALOG.get(zh)
+}
+
+/// 英文译文，以界面中的中文原文为键；新增界面文本时在对应分组中补充
+pub static CATALOG: Catalog = Catalog::new(&[
+    // 工具栏与标签页
+    ("JSON 格式化", "JSON Formatter"),
+    ("格式化", "Format"),
+    ("压缩", "Minify"),
+    ("清空", "Clear"),
+    ("复制", "Copy"),
+    ("↶ 撤销", "↶ Undo"),
+    ("↷ 重做", "↷ Redo"),
+    ("🧩 展开内嵌 JSON", "🧩 Expand embedded JSON"),
+    ("把字符串中转义的 JSON（可多层嵌套）展开为真实子树", "Expand JSON escaped inside strings (possibly nested) into real subtrees"),
+    ("🧰 批量编辑", "🧰 Bulk edit"),
+    ("对搜索结果或路径表达式选中的所有节点执行删除、赋值、改名等操作", "Delete, set, rename and more on every node matched by the search or a path expression"),
+    ("🕶 脱敏", "🕶 Redact"),
+    ("按规则遮盖、哈希或替换密码、令牌、邮箱、卡号等敏感内容", "Mask, hash or replace passwords, tokens, emails, card numbers and other sensitive data by rules"),
+    ("📊 统计", "📊 Statistics"),
+    ("节点数、深度、各子树大小、类型分布和键名频率", "Node count, depth, subtree sizes, type distribution and key frequency"),
+    ("📤 导出", "📤 Export"),
+    ("导出为 HTML、Markdown 或树视图的 PNG 截图，可先脱敏", "Export as HTML, Markdown or a PNG screenshot of the tree view, optionally redacted first"),
+    ("🔀 合并", "🔀 Merge"),
+    ("深度合并多个标签页的文档，逐个处理冲突", "Deep-merge documents from several tabs and resolve conflicts one by one"),
+    ("需要至少两个标签页", "Requires at least two tabs"),
+    ("📜 脚本", "📜 Script"),
+    ("用 jq 脚本变换整个文档，预览结果后作为可撤销的编辑应用", "Transform the whole document with a jq script, preview the result and apply it as an undoable edit"),
+    ("合并结果", "Merged"),
+    ("📂 打开", "📂 Open"),
+    ("所有文件", "All files"),
+    ("跟随文件", "Follow file"),
+    ("文件变化时自动重新载入，并高亮变化的节点", "Reload automatically when the file changes and highlight changed nodes"),
+    ("监视剪贴板", "Watch clipboard"),
+    ("复制新的 JSON 时提示在新标签页中打开", "Offer to open newly copied JSON in a new tab"),
+    ("缩进：", "Indent:"),
+    ("{} 空格", "{} spaces"),
+    ("自动格式化", "Auto-format"),
+    ("编辑或打开文件时按当前缩进重新排版整个文档；关闭时保留原有排版，只在执行“格式化”时排版", "Reformat the whole document with the current indent on every edit or load; when off, the existing layout is kept and only the Format command reformats"),
+    ("🎨 外观", "🎨 Appearance"),
+    ("主题、界面缩放、字号、字体和配色", "Theme, zoom, font size, fonts and colors"),
+    ("错误：{}", "Error: {}"),
+    ("未命名", "Untitled"),
+    ("剪贴板", "Clipboard"),
+    ("关闭标签页", "Close tab"),
+    ("新建标签页", "New tab"),
+    ("📋 剪贴板中检测到新的 JSON {}", "📋 New JSON {} detected in clipboard"),
+    ("在新标签页打开", "Open in new tab"),
+    ("替换当前标签页", "Replace current tab"),
+    ("当前标签页有未保存的修改", "The current tab has unsaved changes"),
+    ("忽略", "Dismiss"),
+    ("读取文件失败：{}", "Failed to read file: {}"),
+    // 命令与快捷键
+    ("复制原文", "Copy source"),
+    ("打开文件", "Open file"),
+    ("下一个标签页", "Next tab"),
+    ("上一个标签页", "Previous tab"),
+    ("撤销", "Undo"),
+    ("重做", "Redo"),
+    ("搜索解析树", "Search tree"),
+    ("全部展开", "Expand all"),
+    ("全部折叠", "Collapse all"),
+    ("展开内嵌 JSON", "Expand embedded JSON"),
+    ("批量编辑", "Bulk edit"),
+    ("脱敏", "Redact"),
+    ("统计", "Statistics"),
+    ("导出", "Export"),
+    ("合并文档", "Merge documents"),
+    ("脚本", "Script"),
+    ("切换跟随文件", "Toggle follow file"),
+    ("切换监视剪贴板", "Toggle clipboard watching"),
+    ("外观设置", "Appearance settings"),
+    ("快捷键设置", "Keyboard shortcuts"),
+    ("命令面板", "Command palette"),
+    ("输入命令名称…", "Type a command name…"),
+    ("没有匹配的命令", "No matching commands"),
+    ("点击快捷键后按下新的组合键，按 Esc 取消", "Click a shortcut, then press the new key combination; Esc cancels"),
+    ("请按下快捷键…", "Press a shortcut…"),
+    ("{} 在输入文本时会被当作字符，请加上 Ctrl 或 Alt 等修饰键（F1–F12 除外）", "{} would be typed as text; add a modifier such as Ctrl or Alt (F1–F12 excepted)"),
+    ("未设置", "Not set"),
+    ("清除", "Clear"),
+    ("与“{}”冲突", "Conflicts with \"{}\""),
+    ("、", ", "),
+    ("全部恢复默认", "Restore all defaults"),
+    ("⌨ 快捷键", "⌨ Shortcuts"),
+    ("查看和修改快捷键", "View and change keyboard shortcuts"),
+    ("🔎 命令", "🔎 Commands"),
+    ("按名称搜索并执行任意命令", "Search any command by name and run it"),
+    ("按名称搜索并执行任意命令（{}）", "Search any command by name and run it ({})"),
+    // 编辑器与树
+    ("原始 JSON：", "Raw JSON:"),
+    ("在此粘贴或输入原始 JSON", "Paste or type raw JSON here"),
+    ("解析树：", "Parsed tree:"),
+    ("无匹配结果", "No matches"),
+    ("无解析结果", "Nothing parsed"),
+    ("✏️ 编辑值", "✏️ Edit value"),
+    ("✏️ 编辑键", "✏️ Edit key"),
+    ("输入新键名：", "New key name:"),
+    ("✅ 保存", "✅ Save"),
+    ("❌ 取消", "❌ Cancel"),
+    ("展开到", "Expand to"),
+    (" 层", " levels"),
+    ("0 层只展开根节点", "Level 0 expands only the root"),
+    ("默认：", "Default:"),
+    ("打开文档时默认展开的层数", "How many levels are expanded when a document is opened"),
+    // 右键菜单
+    ("🌲 展开整个子树", "🌲 Expand entire subtree"),
+    ("📤 导出…", "📤 Export…"),
+    ("📋 复制路径", "📋 Copy path"),
+    ("复制为 {}", "Copy as {}"),
+    ("📄 复制内容", "📄 Copy content"),
+    ("📋 复制为", "📋 Copy as"),
+    ("压缩的 JSON", "Minified JSON"),
+    ("Python 字面量", "Python literal"),
+    ("JavaScript 字面量", "JavaScript literal"),
+    ("转义的 JSON 字符串", "Escaped JSON string"),
+    ("curl 请求体", "curl request body"),
+    ("➕ 添加到对象", "➕ Add to object"),
+    ("➕ 添加到数组", "➕ Add to array"),
+    ("▦ 以表格查看", "▦ View as table"),
+    ("✏ 编辑键", "✏ Edit key"),
+    ("✏ 编辑值", "✏ Edit value"),
+    ("🧵 转为字符串", "🧵 Convert to string"),
+    ("📥 粘贴到末尾", "📥 Paste at end"),
+    ("📥 粘贴到前面", "📥 Paste before"),
+    ("📥 粘贴到后面", "📥 Paste after"),
+    ("🗑 删除", "🗑 Delete"),
+    ("➕ 插入", "➕ Insert"),
+    ("在前面插入", "Insert before"),
+    ("在后面插入", "Insert after"),
+    ("⧉ 复制节点", "⧉ Duplicate node"),
+    ("⬆ 上移", "⬆ Move up"),
+    ("⬇ 下移", "⬇ Move down"),
+    ("✂ 剪切", "✂ Cut"),
+    // 值类型与值编辑
+    ("字符串", "String"),
+    ("数字", "Number"),
+    ("布尔", "Boolean"),
+    ("对象", "Object"),
+    ("数组", "Array"),
+    ("类型：", "Type:"),
+    ("多行", "Multiline"),
+    ("值将被设置为 null", "The value will be set to null"),
+    ("结果类型：{}", "Result type: {}"),
+    ("\"{}\" 不是有效的数字", "\"{}\" is not a valid number"),
+    ("内容是{}，不是{}", "Content is {}, not {}"),
+    // 树搜索
+    ("🔍 搜索...", "🔍 Search..."),
+    ("任意类型", "Any type"),
+    ("键和值", "Keys and values"),
+    ("仅键名", "Keys only"),
+    ("仅值", "Values only"),
+    ("区分大小写", "Match case"),
+    ("正则表达式", "Regular expression"),
+    ("仅显示匹配分支", "Only matching branches"),
+    ("无结果", "No results"),
+    ("{} 个结果", "{} results"),
+    ("上一个 (Shift+Enter)", "Previous (Shift+Enter)"),
+    ("下一个 (Enter)", "Next (Enter)"),
+    // 查找替换
+    ("查找", "Find"),
+    ("全字匹配", "Whole word"),
+    ("替换", "Replace"),
+    ("全部替换", "Replace all"),
+    ("替换为", "Replace with"),
+    ("替换为（可用 $1、${name}）", "Replace with ($1, ${name} allowed)"),
+    ("关闭 (Esc)", "Close (Esc)"),
+    ("全部文本", "All text"),
+    ("仅字符串值", "String values only"),
+    ("已替换 {} 处", "Replaced {} occurrences"),
+    ("替换后 \"{}\" 不是合法的 JSON 字符串，请检查引号和转义", "After replacing, \"{}\" is not a valid JSON string; check quotes and escapes"),
+    // 路径
+    ("根节点", "Root"),
+    ("以不同写法复制路径", "Copy the path in different syntaxes"),
+    ("跳转：", "Go to:"),
+    ("/users/0、$.users[0].name 或 users[0].name", "/users/0, $.users[0].name or users[0].name"),
+    ("没有匹配的节点", "No matching node"),
+    ("共 {} 个匹配，已跳到第一个", "{} matches, jumped to the first"),
+    ("JS 访问写法", "JS accessor"),
+    ("路径 {} 不存在", "Path {} does not exist"),
+    ("第 {} 个字符处应为 . 或 [", "Expected . or [ at character {}"),
+    ("第 {} 个字符处缺少键名", "Missing key name at character {}"),
+    ("引号未闭合", "Unclosed quote"),
+    ("无效的下标：{}", "Invalid index: {}"),
+    ("缺少 ]", "Missing ]"),
+    // 批量编辑
+    ("目标：", "Target:"),
+    ("当前搜索结果（{} 个）", "Current search results ({})"),
+    ("路径表达式", "Path expression"),
+    ("$.users[*].email、$..password 或 /items/0", "$.users[*].email, $..password or /items/0"),
+    ("操作：", "Operation:"),
+    ("新键名", "New key name"),
+    ("删除节点", "Delete nodes"),
+    ("设置为指定值", "Set to value"),
+    ("重命名键", "Rename key"),
+    ("打码（替换为 ***）", "Mask (replace with ***)"),
+    ("去除首尾空白", "Trim whitespace"),
+    ("转为小写", "Lowercase"),
+    ("转为大写", "Uppercase"),
+    ("数值乘以", "Multiply number by"),
+    ("请输入新键名", "Enter a new key name"),
+    ("倍数不是有效的数字", "The factor is not a valid number"),
+    ("🔍 预览", "🔍 Preview"),
+    ("✅ 应用", "✅ Apply"),
+    ("将修改 {} 个节点，跳过 {} 个不适用或无变化的节点", "{} nodes will change, {} skipped as not applicable or unchanged"),
+    ("键 {}", "key {}"),
+    ("（删除）", "(deleted)"),
+    ("……其余 {} 项未显示", "…{} more not shown"),
+    // 脱敏
+    ("配置：", "Profile:"),
+    ("➕ 新建", "➕ New"),
+    ("以当前配置为模板新建", "Create a new profile based on the current one"),
+    ("配置 {}", "Profile {}"),
+    ("默认", "Default"),
+    ("名称：", "Name:"),
+    ("键名规则：", "Key rule:"),
+    ("正则，不区分大小写", "Regex, case-insensitive"),
+    ("键名匹配的成员，其值（包括整个子树）都会脱敏", "Members whose key matches are redacted together with their whole subtree"),
+    ("键名规则无效：{}", "Invalid key rule: {}"),
+    ("识别内容：", "Detect:"),
+    ("邮箱", "Email"),
+    ("信用卡号", "Card number"),
+    ("IP 地址", "IP address"),
+    ("处理方式：", "Action:"),
+    ("遮盖", "Mask"),
+    ("哈希", "Hash"),
+    ("替换为假数据", "Fake data"),
+    ("✅ 应用到文档", "✅ Apply to document"),
+    ("📋 复制脱敏结果", "📋 Copy redacted result"),
+    ("复制脱敏后的 JSON，不修改当前文档", "Copy the redacted JSON without changing the document"),
+    ("已复制", "Copied"),
+    ("将脱敏 {} 个值", "{} values will be redacted"),
+    // 表格视图
+    ("▦ 表格视图", "▦ Table view"),
+    ("（值）", "(value)"),
+    ("{} 已不是数组", "{} is no longer an array"),
+    ("显示 {} / {} 行，{} 列", "Showing {} / {} rows, {} columns"),
+    ("📋 复制为 CSV", "📋 Copy as CSV"),
+    ("按当前的筛选和排序复制", "Copy with the current filters and sort order"),
+    ("取消排序", "Clear sort"),
+    ("点击列名排序，双击单元格编辑", "Click a header to sort, double-click a cell to edit"),
+    ("✏️ 编辑单元格", "✏️ Edit cell"),
+    ("第 {} 行，{}", "Row {}, {}"),
+    ("筛选", "Filter"),
+    // 统计
+    ("没有可统计的 JSON", "No JSON to analyze"),
+    ("节点总数：", "Total nodes:"),
+    ("最大深度：", "Max depth:"),
+    ("压缩后大小：", "Minified size:"),
+    ("{}（原文 {}）", "{} (source {})"),
+    ("类型分布：", "Types:"),
+    ("矩形树图", "Treemap"),
+    ("面积对应压缩后的大小，点击可在树中定位", "Area is proportional to minified size; click to locate in the tree"),
+    ("最大的子树", "Largest subtrees"),
+    ("数组元素的键名频率", "Key frequency in array elements"),
+    ("没有包含多个对象的数组", "No arrays with multiple objects"),
+    ("（{} 个对象）", "({} objects)"),
+    ("最长的字符串", "Longest strings"),
+    ("{} 个字符", "{} characters"),
+    ("其余 {} 项", "{} more"),
+    ("其余 {} 项，共 {}", "{} more, {} in total"),
+    ("{}\n{}，占 {}%", "{}\n{}, {}%"),
+    // 导出
+    ("PNG 图片", "PNG image"),
+    ("对象，{} 个成员", "object, {} members"),
+    ("数组，{} 个元素", "array, {} items"),
+    ("没有可导出的 JSON", "No JSON to export"),
+    ("范围：", "Scope:"),
+    ("整个文档", "Whole document"),
+    ("导出整个文档", "Export whole document"),
+    ("格式：", "Format:"),
+    ("脱敏：", "Redaction:"),
+    ("不脱敏", "None"),
+    ("导出前按所选的脱敏配置处理，不影响文档本身", "Apply the selected redaction profile before exporting; the document itself is not changed"),
+    ("预览：", "Preview:"),
+    ("PNG 图片截取预览区域，内容须完整显示在预览区域内", "The PNG image captures the preview, so the content must fit inside it"),
+    ("内容超出预览区域，PNG 只能导出完整可见的内容：请折叠节点、缩小导出范围或放大窗口", "The content overflows the preview and a PNG can only capture fully visible content: collapse nodes, narrow the export scope or enlarge the window"),
+    ("💾 保存…", "💾 Save…"),
+    ("📋 复制", "📋 Copy"),
+    ("已保存到 {}", "Saved to {}"),
+    ("保存失败：{}", "Save failed: {}"),
+    ("预览区域超出窗口，无法截取完整内容", "The preview extends beyond the window, so it cannot be captured completely"),
+    // 合并
+    ("覆盖（后者优先）", "Override (later wins)"),
+    ("保留（前者优先）", "Keep (earlier wins)"),
+    ("拼接数组", "Concatenate arrays"),
+    ("按键合并数组", "Merge arrays by key"),
+    ("冲突时报错", "Error on conflict"),
+    ("同一位置的值不同时使用后面文档的值", "Use the later document's value when values differ"),
+    ("同一位置的值不同时保留前面文档的值", "Keep the earlier document's value when values differ"),
+    ("数组首尾相接，其余冲突使用后面文档的值", "Append arrays; other conflicts use the later document's value"),
+    ("数组中指定键的值相同的对象逐个合并，其余元素追加到末尾；其余冲突使用后面文档的值", "Merge array objects whose key field matches and append the rest; other conflicts use the later document's value"),
+    ("每处冲突都需手动选择后才能生成结果", "Every conflict must be resolved by hand before producing the result"),
+    ("（已关闭）", "(closed)"),
+    ("按顺序合并，后面的文档合并到前面的结果上：", "Merged in order, each document on top of the result so far:"),
+    ("标签页已关闭", "Tab closed"),
+    ("不是有效的 JSON", "Not valid JSON"),
+    ("➕ 添加文档", "➕ Add document"),
+    ("策略：", "Strategy:"),
+    ("匹配键：", "Match key:"),
+    ("有文档无法参与合并", "Some documents cannot be merged"),
+    ("没有冲突", "No conflicts"),
+    ("{} 处冲突", "{} conflicts"),
+    ("{} 处未解决", "{} unresolved"),
+    ("全部取前者", "Take all earlier"),
+    ("全部取后者", "Take all later"),
+    ("合并“{}”时", "While merging \"{}\""),
+    ("前者：{}", "Earlier: {}"),
+    ("后者：{}", "Later: {}"),
+    ("📄 在新标签页中打开", "📄 Open in new tab"),
+    ("还有未解决的冲突", "There are unresolved conflicts"),
+    ("📋 复制结果", "📋 Copy result"),
+    // 脚本
+    ("删除所有 null", "Remove all nulls"),
+    ("键名转为小写", "Lowercase keys"),
+    ("数组按 name 排序", "Sort arrays by name"),
+    ("去掉字符串首尾空白", "Trim strings"),
+    ("第 {} 行：{}", "Line {}: {}"),
+    ("脚本末尾", "end of script"),
+    ("“{}”", "\"{}\""),
+    ("变量", "variable"),
+    ("函数", "function"),
+    ("标签", "label"),
+    ("模块", "module"),
+    ("无法解析脚本", "Cannot parse script"),
+    ("不支持导入模块", "Importing modules is not supported"),
+    ("应为 {}，实际为 {}", "Expected {}, found {}"),
+    ("未定义的{}：{}", "Undefined {}: {}"),
+    ("无法编译脚本", "Cannot compile script"),
+    ("运行出错：{}", "Runtime error: {}"),
+    ("脚本没有输出任何值", "The script produced no output"),
+    ("脚本输出了多个值，可用 [ … ] 把它们收集为数组", "The script produced several values; wrap it in [ … ] to collect them into an array"),
+    ("\n… 还有 {} 行", "\n… {} more lines"),
+    ("无法启动脚本：{}", "Cannot start script: {}"),
+    ("脚本异常终止", "The script terminated abnormally"),
+    ("片段：", "Snippet:"),
+    ("以当前脚本为模板新建片段", "Create a snippet from the current script"),
+    ("片段 {}", "Snippet {}"),
+    ("jq 语法：输入 . 与变量 $last_json 都是当前文档，脚本须恰好输出一个值", "jq syntax: both the input . and the variable $last_json are the current document; the script must output exactly one value"),
+    ("运行中… {} 秒", "Running… {} s"),
+    ("⏹ 停止", "⏹ Stop"),
+    ("结束脚本并放弃本次运行的结果", "End the script and discard the result of this run"),
+    ("▶ 运行", "▶ Run"),
+    ("文档已改变，请重新运行", "The document has changed, run again"),
+    ("替换整个文档，可撤销", "Replace the whole document (undoable)"),
+    ("结果与当前文档相同", "The result is identical to the current document"),
+    // 值预览
+    ("毫秒", "milliseconds"),
+    ("秒", "seconds"),
+    ("可能是以{}为单位的时间戳：\n{}", "Possibly a timestamp in {}:\n{}"),
+    ("在浏览器中打开 {}", "Open {} in browser"),
+    ("无法解码图片", "Cannot decode image"),
+    ("UUID v{}，生成时间：\n{}", "UUID v{}, generated at:\n{}"),
+    ("无法解码 JWT", "Cannot decode JWT"),
+    ("头部", "Header"),
+    ("载荷", "Payload"),
+    ("{}：{}", "{}: {}"),
+    ("过期时间", "Expires"),
+    ("签发时间", "Issued at"),
+    ("生效时间", "Not before"),
+    ("已过期", "Expired"),
+    ("签名未校验", "Signature not verified"),
+]);
@@ -0,0 +1,353 @@
+use serde_json::Value;
+
+use crate::i18n::tr;
+use crate::tree_ops::join_pointer;
+
+/// 路径表达式中的一步
+#[derive(Debug)]
+enum Step {
+    /// .name 或 ['name']
+    Child(String),
+    /// [n]，负数从末尾数起
+    Index(i64),
+    /// .* 或 [*]
+    Wildcard,
+    /// ..step：在自身及所有后代上匹配
+    Descendant(Box<Step>),
+}
+
+/// 按路径表达式选取节点，返回匹配节点的 JSON Pointer
+///
+/// 支持三种写法：
+/// - JSON Pointer：`/users/0/name`
+/// - JSONPath 子集：`$.users[*].name`、`$..id`、`$['a b'][-1]`
+/// - 代码访问写法：`users[0].name`（相当于省略了开头的 `$.`）
+pub fn select(root: &Value, input: &str) -> Result<Vec<String>, String> {
+    let expr = input.trim();
+    if expr.is_empty() || expr.starts_with('/') {
+        return match root.pointer(expr) {
+            Some(_) => Ok(vec![expr.to_string()]),
+            None => Err(tr!("路径 {} 不存在", expr)),
+        };
+    }
+    let lead = input[..input.len() - input.trim_start().len()].chars().count();
+    let steps = parse(expr, lead)?;
+    let mut current: Vec<(String, &Value)> = vec![(String::new(), root)];
+    for step in &steps {
+        let mut next = Vec::new();
+        for (pointer, value) in &current {
+            apply(step, pointer, value, &mut next);
+        }
+        // 递归下降可能重复选中同一节点
+        let mut seen = std::collections::HashSet::new();
+        next.retain(|(p, _)| seen.insert(p.clone()));
+        current = next;
+    }
+    Ok(current.into_iter().map(|(p, _)| p).collect())
+}
+
+fn apply<'a>(step: &Step, pointer: &str, value: &'a Value, out: &mut Vec<(String, &'a Value)>) {
+    match step {
+        Step::Child(name) => {
+            if let Some(child) = value.as_object().and_then(|m| m.get(name)) {
+                out.push((join_pointer(pointer, name), child));
+            }
+        }
+        Step::Index(idx) => {
+            if let Some(arr) = value.as_array() {
+                let idx = if *idx < 0 { arr.len() as i64 + idx } else { *idx };
+                if let Some(child) = usize::try_from(idx).ok().and_then(|i| arr.get(i)) {
+                    out.push((format!("{}/{}", pointer, idx), child));
+                }
+            }
+        }
+        Step::Wildcard => match value {
+            Value::Object(map) => out.extend(map.iter().map(|(k, v)| (join_pointer(pointer, k), v))),
+            Value::Array(arr) => out.extend(arr.iter().enumerate().map(|(i, v)| (format!("{}/{}", pointer, i), v))),
+            _ => {}
+        },
+        Step::Descendant(inner) => {
+            apply(inner, pointer, value, out);
+            match value {
+                Value::Object(map) => {
+                    for (k, v) in map {
+                        apply(step, &join_pointer(pointer, k), v, out);
+                    }
+                }
+                Value::Array(arr) => {
+                    for (i, v) in arr.iter().enumerate() {
+                        apply(step, &format!("{}/{}", pointer, i), v, out);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 解析去掉首尾空白的表达式；lead 为原输入开头被去掉的空白字符数，用于报告错误位置
+fn parse(expr: &str, lead: usize) -> Result<Vec<Step>, String> {
+    // 去掉开头的 $，代码访问写法补上开头的 .；shift 为 rest 中的下标相对原输入的偏移
+    let (rest, shift) = match expr.strip_prefix('$') {
+        Some(rest) => (rest.to_string(), lead as isize + 1),
+        None if expr.starts_with('[') => (expr.to_string(), lead as isize),
+        None => (format!(".{}", expr), lead as isize - 1),
+    };
+    // 原输入中从 1 开始的字符位置
+    let column = |pos: usize| pos as isize + shift + 1;
+    let chars: Vec<char> = rest.chars().collect();
+    let mut pos = 0;
+    let mut steps = Vec::new();
+    while pos < chars.len() {
+        let descendant = chars[pos..].starts_with(&['.', '.']);
+        if descendant {
+            pos += 2;
+        } else if chars[pos] == '.' {
+            pos += 1;
+        } else if chars[pos] != '[' {
+            return Err(tr!("第 {} 个字符处应为 . 或 [", column(pos)));
+        }
+        let step = if chars.get(pos) == Some(&'[') {
+            bracket(&chars, &mut pos)?
+        } else if chars.get(pos) == Some(&'*') {
+            pos += 1;
+            Step::Wildcard
+        } else {
+            let start = pos;
+            while pos < chars.len() && chars[pos] != '.' && chars[pos] != '[' {
+                pos += 1;
+            }
+            if start == pos {
+                return Err(tr!("第 {} 个字符处缺少键名", column(pos)));
+            }
+            Step::Child(chars[start..pos].iter().collect())
+        };
+        steps.push(if descendant { Step::Descendant(Box::new(step)) } else { step });
+    }
+    Ok(steps)
+}
+
+/// 解析 [n]、[*]、['name'] 或 ["name"]
+fn bracket(chars: &[char], pos: &mut usize) -> Result<Step, String> {
+    *pos += 1;
+    let step = match chars.get(*pos) {
+        Some(&quote @ ('\'' | '"')) => {
+            *pos += 1;
+            let mut name = String::new();
+            loop {
+                match chars.get(*pos) {
+                    Some('\\') => {
+                        name.extend(chars.get(*pos + 1));
+                        *pos += 2;
+                    }
+                    Some(&c) if c == quote => {
+                        *pos += 1;
+                        break;
+                    }
+                    Some(&c) => {
+                        name.push(c);
+                        *pos += 1;
+                    }
+                    None => return Err(tr!("引号未闭合").to_string()),
+                }
+            }
+            Step::Child(name)
+        }
+        Some('*') => {
+            *pos += 1;
+            Step::Wildcard
+        }
+        _ => {
+            let start = *pos;
+            while chars.get(*pos).is_some_and(|c| *c != ']') {
+                *pos += 1;
+            }
+            let text: String = chars[start..*pos].iter().collect();
+            Step::Index(text.trim().parse().map_err(|_| tr!("无效的下标：{}", text))?)
+        }
+    };
+    if chars.get(*pos) != Some(&']') {
+        return Err(tr!("缺少 ]").to_string());
+    }
+    *pos += 1;
+    Ok(step)
+}
+
+/// 路径的写法
+#[derive(Clone, Copy, PartialEq)]
+pub enum PathSyntax {
+    /// RFC 6901 JSON Pointer：`/users/0/name`
+    Pointer,
+    /// JSONPath：`$.users[0].name`
+    JsonPath,
+    /// JavaScript 访问写法：`users[0].name`
+    Accessor,
+}
+
+impl PathSyntax {
+    pub const ALL: [PathSyntax; 3] = [PathSyntax::Pointer, PathSyntax::JsonPath, PathSyntax::Accessor];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PathSyntax::Pointer => "JSON Pointer",
+            PathSyntax::JsonPath => "JSONPath",
+            PathSyntax::Accessor => tr!("JS 访问写法"),
+        }
+    }
+}
+
+/// 路径中的一段
+pub struct Segment {
+    pub name: String,
+    /// 是否为数组下标
+    pub index: bool,
+    /// 到这一段为止的 JSON Pointer
+    pub pointer: String,
+}
+
+/// 按文档结构拆分 JSON Pointer，区分数组下标和数字形式的键名
+pub fn segments(root: &Value, pointer: &str) -> Vec<Segment> {
+    let mut out = Vec::new();
+    let mut current = root;
+    let mut prefix = String::new();
+    for raw in pointer.split('/').skip(1) {
+        let name = raw.replace("~1", "/").replace("~0", "~");
+        let (child, index) = match current {
+            Value::Array(arr) => (name.parse::<usize>().ok().and_then(|i| arr.get(i)), true),
+            Value::Object(map) => (map.get(&name), false),
+            _ => (None, false),
+        };
+        let Some(child) = child else {
+            break;
+        };
+        prefix = join_pointer(&prefix, &name);
+        out.push(Segment {
+            name,
+            index,
+            pointer: prefix.clone(),
+        });
+        current = child;
+    }
+    out
+}
+
+/// 可以用 `.name` 访问的键名
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// 引号内的键名，转义反斜杠和引号
+fn quoted(name: &str, quote: char) -> String {
+    let escaped = name.replace('\\', "\\\\").replace(quote, &format!("\\{}", quote));
+    format!("{}{}{}", quote, escaped, quote)
+}
+
+/// 以指定写法表示节点路径，结果可再由 select 解析
+pub fn format_path(root: &Value, pointer: &str, syntax: PathSyntax) -> String {
+    let segments = segments(root, pointer);
+    let mut out = match syntax {
+        PathSyntax::Pointer => return pointer.to_string(),
+        PathSyntax::JsonPath => "$".to_string(),
+        PathSyntax::Accessor => String::new(),
+    };
+    for segment in &segments {
+        match syntax {
+            _ if segment.index => out.push_str(&format!("[{}]", segment.name)),
+            PathSyntax::JsonPath if is_identifier(&segment.name) => out.push_str(&format!(".{}", segment.name)),
+            PathSyntax::JsonPath => out.push_str(&format!("[{}]", quoted(&segment.name, '\''))),
+            // 开头的 $ 会被当作 JSONPath，只能用方括号
+            PathSyntax::Accessor
+                if is_identifier(&segment.name) && !(out.is_empty() && segment.name.starts_with('$')) =>
+            {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(&segment.name);
+            }
+            _ => out.push_str(&format!("[{}]", quoted(&segment.name, '"'))),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn doc() -> Value {
+        json!({
+            "users": [{"id": 1, "name": "a"}, {"id": 2, "tags": [{"id": 3}]}],
+            "a b": [10, 20, 30],
+            "it's": {"say \"hi\"": true},
+            "0": {"$x": null, "back\\slash": "", "a/b~c": [[1]]},
+            "名字": ""
+        })
+    }
+
+    #[test]
+    fn quoted_keys_and_negative_index() {
+        let root = doc();
+        assert_eq!(select(&root, "$['a b'][-1]").unwrap(), ["/a b/2"]);
+        assert_eq!(select(&root, "[\"a b\"][0]").unwrap(), ["/a b/0"]);
+        assert_eq!(select(&root, "$['a b'][-4]").unwrap(), Vec::<String>::new());
+        assert_eq!(select(&root, "$['it\\'s']['say \"hi\"']").unwrap(), ["/it's/say \"hi\""]);
+        assert_eq!(select(&root, "[\"it's\"][\"say \\\"hi\\\"\"]").unwrap(), ["/it's/say \"hi\""]);
+    }
+
+    #[test]
+    fn recursive_descent_and_wildcards() {
+        let root = doc();
+        assert_eq!(select(&root, "$..id").unwrap(), ["/users/0/id", "/users/1/id", "/users/1/tags/0/id"]);
+        assert_eq!(select(&root, "users[*].id").unwrap(), ["/users/0/id", "/users/1/id"]);
+        assert_eq!(select(&root, "$.users.*.name").unwrap(), ["/users/0/name"]);
+        assert_eq!(select(&root, "/users/1/tags").unwrap(), ["/users/1/tags"]);
+    }
+
+    #[test]
+    fn error_positions_refer_to_the_input() {
+        let root = doc();
+        let position = |expr: &str| {
+            let err = select(&root, expr).unwrap_err();
+            err.split(' ').nth(1).and_then(|n| n.parse::<usize>().ok()).unwrap_or_else(|| panic!("{err}"))
+        };
+        assert_eq!(position("$x"), 2);
+        assert_eq!(position("$.a."), 5);
+        assert_eq!(position("a."), 3);
+        assert_eq!(position("users[0]x"), 9);
+        assert_eq!(position("[0]x"), 4);
+        assert_eq!(position("  $x"), 4);
+        assert!(select(&root, "$['a").is_err());
+        assert!(select(&root, "$[x]").is_err());
+        assert!(select(&root, "$[0").is_err());
+    }
+
+    #[test]
+    fn formatted_paths_select_the_same_node() {
+        let root = doc();
+        let mut pointers = vec![String::new()];
+        let mut stack = vec![(String::new(), &root)];
+        while let Some((pointer, value)) = stack.pop() {
+            let children: Vec<(String, &Value)> = match value {
+                Value::Object(map) => map.iter().map(|(k, v)| (join_pointer(&pointer, k), v)).collect(),
+                Value::Array(arr) => arr.iter().enumerate().map(|(i, v)| (format!("{}/{}", pointer, i), v)).collect(),
+                _ => Vec::new(),
+            };
+            pointers.extend(children.iter().map(|(p, _)| p.clone()));
+            stack.extend(children);
+        }
+        for pointer in &pointers {
+            for syntax in PathSyntax::ALL {
+                let path = format_path(&root, pointer, syntax);
+                assert_eq!(select(&root, &path).unwrap(), [pointer.as_str()], "{path}");
+            }
+        }
+        assert_eq!(format_path(&root, "/0/$x", PathSyntax::Accessor), "[\"0\"].$x");
+        assert_eq!(format_path(&root, "/users/1/tags/0", PathSyntax::JsonPath), "$.users[1].tags[0]");
+    }
+}
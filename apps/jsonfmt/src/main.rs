@@ -1,14 +1,62 @@
 #![windows_subsystem = "windows"]
 
 mod app;
+mod bulk;
+mod bulk_panel;
+mod clipboard;
+mod command_palette;
+mod commands;
 mod context_menu;
+mod diff;
+mod document;
 mod edit;
+mod editor;
+mod embedded;
+mod expand_bar;
+mod expansion;
+mod export;
+mod export_panel;
+mod find;
+mod find_bar;
+mod highlight;
+mod i18n;
+mod json_path;
+mod merge;
+mod merge_panel;
+mod patch;
+mod path_bar;
+mod preview;
+mod preview_ui;
+mod redact;
+mod redact_panel;
+mod script;
+mod script_panel;
+mod search_bar;
+mod settings;
+mod snippet;
+mod span;
+mod stats;
+mod stats_panel;
+mod table;
+mod table_view;
+mod tree_ops;
+mod tree_search;
 mod ui;
+mod value_editor;
+mod watch;
 
 use app::JsonFmtApp;
 use eframe::egui;
 
 fn main() -> eframe::Result<()> {
+    // 脚本面板以子进程运行脚本，见 script::ScriptProcess
+    if std::env::args().nth(1).as_deref() == Some(script::RUNNER_ARG) {
+        if let Err(e) = script::serve() {
+            eprintln!("{}", e);
+        }
+        return Ok(());
+    }
+
     // 加载窗口图标
     let icon_data = include_bytes!("../jsonfmt.png");
     let icon_image = image::load_from_memory(icon_data)
@@ -31,6 +79,6 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "JSON 格式化",
         options,
-        Box::new(|_cc| Ok(Box::new(JsonFmtApp::default()))),
+        Box::new(|cc| Ok(Box::new(JsonFmtApp::new(cc)))),
     )
 }
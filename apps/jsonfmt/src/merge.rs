@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::i18n::tr;
+use crate::tree_ops::join_pointer;
+
+/// 同一位置的值不同时的处理方式
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MergeStrategy {
+    /// 后面文档的值覆盖前面的
+    Override,
+    /// 保留前面文档的值
+    KeepLeft,
+    /// 数组首尾相接，其余冲突同 Override
+    ConcatArrays,
+    /// 数组中键值相同的对象逐个合并，其余元素追加；其余冲突同 Override
+    MergeByKey,
+    /// 每处冲突都需手动选择
+    ErrorOnConflict,
+}
+
+impl MergeStrategy {
+    pub const ALL: [MergeStrategy; 5] = [
+        MergeStrategy::Override,
+        MergeStrategy::KeepLeft,
+        MergeStrategy::ConcatArrays,
+        MergeStrategy::MergeByKey,
+        MergeStrategy::ErrorOnConflict,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MergeStrategy::Override => tr!("覆盖（后者优先）"),
+            MergeStrategy::KeepLeft => tr!("保留（前者优先）"),
+            MergeStrategy::ConcatArrays => tr!("拼接数组"),
+            MergeStrategy::MergeByKey => tr!("按键合并数组"),
+            MergeStrategy::ErrorOnConflict => tr!("冲突时报错"),
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            MergeStrategy::Override => tr!("同一位置的值不同时使用后面文档的值"),
+            MergeStrategy::KeepLeft => tr!("同一位置的值不同时保留前面文档的值"),
+            MergeStrategy::ConcatArrays => tr!("数组首尾相接，其余冲突使用后面文档的值"),
+            MergeStrategy::MergeByKey => {
+                tr!("数组中指定键的值相同的对象逐个合并，其余元素追加到末尾；其余冲突使用后面文档的值")
+            }
+            MergeStrategy::ErrorOnConflict => tr!("每处冲突都需手动选择后才能生成结果"),
+        }
+    }
+
+    /// 未手动选择时冲突采用的一方，None 表示必须手动选择
+    fn default_side(self) -> Option<Side> {
+        match self {
+            MergeStrategy::KeepLeft => Some(Side::Left),
+            MergeStrategy::ErrorOnConflict => None,
+            _ => Some(Side::Right),
+        }
+    }
+}
+
+/// 冲突中采用的一方
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    /// 之前各文档合并的结果
+    Left,
+    /// 正在合并进来的文档
+    Right,
+}
+
+#[derive(Clone)]
+pub struct MergeOptions {
+    pub strategy: MergeStrategy,
+    /// MergeByKey 时用于匹配数组元素的键名
+    pub array_key: String,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            strategy: MergeStrategy::Override,
+            array_key: "id".to_string(),
+        }
+    }
+}
+
+/// 冲突的标识：第几个文档合并进来时、在结果中的哪个位置
+pub type ConflictKey = (usize, String);
+
+/// 两边都有值、不能递归合并且不相等的位置
+pub struct Conflict {
+    /// 合并进来的文档下标（从 1 开始，0 号文档是起点）
+    pub layer: usize,
+    /// 在合并结果中的 JSON Pointer
+    pub pointer: String,
+    pub left: Value,
+    pub right: Value,
+    /// 采用的一方，None 表示尚未解决
+    pub side: Option<Side>,
+}
+
+impl Conflict {
+    pub fn key(&self) -> ConflictKey {
+        (self.layer, self.pointer.clone())
+    }
+}
+
+pub struct MergeOutcome {
+    /// 合并结果；未解决的冲突处暂时保留前者
+    pub value: Value,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeOutcome {
+    pub fn unresolved(&self) -> usize {
+        self.conflicts.iter().filter(|c| c.side.is_none()).count()
+    }
+}
+
+/// 从左到右依次深度合并各文档。对象按键名递归合并；其余值不同的位置记为冲突，
+/// 按 choices 中的手动选择或策略的默认方式处理
+pub fn merge(layers: &[&Value], options: &MergeOptions, choices: &HashMap<ConflictKey, Side>) -> MergeOutcome {
+    let mut conflicts = Vec::new();
+    let mut value = layers.first().map_or(Value::Null, |v| (*v).clone());
+    for (layer, right) in layers.iter().enumerate().skip(1) {
+        let mut merger = Merger {
+            options,
+            choices,
+            layer,
+            conflicts: &mut conflicts,
+        };
+        merger.merge_into(&mut value, right, String::new());
+    }
+    MergeOutcome { value, conflicts }
+}
+
+struct Merger<'a> {
+    options: &'a MergeOptions,
+    choices: &'a HashMap<ConflictKey, Side>,
+    layer: usize,
+    conflicts: &'a mut Vec<Conflict>,
+}
+
+impl Merger<'_> {
+    fn merge_into(&mut self, left: &mut Value, right: &Value, pointer: String) {
+        let strategy = self.options.strategy;
+        match (left, right) {
+            (Value::Object(l), Value::Object(r)) => {
+                for (key, rv) in r {
+                    let child = join_pointer(&pointer, key);
+                    match l.get_mut(key) {
+                        Some(lv) => self.merge_into(lv, rv, child),
+                        None => {
+                            l.insert(key.clone(), rv.clone());
+                        }
+                    }
+                }
+            }
+            (Value::Array(l), Value::Array(r)) if strategy == MergeStrategy::ConcatArrays => {
+                l.extend(r.iter().cloned());
+            }
+            (Value::Array(l), Value::Array(r))
+                if strategy == MergeStrategy::MergeByKey && (self.keyed(l) || self.keyed(r)) =>
+            {
+                self.merge_by_key(l, r, &pointer);
+            }
+            (left, right) => {
+                if left != right {
+                    self.conflict(left, right, pointer);
+                }
+            }
+        }
+    }
+
+    fn key_of<'v>(&self, item: &'v Value) -> Option<&'v Value> {
+        item.as_object()?.get(&self.options.array_key)
+    }
+
+    /// 数组中有带匹配键的对象
+    fn keyed(&self, items: &[Value]) -> bool {
+        items.iter().any(|item| self.key_of(item).is_some())
+    }
+
+    fn merge_by_key(&mut self, left: &mut Vec<Value>, right: &[Value], pointer: &str) {
+        for item in right {
+            let matched = self
+                .key_of(item)
+                .and_then(|key| left.iter().position(|l| self.key_of(l) == Some(key)));
+            match matched {
+                Some(idx) => self.merge_into(&mut left[idx], item, format!("{}/{}", pointer, idx)),
+                None => left.push(item.clone()),
+            }
+        }
+    }
+
+    fn conflict(&mut self, left: &mut Value, right: &Value, pointer: String) {
+        let key = (self.layer, pointer);
+        let side = self
+            .choices
+            .get(&key)
+            .copied()
+            .or(self.options.strategy.default_side());
+        self.conflicts.push(Conflict {
+            layer: self.layer,
+            pointer: key.1,
+            left: left.clone(),
+            right: right.clone(),
+            side,
+        });
+        if side == Some(Side::Right) {
+            *left = right.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn run(layers: &[Value], strategy: MergeStrategy) -> MergeOutcome {
+        let refs: Vec<&Value> = layers.iter().collect();
+        let options = MergeOptions {
+            strategy,
+            ..MergeOptions::default()
+        };
+        merge(&refs, &options, &HashMap::new())
+    }
+
+    fn pointers(outcome: &MergeOutcome) -> Vec<(usize, &str)> {
+        outcome.conflicts.iter().map(|c| (c.layer, c.pointer.as_str())).collect()
+    }
+
+    #[test]
+    fn override_and_keep_left() {
+        let layers = [json!({"a": 1, "n": {"x": 1, "y": [1]}}), json!({"a": 2, "n": {"y": [2], "z": 3}})];
+        let out = run(&layers, MergeStrategy::Override);
+        assert_eq!(out.value, json!({"a": 2, "n": {"x": 1, "y": [2], "z": 3}}));
+        assert_eq!(pointers(&out), [(1, "/a"), (1, "/n/y")]);
+        assert_eq!(out.unresolved(), 0);
+
+        let out = run(&layers, MergeStrategy::KeepLeft);
+        assert_eq!(out.value, json!({"a": 1, "n": {"x": 1, "y": [1], "z": 3}}));
+        assert!(out.conflicts.iter().all(|c| c.side == Some(Side::Left)));
+    }
+
+    #[test]
+    fn equal_values_and_type_changes() {
+        let layers = [json!({"same": [1], "t": {"k": 1}}), json!({"same": [1], "t": [1]})];
+        let out = run(&layers, MergeStrategy::Override);
+        assert_eq!(pointers(&out), [(1, "/t")]);
+        assert_eq!(out.conflicts[0].left, json!({"k": 1}));
+        assert_eq!(out.value["t"], json!([1]));
+    }
+
+    #[test]
+    fn concat_arrays() {
+        let layers = [json!({"l": [1, 2], "s": "a"}), json!({"l": [2, 3], "s": "b"}), json!({"l": []})];
+        let out = run(&layers, MergeStrategy::ConcatArrays);
+        assert_eq!(out.value, json!({"l": [1, 2, 2, 3], "s": "b"}));
+        assert_eq!(pointers(&out), [(1, "/s")]);
+    }
+
+    #[test]
+    fn merge_by_key() {
+        let layers = [
+            json!({"users": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}, {"name": "no id"}]}),
+            json!({"users": [{"id": 2, "name": "B", "age": 3}, {"id": 9}, {"name": "no id"}, 5]}),
+        ];
+        let out = run(&layers, MergeStrategy::MergeByKey);
+        assert_eq!(
+            out.value["users"],
+            json!([
+                {"id": 1, "name": "a"},
+                {"id": 2, "name": "B", "age": 3},
+                {"name": "no id"},
+                {"id": 9},
+                {"name": "no id"},
+                5
+            ])
+        );
+        // 冲突路径使用元素在结果中的下标
+        assert_eq!(pointers(&out), [(1, "/users/1/name")]);
+    }
+
+    #[test]
+    fn merge_by_key_without_keys_is_a_plain_conflict() {
+        let layers = [json!({"l": [1, {"x": 1}]}), json!({"l": [{"x": 2}]})];
+        let out = run(&layers, MergeStrategy::MergeByKey);
+        assert_eq!(pointers(&out), [(1, "/l")]);
+        assert_eq!(out.value["l"], json!([{"x": 2}]));
+
+        let refs = [&layers[0], &layers[1]];
+        let options = MergeOptions {
+            strategy: MergeStrategy::MergeByKey,
+            array_key: "x".to_owned(),
+        };
+        let out = merge(&refs, &options, &HashMap::new());
+        assert_eq!(out.value["l"], json!([1, {"x": 1}, {"x": 2}]));
+        assert!(out.conflicts.is_empty());
+    }
+
+    #[test]
+    fn error_on_conflict_needs_choices() {
+        let layers = [json!({"a/b": 1, "c": 1}), json!({"a/b": 2, "c": 2}), json!({"c": 3})];
+        let out = run(&layers, MergeStrategy::ErrorOnConflict);
+        assert_eq!(pointers(&out), [(1, "/a~1b"), (1, "/c"), (2, "/c")]);
+        assert_eq!(out.unresolved(), 3);
+        // 未解决的冲突暂时保留前者
+        assert_eq!(out.value, layers[0]);
+
+        let refs: Vec<&Value> = layers.iter().collect();
+        let options = MergeOptions {
+            strategy: MergeStrategy::ErrorOnConflict,
+            ..MergeOptions::default()
+        };
+        let choices = HashMap::from([
+            ((1, "/a~1b".to_owned()), Side::Right),
+            ((1, "/c".to_owned()), Side::Left),
+            ((2, "/c".to_owned()), Side::Right),
+        ]);
+        let out = merge(&refs, &options, &choices);
+        assert_eq!(out.unresolved(), 0);
+        assert_eq!(out.value, json!({"a/b": 2, "c": 3}));
+        // 第二层的冲突看到的是第一层合并后的左值
+        assert_eq!(out.conflicts[2].left, json!(1));
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        assert_eq!(run(&[], MergeStrategy::Override).value, Value::Null);
+        let out = run(&[json!([1])], MergeStrategy::Override);
+        assert_eq!(out.value, json!([1]));
+        assert!(out.conflicts.is_empty());
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use appkit::theme::Palette;
+use eframe::egui;
+use serde_json::Value;
+
+use crate::bulk::summarize;
+use crate::bulk_panel::text_hash;
+use crate::document::to_string_with_indent;
+use crate::i18n::tr;
+use crate::merge::{merge, ConflictKey, MergeOptions, MergeOutcome, MergeStrategy, Side};
+
+/// 冲突列表的最大高度
+const CONFLICT_LIST_HEIGHT: f32 = 300.0;
+
+/// 可参与合并的标签页
+pub struct MergeSource<'a> {
+    pub id: u64,
+    pub title: String,
+    pub text: &'a str,
+    pub value: Option<&'a Value>,
+}
+
+/// 合并面板：按顺序深度合并多个标签页的文档，列出冲突并逐个选择采用哪一方
+pub struct MergePanel {
+    /// 参与合并的标签页 id，按合并顺序
+    layers: Vec<u64>,
+    options: MergeOptions,
+    /// 手动选择的冲突处理方式
+    choices: HashMap<ConflictKey, Side>,
+    /// 合并结果及对应输入的哈希
+    cache: Option<(u64, MergeOutcome)>,
+}
+
+impl MergePanel {
+    pub fn new(layers: Vec<u64>) -> Self {
+        Self {
+            layers,
+            options: MergeOptions::default(),
+            choices: HashMap::new(),
+            cache: None,
+        }
+    }
+
+    /// 输入文档、合并顺序和选项共同决定合并结果
+    fn input_key(&self, sources: &[&MergeSource]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for source in sources {
+            source.id.hash(&mut hasher);
+            text_hash(source.text).hash(&mut hasher);
+        }
+        self.options.strategy.hash(&mut hasher);
+        self.options.array_key.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 绘制面板内容；点击“在新标签页中打开”时返回合并结果
+    pub fn show(&mut self, ui: &mut egui::Ui, sources: &[MergeSource], indent_spaces: usize) -> Option<Value> {
+        let palette = Palette::of(ui);
+        let title_of = |id: u64| {
+            sources
+                .iter()
+                .find(|s| s.id == id)
+                .map_or_else(|| tr!("（已关闭）").to_owned(), |s| s.title.clone())
+        };
+
+        ui.label(tr!("按顺序合并，后面的文档合并到前面的结果上："));
+        let mut changed = false;
+        let mut swap = None;
+        let mut remove = None;
+        egui::Grid::new("merge_layers").num_columns(3).show(ui, |ui| {
+            let count = self.layers.len();
+            for (i, id) in self.layers.iter_mut().enumerate() {
+                ui.label(format!("{}.", i + 1));
+                egui::ComboBox::from_id_salt(("merge_layer", i))
+                    .selected_text(title_of(*id))
+                    .show_ui(ui, |ui| {
+                        for source in sources {
+                            changed |= ui.selectable_value(id, source.id, source.title.as_str()).changed();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(i > 0, egui::Button::new("⬆")).clicked() {
+                        swap = Some(i - 1);
+                    }
+                    if ui.add_enabled(i + 1 < count, egui::Button::new("⬇")).clicked() {
+                        swap = Some(i);
+                    }
+                    if ui.add_enabled(count > 2, egui::Button::new("🗑")).clicked() {
+                        remove = Some(i);
+                    }
+                    match sources.iter().find(|s| s.id == *id) {
+                        None => {
+                            ui.colored_label(palette.error, tr!("标签页已关闭"));
+                        }
+                        Some(source) if source.value.is_none() => {
+                            ui.colored_label(palette.error, tr!("不是有效的 JSON"));
+                        }
+                        Some(_) => {}
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if let Some(i) = swap {
+            self.layers.swap(i, i + 1);
+            changed = true;
+        }
+        if let Some(i) = remove {
+            self.layers.remove(i);
+            changed = true;
+        }
+        if ui.button(tr!("➕ 添加文档")).clicked() {
+            let next = sources.iter().find(|s| !self.layers.contains(&s.id)).or(sources.first());
+            if let Some(source) = next {
+                self.layers.push(source.id);
+                changed = true;
+            }
+        }
+        // 合并顺序变化后原来的冲突位置不再对应
+        if changed {
+            self.choices.clear();
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label(tr!("策略："));
+            egui::ComboBox::from_id_salt("merge_strategy")
+                .selected_text(self.options.strategy.label())
+                .show_ui(ui, |ui| {
+                    for strategy in MergeStrategy::ALL {
+                        ui.selectable_value(&mut self.options.strategy, strategy, strategy.label())
+                            .on_hover_text(strategy.description());
+                    }
+                });
+            if self.options.strategy == MergeStrategy::MergeByKey {
+                ui.label(tr!("匹配键："));
+                ui.add(egui::TextEdit::singleline(&mut self.options.array_key).desired_width(100.0));
+            }
+        });
+        ui.weak(self.options.strategy.description());
+
+        let layers: Vec<&MergeSource> = self
+            .layers
+            .iter()
+            .filter_map(|id| sources.iter().find(|s| s.id == *id))
+            .collect();
+        let values: Vec<&Value> = layers.iter().filter_map(|s| s.value).collect();
+        if values.len() < self.layers.len() {
+            ui.colored_label(palette.error, tr!("有文档无法参与合并"));
+            return None;
+        }
+        let key = self.input_key(&layers);
+        if self.cache.as_ref().is_none_or(|(k, _)| *k != key) {
+            self.cache = Some((key, merge(&values, &self.options, &self.choices)));
+        }
+        let (_, outcome) = self.cache.as_ref()?;
+
+        ui.separator();
+        let mut choose = Vec::new();
+        if outcome.conflicts.is_empty() {
+            ui.label(tr!("没有冲突"));
+        } else {
+            ui.horizontal(|ui| {
+                ui.label(tr!("{} 处冲突", outcome.conflicts.len()));
+                let unresolved = outcome.unresolved();
+                if unresolved > 0 {
+                    ui.colored_label(palette.error, tr!("{} 处未解决", unresolved));
+                }
+                if ui.button(tr!("全部取前者")).clicked() {
+                    choose.extend(outcome.conflicts.iter().map(|c| (c.key(), Side::Left)));
+                }
+                if ui.button(tr!("全部取后者")).clicked() {
+                    choose.extend(outcome.conflicts.iter().map(|c| (c.key(), Side::Right)));
+                }
+            });
+            egui::ScrollArea::vertical().max_height(CONFLICT_LIST_HEIGHT).show(ui, |ui| {
+                egui::Grid::new("merge_conflicts").num_columns(3).striped(true).show(ui, |ui| {
+                    for conflict in &outcome.conflicts {
+                        let pointer = if conflict.pointer.is_empty() { "/" } else { conflict.pointer.as_str() };
+                        let mut text = egui::RichText::new(pointer).monospace();
+                        if conflict.side.is_none() {
+                            text = text.color(palette.error);
+                        }
+                        ui.label(text)
+                            .on_hover_text(tr!("合并“{}”时", title_of(self.layers[conflict.layer])));
+                        let mut side = conflict.side;
+                        ui.radio_value(&mut side, Some(Side::Left), tr!("前者：{}", summarize(&conflict.left)));
+                        ui.radio_value(&mut side, Some(Side::Right), tr!("后者：{}", summarize(&conflict.right)));
+                        if side != conflict.side {
+                            choose.extend(side.map(|s| (conflict.key(), s)));
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        }
+
+        ui.separator();
+        let mut result = None;
+        ui.horizontal(|ui| {
+            let ready = outcome.unresolved() == 0;
+            if ui
+                .add_enabled(ready, egui::Button::new(tr!("📄 在新标签页中打开")))
+                .on_disabled_hover_text(tr!("还有未解决的冲突"))
+                .clicked()
+            {
+                result = Some(outcome.value.clone());
+            }
+            if ui
+                .add_enabled(ready, egui::Button::new(tr!("📋 复制结果")))
+                .on_disabled_hover_text(tr!("还有未解决的冲突"))
+                .clicked()
+            {
+                ui.ctx().copy_text(to_string_with_indent(&outcome.value, indent_spaces));
+            }
+        });
+
+        if !choose.is_empty() {
+            self.choices.extend(choose);
+            self.cache = None;
+        }
+        result
+    }
+}
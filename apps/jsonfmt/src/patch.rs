@@ -0,0 +1,457 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::document::to_string_with_indent;
+use crate::span::SpanMap;
+use crate::tree_ops::join_pointer;
+
+/// 中间区域超过该长度时不再逐个查找相同元素，直接按位置配对
+const MAX_MATCH_SCAN: usize = 256;
+
+/// 把树编辑的结果写回原文：未变化的节点原样保留，只重写发生变化的区域
+///
+/// 原文无法解析或与编辑前的值不一致时，退回到按指定缩进整体格式化。
+pub fn patch_text(text: &str, old: &Value, new: &Value, indent_spaces: usize) -> String {
+    let fallback = || to_string_with_indent(new, indent_spaces);
+    let Some(spans) = SpanMap::parse(text) else {
+        return fallback();
+    };
+    // 原文需与编辑前的值一致（包括键的顺序），否则位置信息不可靠
+    if !serde_json::from_str::<Value>(text).is_ok_and(|parsed| same(&parsed, old)) {
+        return fallback();
+    }
+    let Some(root) = spans.get("") else {
+        return fallback();
+    };
+
+    let patcher = Patcher {
+        text,
+        spans: &spans,
+        unit: detect_indent_unit(text).unwrap_or_else(|| " ".repeat(indent_spaces)),
+    };
+    let mut out = String::with_capacity(text.len());
+    out.push_str(&text[..root.value.start]);
+    patcher.node("", old, new, text.contains('\n'), &mut out);
+    out.push_str(&text[root.value.end..]);
+    out
+}
+
+/// 比较两个值是否完全相同；与 `==` 不同，对象的键顺序也必须一致
+fn same(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|((ka, va), (kb, vb))| ka == kb && same(va, vb))
+        }
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(x, y)| same(x, y)),
+        _ => a == b,
+    }
+}
+
+/// 从原文推断缩进单位：取所有缩进行中最短的前导空白
+fn detect_indent_unit(text: &str) -> Option<String> {
+    text.lines()
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .filter(|ws| !ws.is_empty())
+        .min_by_key(|ws| ws.len())
+        .map(str::to_string)
+}
+
+/// 容器中一个子节点在原文中的布局
+struct Slot<'a> {
+    pointer: String,
+    /// 上一个分隔符（{ [ 或 ,）之后到该子节点开始之间的空白
+    lead: &'a str,
+    /// 键（含引号），数组元素没有
+    key: Option<&'a str>,
+    /// 键与值之间的文本，例如 `": "`
+    colon: &'a str,
+}
+
+/// 容器在原文中的布局
+struct Layout<'a> {
+    open: usize,
+    close: usize,
+    slots: Vec<Slot<'a>>,
+    /// 最后一个子节点之后到右括号之间的文本；空容器时为括号内的全部文本
+    closing: &'a str,
+}
+
+struct Patcher<'a> {
+    text: &'a str,
+    spans: &'a SpanMap,
+    unit: String,
+}
+
+impl<'a> Patcher<'a> {
+    /// 输出节点的新文本；pretty 表示所在容器是否为多行格式
+    fn node(&self, pointer: &str, old: &Value, new: &Value, pretty: bool, out: &mut String) {
+        let Some(span) = self.spans.get(pointer) else {
+            self.replace(pointer, new, pretty, out);
+            return;
+        };
+        if same(old, new) {
+            out.push_str(&self.text[span.value.clone()]);
+            return;
+        }
+        match (old, new) {
+            (Value::Object(o), Value::Object(n)) if self.object(pointer, o, n, pretty, out) => {}
+            (Value::Array(o), Value::Array(n)) if self.array(pointer, o, n, pretty, out) => {}
+            _ => self.replace(pointer, new, pretty, out),
+        }
+    }
+
+    /// 整体重写节点：按其所在行的缩进序列化新值
+    fn replace(&self, pointer: &str, new: &Value, pretty: bool, out: &mut String) {
+        let indent = self.spans.get(pointer).map_or("", |span| self.line_indent(span.full().start));
+        out.push_str(&self.serialize(new, indent, pretty));
+    }
+
+    /// 按键名对齐新旧对象并重建；原文布局无法解析时返回 false
+    fn object(
+        &self,
+        pointer: &str,
+        old: &Map<String, Value>,
+        new: &Map<String, Value>,
+        parent_pretty: bool,
+        out: &mut String,
+    ) -> bool {
+        let Some(layout) = self.layout(pointer, old.keys().map(|k| join_pointer(pointer, k))) else {
+            return false;
+        };
+        let old_keys: Vec<&String> = old.keys().collect();
+
+        // 按键名配对；新键紧跟在上一个配对位置之后、且该位置的旧键已不存在时视为改名
+        let mut origins = Vec::with_capacity(new.len());
+        let mut prev: Option<usize> = None;
+        for key in new.keys() {
+            let origin = old_keys.iter().position(|k| *k == key).or_else(|| {
+                let candidate = prev.map_or(0, |p| p + 1);
+                old_keys
+                    .get(candidate)
+                    .filter(|k| !new.contains_key(k.as_str()))
+                    .map(|_| candidate)
+            });
+            if origin.is_some() {
+                prev = origin;
+            }
+            origins.push(origin);
+        }
+
+        let colon = layout.slots.first().map(|s| s.colon);
+        let children = new
+            .iter()
+            .zip(origins)
+            .map(|((key, value), origin)| {
+                // 键名未变时保留原文中的写法（例如转义形式）
+                let key_text = match origin {
+                    Some(i) if old_keys[i] == key => layout.slots[i].key.unwrap_or_default().to_string(),
+                    _ => serde_json::to_string(key).unwrap_or_default(),
+                };
+                let old_entry = origin.map(|i| (&layout.slots[i], &old[old_keys[i].as_str()]));
+                (Some(key_text), value, old_entry)
+            })
+            .collect();
+        self.container(pointer, &layout, colon, children, parent_pretty, out);
+        true
+    }
+
+    /// 对齐新旧数组元素并重建；原文布局无法解析时返回 false
+    fn array(&self, pointer: &str, old: &[Value], new: &[Value], parent_pretty: bool, out: &mut String) -> bool {
+        let Some(layout) = self.layout(pointer, (0..old.len()).map(|i| format!("{}/{}", pointer, i))) else {
+            return false;
+        };
+
+        // 相同的前缀、后缀直接配对，中间部分优先复用相等的旧元素，其次按位置配对
+        let prefix = old.iter().zip(new).take_while(|(o, n)| same(o, n)).count();
+        let max_suffix = old.len().min(new.len()) - prefix;
+        let suffix = old
+            .iter()
+            .rev()
+            .zip(new.iter().rev())
+            .take(max_suffix)
+            .take_while(|(o, n)| same(o, n))
+            .count();
+        let old_mid = prefix..old.len() - suffix;
+        let scan = old_mid.len() <= MAX_MATCH_SCAN;
+        let mut used = vec![false; old.len()];
+        let mut origins = vec![None; new.len()];
+        for (j, origin) in origins.iter_mut().enumerate() {
+            let i = if j < prefix {
+                Some(j)
+            } else if j >= new.len() - suffix {
+                Some(old.len() - (new.len() - j))
+            } else {
+                let by_value = if scan {
+                    old_mid.clone().find(|&i| !used[i] && same(&old[i], &new[j]))
+                } else {
+                    None
+                };
+                by_value.or_else(|| Some(j).filter(|&i| old_mid.contains(&i) && !used[i]))
+            };
+            if let Some(i) = i {
+                used[i] = true;
+            }
+            *origin = i;
+        }
+
+        let children = new
+            .iter()
+            .zip(origins)
+            .map(|(value, origin)| (None, value, origin.map(|i| (&layout.slots[i], &old[i]))))
+            .collect();
+        self.container(pointer, &layout, None, children, parent_pretty, out);
+        true
+    }
+
+    /// 按新的子节点列表重建容器文本，复用原有的空白、键和值文本
+    #[allow(clippy::type_complexity)]
+    fn container(
+        &self,
+        pointer: &str,
+        layout: &Layout,
+        colon: Option<&str>,
+        children: Vec<(Option<String>, &Value, Option<(&Slot, &Value)>)>,
+        parent_pretty: bool,
+        out: &mut String,
+    ) {
+        // 空容器沿用父容器的格式，否则看自身是否跨行
+        let pretty = if layout.slots.is_empty() {
+            parent_pretty
+        } else {
+            self.text[layout.open..layout.close].contains('\n')
+        };
+        let base = self.line_indent(self.spans.get(pointer).map_or(layout.open, |s| s.full().start));
+        let child_indent = format!("{}{}", base, self.unit);
+        let default_lead = match layout.slots.last() {
+            Some(slot) => slot.lead.to_string(),
+            None if pretty => format!("\n{}", child_indent),
+            None => String::new(),
+        };
+        let default_colon = colon.unwrap_or(if pretty { ": " } else { ":" });
+
+        out.push_str(&self.text[layout.open..layout.open + 1]);
+        let count = children.len();
+        for (idx, (key, value, old_entry)) in children.into_iter().enumerate() {
+            if idx > 0 {
+                out.push(',');
+            }
+            match old_entry {
+                Some((slot, old_value)) => {
+                    out.push_str(slot.lead);
+                    if let Some(key) = key {
+                        out.push_str(&key);
+                        out.push_str(slot.colon);
+                    }
+                    self.node(&slot.pointer, old_value, value, pretty, out);
+                }
+                None => {
+                    out.push_str(&default_lead);
+                    if let Some(key) = key {
+                        out.push_str(&key);
+                        out.push_str(default_colon);
+                    }
+                    let indent = default_lead.rsplit('\n').next().unwrap_or_default();
+                    out.push_str(&self.serialize(value, indent, pretty));
+                }
+            }
+        }
+
+        if count > 0 {
+            if !layout.slots.is_empty() {
+                out.push_str(layout.closing);
+            } else if pretty {
+                out.push('\n');
+                out.push_str(base);
+            }
+        }
+        out.push_str(&self.text[layout.close..layout.close + 1]);
+    }
+
+    /// 解析容器中每个子节点的布局
+    fn layout(&self, pointer: &str, children: impl Iterator<Item = String>) -> Option<Layout<'a>> {
+        let span = self.spans.get(pointer)?;
+        let open = span.value.start;
+        let close = span.value.end - 1;
+        let mut slots = Vec::new();
+        let mut cursor = open + 1;
+        let mut last_end = None;
+        for child_pointer in children {
+            let child = self.spans.get(&child_pointer)?;
+            let full = child.full();
+            if let Some(end) = last_end {
+                // 跳过上一个值之后的逗号；有重复键时子节点的位置可能不按顺序，此时放弃按原文布局
+                cursor = end + self.text.get(end..full.start)?.find(',')? + 1;
+            }
+            slots.push(Slot {
+                lead: &self.text[cursor..full.start],
+                key: child.key.clone().map(|k| &self.text[k]),
+                colon: child.key.as_ref().map_or("", |k| &self.text[k.end..child.value.start]),
+                pointer: child_pointer,
+            });
+            last_end = Some(child.value.end);
+        }
+        let closing = &self.text[last_end.unwrap_or(open + 1)..close];
+        Some(Layout {
+            open,
+            close,
+            slots,
+            closing,
+        })
+    }
+
+    /// 指定位置所在行的前导空白
+    fn line_indent(&self, pos: usize) -> &'a str {
+        let line_start = self.text[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let line = &self.text[line_start..];
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    /// 序列化新值：多行格式时沿用原文的缩进单位，并整体缩进到当前层级
+    fn serialize(&self, value: &Value, indent: &str, pretty: bool) -> String {
+        if !pretty {
+            return serde_json::to_string(value).unwrap_or_default();
+        }
+        let mut buf = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(self.unit.as_bytes());
+        let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+        let _ = value.serialize(&mut ser);
+        String::from_utf8(buf)
+            .unwrap_or_default()
+            .replace('\n', &format!("\n{}", indent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::edit::{apply_edit, JsonEdit};
+
+    /// 对原文应用 edit，返回写回后的文本；同时检查结果能解析回新值
+    fn patch(text: &str, edit: impl FnOnce(&mut Value)) -> String {
+        let old: Value = serde_json::from_str(text).unwrap();
+        let mut new = old.clone();
+        edit(&mut new);
+        let out = patch_text(text, &old, &new, 2);
+        let parsed: Value = serde_json::from_str(&out).unwrap();
+        assert!(same(&parsed, &new), "{out}");
+        out
+    }
+
+    /// 通过删除编辑移除根对象的键，与界面上的删除走同一路径
+    fn delete(v: &mut Value, key: &str) {
+        apply_edit(
+            v,
+            JsonEdit::DeleteFromObject {
+                object_pointer: String::new(),
+                key: key.to_owned(),
+            },
+        );
+    }
+
+    #[test]
+    fn untouched_text_is_kept_verbatim() {
+        let text = "{\n    \"a\" :  1,\n    \"b\": [ 1,2 ,3 ],\n    \"c\": \"\\u0041\"\n}\n";
+        assert_eq!(patch(text, |v| v["a"] = json!(2)), "{\n    \"a\" :  2,\n    \"b\": [ 1,2 ,3 ],\n    \"c\": \"\\u0041\"\n}\n");
+        assert_eq!(patch(text, |_| {}), text);
+    }
+
+    #[test]
+    fn insert_into_pretty_object_uses_detected_indent() {
+        let text = "{\n\t\"a\": 1,\n\t\"b\": {\n\t\t\"x\": true\n\t}\n}";
+        let out = patch(text, |v| v["b"]["y"] = json!({"z": [1]}));
+        assert_eq!(out, "{\n\t\"a\": 1,\n\t\"b\": {\n\t\t\"x\": true,\n\t\t\"y\": {\n\t\t\t\"z\": [\n\t\t\t\t1\n\t\t\t]\n\t\t}\n\t}\n}");
+    }
+
+    #[test]
+    fn compact_text_stays_compact() {
+        let text = r#"{"a":1,"b":[1,2]}"#;
+        assert_eq!(patch(text, |v| v["c"] = json!({"d": null})), r#"{"a":1,"b":[1,2],"c":{"d":null}}"#);
+        assert_eq!(patch(text, |v| v["b"].as_array_mut().unwrap().insert(1, json!(9))), r#"{"a":1,"b":[1,9,2]}"#);
+    }
+
+    #[test]
+    fn remove_keeps_neighbours() {
+        let text = "{\n  \"a\": 1,\n  \"b\": 2,\n  \"c\": 3\n}";
+        assert_eq!(patch(text, |v| delete(v, "b")), "{\n  \"a\": 1,\n  \"c\": 3\n}");
+        assert_eq!(patch(text, |v| delete(v, "c")), "{\n  \"a\": 1,\n  \"b\": 2\n}");
+        assert_eq!(patch(text, |v| delete(v, "a")), "{\n  \"b\": 2,\n  \"c\": 3\n}");
+    }
+
+    #[test]
+    fn rename_keeps_position_and_value_text() {
+        let text = "{\"a\": 1.50, \"b\": 2}";
+        let out = patch(text, |v| {
+            apply_edit(
+                v,
+                JsonEdit::EditObjectKey {
+                    object_pointer: String::new(),
+                    old_key: "a".to_owned(),
+                    new_key: "renamed".to_owned(),
+                },
+            )
+        });
+        assert_eq!(out, "{\"renamed\": 1.50, \"b\": 2}");
+    }
+
+    #[test]
+    fn escaped_keys_keep_their_spelling() {
+        let text = r#"{"\u0061/b": {"m~n": 1}, "z": 0}"#;
+        assert_eq!(patch(text, |v| v["a/b"]["m~n"] = json!(2)), r#"{"\u0061/b": {"m~n": 2}, "z": 0}"#);
+    }
+
+    #[test]
+    fn array_edits_reuse_equal_elements() {
+        let text = "[\n  {\"id\": 1},\n  {\"id\": 2},\n  {\"id\": 3}\n]";
+        let out = patch(text, |v| {
+            v.as_array_mut().unwrap().remove(0);
+        });
+        assert_eq!(out, "[\n  {\"id\": 2},\n  {\"id\": 3}\n]");
+        let out = patch(text, |v| v.as_array_mut().unwrap().swap(0, 2));
+        assert_eq!(out, "[\n  {\"id\": 3},\n  {\"id\": 2},\n  {\"id\": 1}\n]");
+    }
+
+    #[test]
+    fn empty_container_follows_parent_layout() {
+        let text = "{\n  \"list\": [],\n  \"obj\": {}\n}";
+        let out = patch(text, |v| {
+            v["list"] = json!([1]);
+            v["obj"] = json!({"k": "v"});
+        });
+        assert_eq!(out, "{\n  \"list\": [\n    1\n  ],\n  \"obj\": {\n    \"k\": \"v\"\n  }\n}");
+    }
+
+    #[test]
+    fn nested_replacement_is_indented_at_its_level() {
+        let text = "{\n  \"a\": {\n    \"b\": 1\n  }\n}";
+        let out = patch(text, |v| v["a"]["b"] = json!([true]));
+        assert_eq!(out, "{\n  \"a\": {\n    \"b\": [\n      true\n    ]\n  }\n}");
+    }
+
+    #[test]
+    fn falls_back_to_formatting() {
+        let old = json!({"a": 1});
+        let new = json!({"a": 2});
+        // 原文与编辑前的值不一致（含键顺序）或无法解析时整体格式化
+        assert_eq!(patch_text(r#"{"a": 5}"#, &old, &new, 2), "{\n  \"a\": 2\n}");
+        assert_eq!(patch_text("{\"a\": 1,}", &old, &new, 4), "{\n    \"a\": 2\n}");
+        let old = json!({"a": 1, "b": 2});
+        assert_eq!(patch_text(r#"{"b":2,"a":1}"#, &old, &json!({"a": 1}), 0), "{\n\"a\": 1\n}");
+    }
+
+    #[test]
+    fn duplicate_keys_rewrite_the_object() {
+        // 重复键按最后一次出现取值，但位置仍在第一次出现处，无法对齐原文布局
+        let text = r#"{"a": 1, "b": 2, "a": 3}"#;
+        assert_eq!(patch(text, |v| v["b"] = json!(5)), r#"{"a":3,"b":5}"#);
+        let text = "{\n  \"x\": {\"a\": 1, \"b\": 2, \"a\": 3},\n  \"y\": 0\n}";
+        assert_eq!(patch(text, |v| v["x"]["b"] = json!(5)), "{\n  \"x\": {\n    \"a\": 3,\n    \"b\": 5\n  },\n  \"y\": 0\n}");
+    }
+
+    #[test]
+    fn detects_indent_unit() {
+        assert_eq!(detect_indent_unit("{\n    \"a\": {\n        \"b\": 1\n    }\n}").as_deref(), Some("    "));
+        assert_eq!(detect_indent_unit("{\"a\": 1}"), None);
+    }
+}
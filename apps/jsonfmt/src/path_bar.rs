@@ -0,0 +1,97 @@
+use appkit::theme::Palette;
+use eframe::egui;
+use serde_json::Value;
+
+use crate::i18n::tr;
+use crate::json_path::{self, PathSyntax};
+
+/// 解析树上方的路径栏：选中节点的面包屑导航和按路径跳转
+#[derive(Default)]
+pub struct PathBar {
+    input: String,
+    /// 跳转结果的提示，出错时为 Err
+    message: Option<Result<String, String>>,
+}
+
+impl PathBar {
+    /// 按输入的路径表达式查找节点
+    fn jump(&mut self, root: &Value) -> Option<String> {
+        match json_path::select(root, &self.input) {
+            Ok(hits) if hits.is_empty() => {
+                self.message = Some(Err(tr!("没有匹配的节点").to_string()));
+                None
+            }
+            Ok(hits) => {
+                self.message = (hits.len() > 1).then(|| Ok(tr!("共 {} 个匹配，已跳到第一个", hits.len())));
+                hits.into_iter().next()
+            }
+            Err(e) => {
+                self.message = Some(Err(e));
+                None
+            }
+        }
+    }
+
+    /// 绘制路径栏，返回需要跳转到的节点路径
+    pub fn show(&mut self, ui: &mut egui::Ui, root: Option<&Value>, selected: Option<&str>) -> Option<String> {
+        let root = root?;
+        let mut target = None;
+
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+            if ui.selectable_label(selected == Some(""), "$").on_hover_text(tr!("根节点")).clicked() {
+                target = Some(String::new());
+            }
+            let pointer = selected.unwrap_or_default();
+            for segment in json_path::segments(root, pointer) {
+                ui.weak("›");
+                let text = if segment.index { format!("[{}]", segment.name) } else { segment.name };
+                let current = segment.pointer == pointer;
+                if ui.selectable_label(current, text).clicked() {
+                    target = Some(segment.pointer);
+                }
+            }
+            if let Some(pointer) = selected {
+                ui.add_space(6.0);
+                ui.menu_button("📋", |ui| {
+                    for syntax in PathSyntax::ALL {
+                        let path = json_path::format_path(root, pointer, syntax);
+                        let button = ui.button(tr!("复制为 {}", syntax.label())).on_hover_text(&path);
+                        if button.clicked() {
+                            ui.ctx().copy_text(path);
+                            ui.close();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text(tr!("以不同写法复制路径"));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("跳转："));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.input)
+                    .hint_text(tr!("/users/0、$.users[0].name 或 users[0].name"))
+                    .desired_width(f32::INFINITY),
+            );
+            if response.changed() {
+                self.message = None;
+            }
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.input.trim().is_empty() {
+                target = self.jump(root);
+                response.request_focus();
+            }
+        });
+        match &self.message {
+            Some(Ok(info)) => {
+                ui.weak(info);
+            }
+            Some(Err(err)) => {
+                ui.colored_label(Palette::of(ui).error, err);
+            }
+            None => {}
+        }
+        target
+    }
+}
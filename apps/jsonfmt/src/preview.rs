@@ -0,0 +1,235 @@
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// 2000-01-01 到 2100-01-01 之间的秒数才视为时间戳
+const SECONDS_RANGE: std::ops::Range<i64> = 946_684_800..4_102_444_800;
+/// UUID v1 的时间起点（1582-10-15）到 Unix 纪元的 100 纳秒数
+const UUID_EPOCH_OFFSET: u64 = 0x01B2_1DD2_1381_4000;
+
+/// 叶子值可识别出的附加信息
+pub enum Preview<'a> {
+    /// 以秒或毫秒表示的 Unix 时间戳
+    Timestamp { time: DateTime<Utc>, millis: bool },
+    /// 十六进制颜色，RGBA
+    Color([u8; 4]),
+    Url(&'a str),
+    /// data:image/...;base64, 形式的图片
+    DataImage { mime: &'a str, data: &'a str },
+    Jwt(&'a str),
+    Uuid { version: u8, time: Option<DateTime<Utc>> },
+}
+
+/// 识别叶子值；只做廉价的格式判断，解码留到需要显示时再做
+pub fn detect(value: &Value) -> Option<Preview<'_>> {
+    match value {
+        Value::Number(n) => timestamp(n.as_i64()?),
+        Value::String(s) => {
+            let s = s.trim();
+            color(s)
+                .map(Preview::Color)
+                .or_else(|| data_image(s))
+                .or_else(|| url(s))
+                .or_else(|| jwt(s))
+                .or_else(|| uuid(s))
+        }
+        _ => None,
+    }
+}
+
+fn timestamp(n: i64) -> Option<Preview<'static>> {
+    let millis = !SECONDS_RANGE.contains(&n);
+    if millis && !SECONDS_RANGE.contains(&(n / 1000)) {
+        return None;
+    }
+    let time = if millis { DateTime::from_timestamp_millis(n)? } else { DateTime::from_timestamp(n, 0)? };
+    Some(Preview::Timestamp { time, millis })
+}
+
+/// #rgb、#rgba、#rrggbb 或 #rrggbbaa
+fn color(s: &str) -> Option<[u8; 4]> {
+    let hex = s.strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |i: usize| u8::from_str_radix(&hex[i..=i], 16).ok().map(|d| d * 17);
+    let pair = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 => Some([digit(0)?, digit(1)?, digit(2)?, 255]),
+        4 => Some([digit(0)?, digit(1)?, digit(2)?, digit(3)?]),
+        6 => Some([pair(0)?, pair(2)?, pair(4)?, 255]),
+        8 => Some([pair(0)?, pair(2)?, pair(4)?, pair(6)?]),
+        _ => None,
+    }
+}
+
+fn data_image(s: &str) -> Option<Preview<'_>> {
+    let rest = s.strip_prefix("data:")?;
+    let (mime, data) = rest.split_once(";base64,")?;
+    mime.starts_with("image/").then_some(Preview::DataImage { mime, data })
+}
+
+fn url(s: &str) -> Option<Preview<'_>> {
+    let is_url = (s.starts_with("http://") || s.starts_with("https://")) && !s.contains(char::is_whitespace);
+    is_url.then_some(Preview::Url(s))
+}
+
+fn jwt(s: &str) -> Option<Preview<'_>> {
+    let token = s.strip_prefix("Bearer ").unwrap_or(s);
+    let parts: Vec<&str> = token.split('.').collect();
+    let is_jwt = parts.len() == 3
+        && token.starts_with("eyJ")
+        && parts[..2].iter().all(|p| !p.is_empty())
+        && token.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '='));
+    is_jwt.then_some(Preview::Jwt(token))
+}
+
+fn uuid(s: &str) -> Option<Preview<'static>> {
+    let bytes = s.as_bytes();
+    let valid = bytes.len() == 36
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        });
+    if !valid {
+        return None;
+    }
+    let hex: String = s.chars().filter(|c| *c != '-').collect();
+    let version = u8::from_str_radix(&hex[12..13], 16).ok()?;
+    let time = match version {
+        // v1：60 位时间戳，单位 100 纳秒，起点 1582-10-15
+        1 => {
+            let low = u64::from_str_radix(&hex[0..8], 16).ok()?;
+            let mid = u64::from_str_radix(&hex[8..12], 16).ok()?;
+            let high = u64::from_str_radix(&hex[13..16], 16).ok()?;
+            let ticks = (high << 48) | (mid << 32) | low;
+            let micros = ticks.checked_sub(UUID_EPOCH_OFFSET)? / 10;
+            DateTime::from_timestamp_micros(i64::try_from(micros).ok()?)
+        }
+        // v7：前 48 位是 Unix 毫秒数
+        7 => DateTime::from_timestamp_millis(i64::from_str_radix(&hex[0..12], 16).ok()?),
+        _ => None,
+    };
+    Some(Preview::Uuid { version, time })
+}
+
+/// 解码 JWT 的头部和载荷
+pub fn decode_jwt(token: &str) -> Option<(Value, Value)> {
+    let mut parts = token.split('.');
+    let mut part = || -> Option<Value> {
+        let bytes = URL_SAFE_NO_PAD.decode(parts.next()?.trim_end_matches('=')).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    };
+    Some((part()?, part()?))
+}
+
+/// 解码 data URI 中的 base64 数据
+pub fn decode_data(data: &str) -> Option<Vec<u8>> {
+    let cleaned: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+    STANDARD.decode(cleaned).ok()
+}
+
+/// JWT 中以秒表示时间的声明；名称在显示时翻译
+pub const JWT_TIME_CLAIMS: [(&str, &str); 3] = [("exp", "过期时间"), ("iat", "签发时间"), ("nbf", "生效时间")];
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// 识别为时间戳时返回 (RFC 3339 时间, 是否毫秒)
+    fn timestamp_of(value: Value) -> Option<(String, bool)> {
+        match detect(&value)? {
+            Preview::Timestamp { time, millis } => Some((time.to_rfc3339(), millis)),
+            _ => None,
+        }
+    }
+
+    fn color_of(s: &str) -> Option<[u8; 4]> {
+        match detect(&json!(s))? {
+            Preview::Color(rgba) => Some(rgba),
+            _ => None,
+        }
+    }
+
+    fn uuid_of(s: &str) -> Option<(u8, Option<String>)> {
+        match detect(&json!(s))? {
+            Preview::Uuid { version, time } => Some((version, time.map(|t| t.to_rfc3339()))),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn timestamp_range_boundaries() {
+        assert_eq!(timestamp_of(json!(946_684_800)), Some(("2000-01-01T00:00:00+00:00".into(), false)));
+        assert_eq!(timestamp_of(json!(4_102_444_799i64)), Some(("2099-12-31T23:59:59+00:00".into(), false)));
+        assert_eq!(timestamp_of(json!(946_684_799)), None);
+        assert_eq!(timestamp_of(json!(4_102_444_800i64)), None);
+        assert_eq!(timestamp_of(json!(946_684_800_000i64)), Some(("2000-01-01T00:00:00+00:00".into(), true)));
+        assert_eq!(timestamp_of(json!(4_102_444_799_999i64)), Some(("2099-12-31T23:59:59.999+00:00".into(), true)));
+        assert_eq!(timestamp_of(json!(946_684_799_999i64)), None);
+        assert_eq!(timestamp_of(json!(4_102_444_800_000i64)), None);
+        assert_eq!(timestamp_of(json!(-1_700_000_000)), None);
+        assert_eq!(timestamp_of(json!(1_700_000_000.5)), None);
+        assert_eq!(timestamp_of(json!("1700000000")), None);
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(color_of("#abc"), Some([0xaa, 0xbb, 0xcc, 255]));
+        assert_eq!(color_of("#aBcD"), Some([0xaa, 0xbb, 0xcc, 0xdd]));
+        assert_eq!(color_of(" #102030 "), Some([0x10, 0x20, 0x30, 255]));
+        assert_eq!(color_of("#11223344"), Some([0x11, 0x22, 0x33, 0x44]));
+        assert_eq!(color_of("#12345"), None);
+        assert_eq!(color_of("#ggg"), None);
+        assert_eq!(color_of("abc"), None);
+    }
+
+    #[test]
+    fn uuid_versions_and_times() {
+        // RFC 9562 附录 A 的示例，两者都是 2022-02-22 19:22:22 UTC
+        assert_eq!(
+            uuid_of("C232AB00-9414-11EC-B3C8-9F6BDECED846"),
+            Some((1, Some("2022-02-22T19:22:22+00:00".into())))
+        );
+        assert_eq!(
+            uuid_of("017f22e2-79b0-7cc3-98c4-dc0c0c07398f"),
+            Some((7, Some("2022-02-22T19:22:22+00:00".into())))
+        );
+        assert_eq!(uuid_of("919108f7-52d1-4320-9bac-f847db4148a8"), Some((4, None)));
+        assert_eq!(uuid_of("919108f7-52d1-4320-9bac-f847db4148a"), None);
+        assert_eq!(uuid_of("919108f7+52d1-4320-9bac-f847db4148a8"), None);
+    }
+
+    #[test]
+    fn jwt_with_and_without_bearer() {
+        let token = "eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.sig";
+        for text in [token.to_string(), format!("Bearer {}", token), format!("  Bearer {}  ", token)] {
+            match detect(&json!(text)) {
+                Some(Preview::Jwt(found)) => assert_eq!(found, token),
+                _ => panic!("{text}"),
+            }
+        }
+        assert!(detect(&json!(format!("bearer {}", token))).is_none());
+        assert!(detect(&json!("eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0")).is_none());
+        let (header, payload) = decode_jwt(token).unwrap();
+        assert_eq!(header, json!({"alg": "HS256"}));
+        assert_eq!(payload, json!({"sub": "1"}));
+    }
+
+    #[test]
+    fn urls_and_data_images() {
+        assert!(matches!(detect(&json!("https://example.org/a?b=1")), Some(Preview::Url(_))));
+        assert!(detect(&json!("https://example.org/a b")).is_none());
+        match detect(&json!("data:image/png;base64,aGk=")) {
+            Some(Preview::DataImage { mime, data }) => {
+                assert_eq!(mime, "image/png");
+                assert_eq!(decode_data(data).unwrap(), b"hi");
+            }
+            _ => panic!("data URI"),
+        }
+        assert!(detect(&json!("data:text/plain;base64,aGk=")).is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use chrono::{DateTime, Local, Utc};
+use appkit::theme::Palette;
+use eframe::egui;
+use serde_json::Value;
+
+use crate::i18n::{self, tr};
+use crate::preview::{self, Preview, JWT_TIME_CLAIMS};
+
+/// 缩略图的最大边长
+const THUMBNAIL_SIZE: u32 = 160;
+/// 最多缓存的缩略图数，超出时丢弃最久未显示的，释放其纹理
+const MAX_THUMBNAILS: usize = 16;
+
+/// 本地时间（带时区）和 UTC 时间
+fn format_time(time: DateTime<Utc>) -> String {
+    format!(
+        "{}\n{} UTC",
+        time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S%.3f %:z"),
+        time.format("%Y-%m-%d %H:%M:%S%.3f")
+    )
+}
+
+/// 树中叶子值的预览：值后面的小标记，悬停时显示详情
+#[derive(Default)]
+pub struct ValuePreviews {
+    /// data URI 图片的缩略图及数据的哈希，最近显示的在末尾；解码失败时为 None
+    thumbnails: Vec<(u64, Option<egui::TextureHandle>)>,
+}
+
+impl ValuePreviews {
+    /// 在值后面绘制预览标记
+    pub fn show_inline(&mut self, ui: &mut egui::Ui, value: &Value) {
+        let Some(preview) = preview::detect(value) else {
+            return;
+        };
+        match preview {
+            Preview::Timestamp { time, millis } => {
+                let unit = if millis { tr!("毫秒") } else { tr!("秒") };
+                ui.weak("🕒")
+                    .on_hover_text(tr!("可能是以{}为单位的时间戳：\n{}", unit, format_time(time)));
+            }
+            Preview::Color([r, g, b, a]) => {
+                let size = egui::Vec2::splat(ui.text_style_height(&egui::TextStyle::Body) * 0.8);
+                let (rect, response) = ui.allocate_exact_size(size, egui::Sense::hover());
+                let color = egui::Color32::from_rgba_unmultiplied(r, g, b, a);
+                ui.painter().rect_filled(rect, 2.0, color);
+                ui.painter().rect_stroke(
+                    rect,
+                    2.0,
+                    egui::Stroke::new(1.0, ui.visuals().widgets.noninteractive.fg_stroke.color),
+                    egui::StrokeKind::Outside,
+                );
+                response.on_hover_text(format!("rgba({}, {}, {}, {:.2})", r, g, b, a as f32 / 255.0));
+            }
+            Preview::Url(url) => {
+                let response = ui
+                    .add(egui::Button::new("🔗").small().frame(false))
+                    .on_hover_text(tr!("在浏览器中打开 {}", url));
+                if response.clicked() {
+                    ui.ctx().open_url(egui::OpenUrl::new_tab(url));
+                }
+            }
+            Preview::DataImage { mime, data } => {
+                ui.weak("🖼").on_hover_ui(|ui| {
+                    ui.label(mime);
+                    match self.thumbnail(ui.ctx(), data) {
+                        Some(texture) => {
+                            ui.image((texture.id(), texture.size_vec2()));
+                        }
+                        None => {
+                            ui.weak(tr!("无法解码图片"));
+                        }
+                    }
+                });
+            }
+            Preview::Jwt(token) => {
+                ui.weak("🔑").on_hover_ui(|ui| show_jwt(ui, token));
+            }
+            Preview::Uuid { version, time } => {
+                let text = match time {
+                    Some(time) => tr!("UUID v{}，生成时间：\n{}", version, format_time(time)),
+                    None => format!("UUID v{}", version),
+                };
+                ui.weak("🆔").on_hover_text(text);
+            }
+        }
+    }
+
+    /// 解码并缓存缩略图
+    fn thumbnail(&mut self, ctx: &egui::Context, data: &str) -> Option<&egui::TextureHandle> {
+        let mut hasher = DefaultHasher::new();
+        data.hash(&mut hasher);
+        let key = hasher.finish();
+        let entry = match self.thumbnails.iter().position(|(k, _)| *k == key) {
+            Some(i) => self.thumbnails.remove(i),
+            None => (key, load_thumbnail(ctx, data, key)),
+        };
+        if self.thumbnails.len() >= MAX_THUMBNAILS {
+            self.thumbnails.remove(0);
+        }
+        self.thumbnails.push(entry);
+        self.thumbnails.last()?.1.as_ref()
+    }
+}
+
+/// 解码 data URI 图片并生成缩略图纹理
+fn load_thumbnail(ctx: &egui::Context, data: &str, key: u64) -> Option<egui::TextureHandle> {
+    let bytes = preview::decode_data(data)?;
+    let image = image::load_from_memory(&bytes).ok()?;
+    let rgba = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    let color_image = egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw());
+    Some(ctx.load_texture(format!("data_image_{}", key), color_image, Default::default()))
+}
+
+/// 显示 JWT 解码后的头部、载荷和时间声明
+fn show_jwt(ui: &mut egui::Ui, token: &str) {
+    let Some((header, payload)) = preview::decode_jwt(token) else {
+        ui.weak(tr!("无法解码 JWT"));
+        return;
+    };
+    ui.strong(tr!("头部"));
+    ui.monospace(serde_json::to_string_pretty(&header).unwrap_or_default());
+    ui.strong(tr!("载荷"));
+    ui.monospace(serde_json::to_string_pretty(&payload).unwrap_or_default());
+    for (claim, label) in JWT_TIME_CLAIMS {
+        let Some(time) = payload.get(claim).and_then(Value::as_i64).and_then(|s| DateTime::from_timestamp(s, 0)) else {
+            continue;
+        };
+        ui.label(tr!("{}：{}", i18n::t(label), format_time(time).replace('\n', " / ")));
+        if claim == "exp" && time < Utc::now() {
+            ui.colored_label(Palette::of(ui).error, tr!("已过期"));
+        }
+    }
+    ui.weak(tr!("签名未校验"));
+}
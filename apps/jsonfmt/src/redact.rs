@@ -0,0 +1,430 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::bulk::{summarize, BulkChange, BulkPlan};
+use crate::edit::JsonEdit;
+use crate::i18n::tr;
+use crate::tree_ops::join_pointer;
+
+/// 敏感值的处理方式
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RedactAction {
+    /// 遮盖，保留少量可辨认的部分（例如卡号后四位）
+    Mask,
+    /// 替换为 HMAC-SHA256 摘要，同一次运行中相同的值得到相同的结果
+    Hash,
+    /// 替换为格式相同的假数据，相同的值得到相同的假数据
+    Fake,
+}
+
+impl RedactAction {
+    pub const ALL: [RedactAction; 3] = [RedactAction::Mask, RedactAction::Hash, RedactAction::Fake];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RedactAction::Mask => tr!("遮盖"),
+            RedactAction::Hash => tr!("哈希"),
+            RedactAction::Fake => tr!("替换为假数据"),
+        }
+    }
+}
+
+/// 一组脱敏规则，可保存为配置
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionProfile {
+    pub name: String,
+    /// 键名匹配该正则（不区分大小写）的值整体脱敏
+    pub key_pattern: String,
+    pub detect_email: bool,
+    pub detect_card: bool,
+    pub detect_jwt: bool,
+    pub detect_ip: bool,
+    pub action: RedactAction,
+}
+
+impl Default for RedactionProfile {
+    fn default() -> Self {
+        Self {
+            name: tr!("默认").to_string(),
+            key_pattern: "password|passwd|pwd|secret|token|api[_-]?key|authorization|cookie|session".to_string(),
+            detect_email: true,
+            detect_card: true,
+            detect_jwt: true,
+            detect_ip: true,
+            action: RedactAction::Mask,
+        }
+    }
+}
+
+/// 哈希密钥，每次启动程序时随机生成：同一次运行中的预览、复制和导出结果一致，
+/// 又无法通过对常见值（手机号、短密码等）逐个计算摘要来反查原值
+fn session_key() -> [u8; 32] {
+    static KEY: OnceLock<[u8; 32]> = OnceLock::new();
+    *KEY.get_or_init(|| {
+        let mut key = [0; 32];
+        getrandom::fill(&mut key).expect("系统随机数不可用");
+        key
+    })
+}
+
+/// 字符串中可识别的敏感内容
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    /// 键名命中规则的整个值
+    Secret,
+    Jwt,
+    Email,
+    Card,
+    Ip,
+}
+
+/// 按配置对 JSON 做脱敏
+pub struct Redactor {
+    key_re: Option<Regex>,
+    detectors: Vec<(Kind, Regex)>,
+    action: RedactAction,
+    /// 假数据对照表，保证同一原值总是得到同一假数据
+    fakes: HashMap<(Kind, String), String>,
+    counters: HashMap<Kind, usize>,
+    hash_key: [u8; 32],
+}
+
+impl Redactor {
+    pub fn new(profile: &RedactionProfile) -> Result<Self, String> {
+        let key_re = if profile.key_pattern.trim().is_empty() {
+            None
+        } else {
+            let re = RegexBuilder::new(&profile.key_pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| tr!("键名规则无效：{}", e))?;
+            Some(re)
+        };
+        // JWT 含有点号，需先于邮箱和 IP 识别
+        let patterns = [
+            (profile.detect_jwt, Kind::Jwt, r"eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*"),
+            (profile.detect_email, Kind::Email, r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+            (profile.detect_card, Kind::Card, r"\b\d(?:[ -]?\d){12,18}\b"),
+            (
+                profile.detect_ip,
+                Kind::Ip,
+                r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b",
+            ),
+        ];
+        let detectors = patterns
+            .into_iter()
+            .filter(|(enabled, _, _)| *enabled)
+            .map(|(_, kind, pattern)| (kind, Regex::new(pattern).expect("内置规则有效")))
+            .collect();
+        Ok(Self {
+            key_re,
+            detectors,
+            action: profile.action,
+            fakes: HashMap::new(),
+            counters: HashMap::new(),
+            hash_key: session_key(),
+        })
+    }
+
+    /// 生成脱敏需要的所有修改
+    pub fn plan(&mut self, root: &Value) -> BulkPlan {
+        let mut changes = Vec::new();
+        self.walk(root, String::new(), false, &mut changes);
+        BulkPlan { changes, skipped: 0 }
+    }
+
+    /// 返回脱敏后的副本，不修改原值
+    pub fn redacted(&mut self, root: &Value) -> Value {
+        let mut value = root.clone();
+        for change in self.plan(root).changes {
+            let JsonEdit::EditValue { pointer, new_value } = change.edit else {
+                continue;
+            };
+            if let Some(target) = value.pointer_mut(&pointer) {
+                *target = new_value;
+            }
+        }
+        value
+    }
+
+    fn walk(&mut self, value: &Value, pointer: String, secret: bool, changes: &mut Vec<BulkChange>) {
+        let new_value = match value {
+            Value::Object(map) => {
+                for (k, child) in map {
+                    let child_secret = secret || self.key_re.as_ref().is_some_and(|re| re.is_match(k));
+                    self.walk(child, join_pointer(&pointer, k), child_secret, changes);
+                }
+                return;
+            }
+            Value::Array(arr) => {
+                for (i, child) in arr.iter().enumerate() {
+                    self.walk(child, format!("{}/{}", pointer, i), secret, changes);
+                }
+                return;
+            }
+            Value::Null => return,
+            _ if secret => {
+                let text = value.as_str().map_or_else(|| value.to_string(), str::to_string);
+                Value::String(self.replace(Kind::Secret, &text))
+            }
+            Value::String(s) => Value::String(self.scan(s)),
+            // 数字形式的卡号
+            Value::Number(n) => {
+                let text = n.to_string();
+                let scanned = self.scan(&text);
+                if scanned == text {
+                    return;
+                }
+                Value::String(scanned)
+            }
+            Value::Bool(_) => return,
+        };
+        if new_value != *value {
+            changes.push(BulkChange {
+                before: summarize(value),
+                after: Some(summarize(&new_value)),
+                edit: JsonEdit::EditValue {
+                    pointer: pointer.clone(),
+                    new_value,
+                },
+                pointer,
+            });
+        }
+    }
+
+    /// 替换字符串中识别出的敏感内容
+    fn scan(&mut self, text: &str) -> String {
+        let mut text = text.to_string();
+        let detectors = std::mem::take(&mut self.detectors);
+        for (kind, re) in &detectors {
+            text = re
+                .replace_all(&text, |caps: &Captures| {
+                    let found = &caps[0];
+                    if *kind == Kind::Card && !luhn_valid(found) {
+                        found.to_string()
+                    } else {
+                        self.replace(*kind, found)
+                    }
+                })
+                .into_owned();
+        }
+        self.detectors = detectors;
+        text
+    }
+
+    fn replace(&mut self, kind: Kind, original: &str) -> String {
+        // 卡号忽略分隔符，带空格和不带空格的同一卡号得到相同结果
+        let normalized;
+        let original = if kind == Kind::Card {
+            normalized = original.chars().filter(char::is_ascii_digit).collect::<String>();
+            normalized.as_str()
+        } else {
+            original
+        };
+        match self.action {
+            RedactAction::Mask => mask(kind, original),
+            RedactAction::Hash => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(&self.hash_key).expect("HMAC 接受任意长度的密钥");
+                mac.update(original.as_bytes());
+                let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+                format!("hmac-sha256:{}", hex)
+            }
+            RedactAction::Fake => {
+                if let Some(fake) = self.fakes.get(&(kind, original.to_string())) {
+                    return fake.clone();
+                }
+                let counter = self.counters.entry(kind).or_insert(0);
+                *counter += 1;
+                let fake = fake(kind, *counter);
+                self.fakes.insert((kind, original.to_string()), fake.clone());
+                fake
+            }
+        }
+    }
+}
+
+fn mask(kind: Kind, original: &str) -> String {
+    match kind {
+        Kind::Secret | Kind::Ip => "***".to_string(),
+        Kind::Jwt => "eyJ***".to_string(),
+        Kind::Email => {
+            let (user, domain) = original.split_once('@').unwrap_or((original, ""));
+            let first: String = user.chars().take(1).collect();
+            format!("{}***@{}", first, domain)
+        }
+        Kind::Card => {
+            let digits: String = original.chars().filter(char::is_ascii_digit).collect();
+            format!("**** **** **** {}", &digits[digits.len().saturating_sub(4)..])
+        }
+    }
+}
+
+/// 第 n 个假数据；卡号满足 Luhn 校验，JWT 可被解码
+fn fake(kind: Kind, n: usize) -> String {
+    match kind {
+        Kind::Secret => format!("secret_{}", n),
+        Kind::Email => format!("user{}@example.com", n),
+        Kind::Ip => format!("10.0.{}.{}", n / 254 % 256, n % 254 + 1),
+        Kind::Jwt => format!(
+            "eyJhbGciOiJub25lIn0.{}.",
+            URL_SAFE_NO_PAD.encode(format!("{{\"sub\":\"user{}\"}}", n))
+        ),
+        Kind::Card => {
+            let body = format!("4{:014}", n);
+            let check = (0..10).find(|d| luhn_valid(&format!("{}{}", body, d))).unwrap_or(0);
+            format!("{}{}", body, check)
+        }
+    }
+}
+
+/// 卡号的 Luhn 校验
+fn luhn_valid(text: &str) -> bool {
+    let digits: Vec<u32> = text.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2 == 1, d * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => d,
+        })
+        .sum();
+    digits.len() >= 13 && sum.is_multiple_of(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn profile(action: RedactAction) -> RedactionProfile {
+        RedactionProfile {
+            action,
+            ..RedactionProfile::default()
+        }
+    }
+
+    fn redact(action: RedactAction, value: Value) -> Value {
+        Redactor::new(&profile(action)).unwrap().redacted(&value)
+    }
+
+    #[test]
+    fn secret_keys_cover_whole_subtree() {
+        let doc = json!({"Password": "p", "auth": {"api_key": ["k1", 2, null, true]}, "name": "n"});
+        let out = redact(RedactAction::Mask, doc);
+        assert_eq!(out, json!({"Password": "***", "auth": {"api_key": ["***", "***", null, "***"]}, "name": "n"}));
+    }
+
+    #[test]
+    fn mask_detected_values() {
+        let doc = json!({
+            "email": "alice@example.org",
+            "note": "card 4111 1111 1111 1111 from 192.168.1.20",
+            "not_card": "4111 1111 1111 1112",
+            "num": 4111111111111111u64,
+            "jwt": "Bearer eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxIn0.sig"
+        });
+        let out = redact(RedactAction::Mask, doc);
+        assert_eq!(out["email"], json!("a***@example.org"));
+        assert_eq!(out["note"], json!("card **** **** **** 1111 from ***"));
+        assert_eq!(out["not_card"], json!("4111 1111 1111 1112"));
+        assert_eq!(out["num"], json!("**** **** **** 1111"));
+        assert_eq!(out["jwt"], json!("Bearer eyJ***"));
+    }
+
+    #[test]
+    fn fakes_are_consistent() {
+        let doc = json!([
+            "alice@example.org",
+            "bob@example.org",
+            {"cc": "alice@example.org", "card": "4111-1111-1111-1111", "again": "4111111111111111"},
+            "10.1.2.3 and 10.1.2.3 and 10.9.9.9"
+        ]);
+        let out = redact(RedactAction::Fake, doc);
+        assert_eq!(out[0], json!("user1@example.com"));
+        assert_eq!(out[1], json!("user2@example.com"));
+        assert_eq!(out[2]["cc"], out[0]);
+        // 带分隔符和不带分隔符的同一卡号得到相同的假卡号，且仍通过校验
+        assert_eq!(out[2]["card"], out[2]["again"]);
+        assert!(luhn_valid(out[2]["card"].as_str().unwrap()));
+        assert_eq!(out[3], json!("10.0.0.2 and 10.0.0.2 and 10.0.0.3"));
+    }
+
+    #[test]
+    fn fake_jwt_decodes() {
+        let token = fake(Kind::Jwt, 3);
+        let payload = token.split('.').nth(1).unwrap();
+        assert_eq!(URL_SAFE_NO_PAD.decode(payload).unwrap(), br#"{"sub":"user3"}"#);
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        let doc = json!({"token": "abc", "other": {"secret": "abc"}, "x": "abd"});
+        let out = redact(RedactAction::Hash, doc);
+        assert_eq!(out["token"], out["other"]["secret"]);
+        assert_eq!(out["x"], json!("abd"));
+        let hash = out["token"].as_str().unwrap();
+        assert_eq!(hash.len(), "hmac-sha256:".len() + 64);
+        assert!(hash.starts_with("hmac-sha256:"));
+        // 同一次运行中的其他 Redactor（例如复制和导出）得到相同的结果
+        assert_eq!(redact(RedactAction::Hash, json!({"token": "abc"}))["token"], out["token"]);
+    }
+
+    #[test]
+    fn hash_depends_on_key() {
+        let mut redactor = Redactor::new(&profile(RedactAction::Hash)).unwrap();
+        let first = redactor.redacted(&json!({"token": "abc"}));
+        redactor.hash_key = [7; 32];
+        let second = redactor.redacted(&json!({"token": "abc"}));
+        assert_ne!(first, second);
+        // RFC 4231 测试用例 2；短于分组长度的密钥按补零处理，"Jefe" 与补零后的 32 字节等价
+        redactor.hash_key = [0; 32];
+        redactor.hash_key[..4].copy_from_slice(b"Jefe");
+        let out = redactor.redacted(&json!({"token": "what do ya want for nothing?"}));
+        assert_eq!(
+            out["token"],
+            json!("hmac-sha256:5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+        );
+    }
+
+    #[test]
+    fn detectors_can_be_disabled() {
+        let profile = RedactionProfile {
+            key_pattern: String::new(),
+            detect_email: false,
+            ..RedactionProfile::default()
+        };
+        let doc = json!({"password": "p", "email": "a@b.cd", "ip": "1.2.3.4"});
+        let plan = Redactor::new(&profile).unwrap().plan(&doc);
+        let pointers: Vec<&str> = plan.changes.iter().map(|c| c.pointer.as_str()).collect();
+        assert_eq!(pointers, ["/ip"]);
+    }
+
+    #[test]
+    fn invalid_key_pattern() {
+        let profile = RedactionProfile {
+            key_pattern: "(".to_owned(),
+            ..RedactionProfile::default()
+        };
+        assert!(Redactor::new(&profile).is_err());
+    }
+
+    #[test]
+    fn luhn() {
+        assert!(luhn_valid("4111111111111111"));
+        assert!(luhn_valid("4111 1111 1111 1111"));
+        assert!(!luhn_valid("4111111111111112"));
+        assert!(!luhn_valid("0000"));
+    }
+}
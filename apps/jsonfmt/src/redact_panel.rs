@@ -0,0 +1,172 @@
+use appkit::theme::Palette;
+use eframe::egui;
+use serde_json::Value;
+
+use crate::bulk::BulkPlan;
+use crate::bulk_panel::{show_preview, text_hash};
+use crate::document::to_string_with_indent;
+use crate::edit::JsonEdit;
+use crate::i18n::tr;
+use crate::redact::{RedactAction, RedactionProfile, Redactor};
+
+/// 脱敏面板：编辑规则配置，预览后应用到文档或复制脱敏后的副本
+#[derive(Default)]
+pub struct RedactPanel {
+    /// 当前选中的配置下标
+    selected: usize,
+    preview: Option<BulkPlan>,
+    /// 生成预览时原文的哈希，原文变化后预览作废
+    preview_key: u64,
+    error: Option<String>,
+    /// 刚复制到剪贴板时显示提示
+    copied: bool,
+}
+
+impl RedactPanel {
+    fn redactor(&mut self, profile: &RedactionProfile) -> Option<Redactor> {
+        match Redactor::new(profile) {
+            Ok(redactor) => Some(redactor),
+            Err(e) => {
+                self.preview = None;
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    fn make_preview(&mut self, profile: &RedactionProfile, root: &Value, text: &str) {
+        let Some(mut redactor) = self.redactor(profile) else {
+            return;
+        };
+        self.preview = Some(redactor.plan(root));
+        self.preview_key = text_hash(text);
+        self.error = None;
+    }
+
+    /// 把脱敏后的副本复制到剪贴板
+    fn copy_redacted(&mut self, ctx: &egui::Context, profile: &RedactionProfile, root: &Value, indent_spaces: usize) {
+        let Some(mut redactor) = self.redactor(profile) else {
+            return;
+        };
+        ctx.copy_text(to_string_with_indent(&redactor.redacted(root), indent_spaces));
+        self.copied = true;
+    }
+
+    /// 绘制面板内容；点击“应用”时返回生成的编辑操作
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        profiles: &mut Vec<RedactionProfile>,
+        root: Option<&Value>,
+        text: &str,
+        indent_spaces: usize,
+    ) -> Option<Vec<JsonEdit>> {
+        if profiles.is_empty() {
+            profiles.push(RedactionProfile::default());
+        }
+        self.selected = self.selected.min(profiles.len() - 1);
+        let mut changed = self.preview.is_some() && text_hash(text) != self.preview_key;
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("配置："));
+            egui::ComboBox::from_id_salt("redact_profile")
+                .selected_text(profiles[self.selected].name.as_str())
+                .show_ui(ui, |ui| {
+                    for (i, profile) in profiles.iter().enumerate() {
+                        changed |= ui.selectable_value(&mut self.selected, i, profile.name.as_str()).changed();
+                    }
+                });
+            if ui.button(tr!("➕ 新建")).on_hover_text(tr!("以当前配置为模板新建")).clicked() {
+                let mut profile = profiles[self.selected].clone();
+                profile.name = tr!("配置 {}", profiles.len() + 1);
+                profiles.push(profile);
+                self.selected = profiles.len() - 1;
+                changed = true;
+            }
+            if ui.add_enabled(profiles.len() > 1, egui::Button::new(tr!("🗑 删除"))).clicked() {
+                profiles.remove(self.selected);
+                self.selected = self.selected.saturating_sub(1);
+                changed = true;
+            }
+        });
+
+        let profile = &mut profiles[self.selected];
+        egui::Grid::new("redact_profile_fields").num_columns(2).show(ui, |ui| {
+            ui.label(tr!("名称："));
+            ui.text_edit_singleline(&mut profile.name);
+            ui.end_row();
+
+            ui.label(tr!("键名规则："));
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut profile.key_pattern)
+                        .hint_text(tr!("正则，不区分大小写"))
+                        .desired_width(f32::INFINITY),
+                )
+                .on_hover_text(tr!("键名匹配的成员，其值（包括整个子树）都会脱敏"))
+                .changed();
+            ui.end_row();
+
+            ui.label(tr!("识别内容："));
+            ui.horizontal_wrapped(|ui| {
+                changed |= ui.checkbox(&mut profile.detect_email, tr!("邮箱")).changed();
+                changed |= ui.checkbox(&mut profile.detect_card, tr!("信用卡号")).changed();
+                changed |= ui.checkbox(&mut profile.detect_jwt, "JWT").changed();
+                changed |= ui.checkbox(&mut profile.detect_ip, tr!("IP 地址")).changed();
+            });
+            ui.end_row();
+
+            ui.label(tr!("处理方式："));
+            ui.horizontal(|ui| {
+                for action in RedactAction::ALL {
+                    changed |= ui.radio_value(&mut profile.action, action, action.label()).changed();
+                }
+            });
+            ui.end_row();
+        });
+
+        if changed {
+            self.preview = None;
+            self.error = None;
+            self.copied = false;
+        }
+
+        let mut apply = false;
+        ui.horizontal(|ui| {
+            let preview = ui.add_enabled(root.is_some(), egui::Button::new(tr!("🔍 预览"))).clicked();
+            if let (true, Some(root)) = (preview, root) {
+                self.make_preview(profile, root, text);
+            }
+            let can_apply = self.preview.as_ref().is_some_and(|p| !p.changes.is_empty());
+            if ui.add_enabled(can_apply, egui::Button::new(tr!("✅ 应用到文档"))).clicked() {
+                apply = true;
+            }
+            let copy = ui
+                .add_enabled(root.is_some(), egui::Button::new(tr!("📋 复制脱敏结果")))
+                .on_hover_text(tr!("复制脱敏后的 JSON，不修改当前文档"))
+                .clicked();
+            if let (true, Some(root)) = (copy, root) {
+                self.copy_redacted(ui.ctx(), profile, root, indent_spaces);
+            }
+            if self.copied {
+                ui.weak(tr!("已复制"));
+            }
+        });
+
+        if let Some(err) = &self.error {
+            ui.colored_label(Palette::of(ui).error, err);
+        }
+
+        if let Some(plan) = &self.preview {
+            ui.separator();
+            ui.label(tr!("将脱敏 {} 个值", plan.changes.len()));
+            show_preview(ui, plan);
+        }
+
+        if apply {
+            let plan = self.preview.take()?;
+            return Some(plan.changes.into_iter().map(|c| c.edit).collect());
+        }
+        None
+    }
+}
@@ -0,0 +1,365 @@
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+
+use appkit::i18n::Locale;
+use jaq_core::compile::Undefined;
+use jaq_core::load::{self, Arena, File, Loader};
+use jaq_core::{Compiler, Ctx, Native, RcIter};
+use jaq_json::Val;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::i18n::tr;
+
+/// 脚本中引用当前文档的全局变量，与输入 `.` 相同
+const INPUT_VAR: &str = "$last_json";
+/// 运行错误信息的最大长度；jq 的错误信息会带上出错的整个值
+const MAX_MESSAGE_LEN: usize = 300;
+/// 以子进程运行脚本时传给本程序的参数
+pub const RUNNER_ARG: &str = "--run-script";
+/// 子进程中运行脚本的线程栈大小；jq 的递归求值在深层文档上需要较大的栈
+const SCRIPT_STACK_SIZE: usize = 64 * 1024 * 1024;
+
+/// 保存的脚本片段
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptSnippet {
+    pub name: String,
+    pub code: String,
+}
+
+impl Default for ScriptSnippet {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            code: ".".to_string(),
+        }
+    }
+}
+
+/// 首次使用时提供的示例片段
+pub fn example_snippets() -> Vec<ScriptSnippet> {
+    [
+        (tr!("删除所有 null"), "del(.. | nulls)"),
+        (tr!("键名转为小写"), "walk(if type == \"object\" then with_entries(.key |= ascii_downcase) else . end)"),
+        (tr!("数组按 name 排序"), "walk(if type == \"array\" then sort_by(.name?) else . end)"),
+        (tr!("去掉字符串首尾空白"), "walk(if type == \"string\" then trim else . end)"),
+    ]
+    .into_iter()
+    .map(|(name, code)| ScriptSnippet {
+        name: name.to_string(),
+        code: code.to_string(),
+    })
+    .collect()
+}
+
+/// 脚本错误；解析和编译阶段的错误带行号（从 1 开始）
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScriptError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ScriptError {
+    fn new(message: String) -> Self {
+        Self { line: None, message }
+    }
+
+    /// 出错位置为 code 中的切片 at
+    fn at(code: &str, at: &str, message: String) -> Self {
+        let offset = (at.as_ptr() as usize).wrapping_sub(code.as_ptr() as usize);
+        let line = (offset <= code.len()).then(|| code[..offset].matches('\n').count() + 1);
+        Self { line, message }
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}", tr!("第 {} 行：{}", line, self.message)),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// 出错处的内容，为空表示脚本末尾
+fn found(at: &str) -> String {
+    let token: String = at.chars().take_while(|c| !c.is_whitespace()).take(20).collect();
+    match at.chars().next() {
+        None => tr!("脚本末尾").to_owned(),
+        Some(c) if token.is_empty() => format!("{:?}", c),
+        Some(_) => tr!("“{}”", token),
+    }
+}
+
+fn undefined_kind(undefined: &Undefined) -> &'static str {
+    match undefined {
+        Undefined::Var => tr!("变量"),
+        Undefined::Filter(_) => tr!("函数"),
+        Undefined::Label => tr!("标签"),
+        _ => tr!("模块"),
+    }
+}
+
+/// 把加载阶段的第一个错误转换为 ScriptError
+fn load_error(code: &str, errors: load::Errors<&str, ()>) -> ScriptError {
+    let Some((_, error)) = errors.into_iter().next() else {
+        return ScriptError::new(tr!("无法解析脚本").to_owned());
+    };
+    match error {
+        load::Error::Io(_) => ScriptError::new(tr!("不支持导入模块").to_owned()),
+        load::Error::Lex(errors) => match errors.first() {
+            Some((expect, at)) => ScriptError::at(code, at, tr!("应为 {}，实际为 {}", expect.as_str(), found(at))),
+            None => ScriptError::new(tr!("无法解析脚本").to_owned()),
+        },
+        load::Error::Parse(errors) => match errors.first() {
+            Some((expect, at)) => ScriptError::at(code, at, tr!("应为 {}，实际为 {}", expect.as_str(), found(at))),
+            None => ScriptError::new(tr!("无法解析脚本").to_owned()),
+        },
+    }
+}
+
+/// 用 jq 语言的脚本变换 input：输入 `.` 与变量 `$last_json` 都是当前文档，脚本须恰好输出一个值
+pub fn run(code: &str, input: Value) -> Result<Value, ScriptError> {
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+    let modules = loader
+        .load(&arena, File { code, path: () })
+        .map_err(|errors| load_error(code, errors))?;
+    let filter = Compiler::<_, Native<Val>>::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .with_global_vars([INPUT_VAR])
+        .compile(modules)
+        .map_err(|errors| {
+            let undefined = errors.into_iter().flat_map(|(_, e)| e).next();
+            match undefined {
+                Some((name, kind)) => ScriptError::at(code, name, tr!("未定义的{}：{}", undefined_kind(&kind), name)),
+                None => ScriptError::new(tr!("无法编译脚本").to_owned()),
+            }
+        })?;
+
+    let input = Val::from(input);
+    let inputs = RcIter::new(core::iter::empty());
+    let mut outputs = filter.run((Ctx::new([input.clone()], &inputs), input));
+    let first = match outputs.next() {
+        Some(Ok(value)) => value,
+        Some(Err(e)) => {
+            let mut message = e.to_string();
+            if message.chars().count() > MAX_MESSAGE_LEN {
+                message = format!("{}…", message.chars().take(MAX_MESSAGE_LEN).collect::<String>());
+            }
+            return Err(ScriptError::new(tr!("运行出错：{}", message)));
+        }
+        None => return Err(ScriptError::new(tr!("脚本没有输出任何值").to_owned())),
+    };
+    if outputs.next().is_some() {
+        return Err(ScriptError::new(tr!("脚本输出了多个值，可用 [ … ] 把它们收集为数组").to_owned()));
+    }
+    Ok(first.into())
+}
+
+/// 发给脚本子进程的请求，占一行
+#[derive(Serialize, Deserialize)]
+struct Request {
+    code: String,
+    input: Value,
+    /// 错误信息使用的语言，与界面一致
+    locale: Locale,
+}
+
+fn terminated() -> ScriptError {
+    ScriptError::new(tr!("脚本异常终止").to_owned())
+}
+
+/// 子进程入口：从标准输入读一行请求，运行脚本后把结果作为一行 JSON 写到标准输出。
+/// 标准输入关闭（父进程已退出）时立即结束，不让死循环的脚本留在后台
+pub fn serve() -> io::Result<()> {
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    let request: Request = serde_json::from_str(&line)?;
+    request.locale.set_current();
+    std::thread::spawn(|| {
+        let _ = io::copy(&mut io::stdin(), &mut io::sink());
+        std::process::exit(1);
+    });
+    let outcome = std::thread::Builder::new()
+        .stack_size(SCRIPT_STACK_SIZE)
+        .spawn(move || run(&request.code, request.input))?
+        .join()
+        .unwrap_or_else(|_| Err(terminated()));
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer(&mut stdout, &outcome)?;
+    writeln!(stdout)?;
+    stdout.flush()
+}
+
+/// 以子进程运行脚本的命令：带上 RUNNER_ARG 重新启动本程序
+pub fn runner_command() -> io::Result<Command> {
+    let mut command = Command::new(std::env::current_exe()?);
+    command.arg(RUNNER_ARG);
+    Ok(command)
+}
+
+/// 在子进程中运行的脚本。jq 无法在求值中途打断，放在子进程中才能随时结束；丢弃时结束子进程
+pub struct ScriptProcess {
+    child: Child,
+    receiver: Receiver<Result<Value, ScriptError>>,
+}
+
+impl ScriptProcess {
+    /// 用 command 启动子进程并发送请求，子进程须调用 serve
+    pub fn spawn(mut command: Command, code: &str, input: Value) -> io::Result<Self> {
+        let request = Request {
+            code: code.to_owned(),
+            input,
+            locale: Locale::current(),
+        };
+        let mut line = serde_json::to_string(&request)?;
+        line.push('\n');
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (sender, receiver) = mpsc::channel();
+        let process = Self { child, receiver };
+        std::thread::Builder::new().name("jq-script".to_owned()).spawn(move || {
+            // 结果是最后一行能解析的输出；子进程退出前一直持有它的标准输入
+            let outcome = stdin.write_all(line.as_bytes()).ok().and_then(|_| {
+                BufReader::new(stdout)
+                    .lines()
+                    .map_while(Result::ok)
+                    .filter_map(|line| serde_json::from_str(&line).ok())
+                    .last()
+            });
+            let _ = sender.send(outcome.unwrap_or_else(|| Err(terminated())));
+            drop(stdin);
+        })?;
+        Ok(process)
+    }
+
+    /// 取回运行结果，仍在运行时返回 None
+    pub fn try_result(&self) -> Option<Result<Value, ScriptError>> {
+        match self.receiver.try_recv() {
+            Ok(outcome) => Some(outcome),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(terminated())),
+        }
+    }
+
+    fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for ScriptProcess {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use serde_json::json;
+
+    use super::*;
+
+
+    #[test]
+    fn input_and_variable_are_the_document() {
+        let doc = json!({"b": 1, "a": 2});
+        assert_eq!(run(".", doc.clone()).unwrap(), doc);
+        assert_eq!(run("$last_json.a", doc).unwrap(), json!(2));
+    }
+
+    #[test]
+    fn keeps_key_order() {
+        let doc = json!({"z": null, "b": 1, "a": 2});
+        let out = run("del(.. | nulls)", doc).unwrap();
+        let keys: Vec<&String> = out.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["b", "a"]);
+    }
+
+    #[test]
+    fn errors_carry_line_numbers() {
+        let err = run(".\n| .a\n| foo", json!({})).unwrap_err();
+        assert_eq!(err.line, Some(3));
+        let err = run(".a |\n(", json!({})).unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn requires_exactly_one_output() {
+        assert!(run("empty", json!(1)).is_err());
+        assert!(run("1, 2", json!(1)).is_err());
+        assert!(run("repeat(1)", json!(1)).is_err());
+        assert_eq!(run("[1, 2]", json!(1)).unwrap(), json!([1, 2]));
+    }
+
+    /// 测试中子进程是测试程序自身，只运行 script_runner 这一个测试
+    fn test_runner() -> Command {
+        let mut command = Command::new(std::env::current_exe().unwrap());
+        command
+            .args(["script::tests::script_runner", "--exact", "--ignored", "--nocapture"])
+            .env("JSONFMT_SCRIPT_RUNNER", "1");
+        command
+    }
+
+    /// 等待结果，超时返回 None
+    fn wait(process: &ScriptProcess, timeout: Duration) -> Option<Result<Value, ScriptError>> {
+        let started = Instant::now();
+        while started.elapsed() < timeout {
+            if let Some(outcome) = process.try_result() {
+                return Some(outcome);
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        None
+    }
+
+    /// 子进程入口，由 test_runner 启动
+    #[test]
+    #[ignore]
+    fn script_runner() {
+        if std::env::var_os("JSONFMT_SCRIPT_RUNNER").is_some() {
+            // 测试框架已在当前行输出了测试名，结果须另起一行
+            println!();
+            serve().unwrap();
+        }
+    }
+
+    #[test]
+    fn process_returns_result() {
+        let process = ScriptProcess::spawn(test_runner(), ".a", json!({"a": [1, "x"]})).unwrap();
+        assert_eq!(wait(&process, Duration::from_secs(30)).unwrap().unwrap(), json!([1, "x"]));
+        let process = ScriptProcess::spawn(test_runner(), ".\n| foo", json!({})).unwrap();
+        let err = wait(&process, Duration::from_secs(30)).unwrap().unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn never_finishing_script_is_killed() {
+        for code in ["def f: f; f", "last(repeat(1))"] {
+            let mut process = ScriptProcess::spawn(test_runner(), code, json!(1)).unwrap();
+            assert!(wait(&process, Duration::from_millis(500)).is_none(), "{code}");
+            process.kill();
+            assert!(process.child.try_wait().unwrap().is_some(), "{code}");
+            assert!(wait(&process, Duration::from_secs(5)).unwrap().is_err(), "{code}");
+        }
+    }
+
+    #[test]
+    fn example_snippets_run() {
+        let doc = json!({"B": [{"name": "z", "x": null}, {"name": "a"}], "s": " t "});
+        for snippet in example_snippets() {
+            assert!(run(&snippet.code, doc.clone()).is_ok(), "{}", snippet.name);
+        }
+    }
+}
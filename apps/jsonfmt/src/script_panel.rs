@@ -0,0 +1,249 @@
+use std::time::{Duration, Instant};
+
+use appkit::theme::Palette;
+use eframe::egui::{self, text::LayoutJob, Key, Modifiers, TextFormat, TextStyle};
+use serde_json::Value;
+
+use crate::bulk_panel::text_hash;
+use crate::document::to_string_with_indent;
+use crate::i18n::tr;
+use crate::script::{self, ScriptError, ScriptProcess, ScriptSnippet};
+
+/// 运行中刷新界面的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// 结果预览最多显示的行数
+const PREVIEW_LINES: usize = 500;
+/// 结果预览区域的最大高度
+const PREVIEW_HEIGHT: f32 = 260.0;
+
+/// 运行结果及运行时文档的 id 和原文哈希
+struct ScriptResult {
+    input: (u64, u64),
+    outcome: Result<(Value, String), ScriptError>,
+}
+
+/// 正在子进程中运行的脚本；丢弃时（停止或关闭面板）结束子进程
+struct Running {
+    input: (u64, u64),
+    process: ScriptProcess,
+    started: Instant,
+}
+
+/// 脚本面板：用 jq 脚本变换当前文档，预览结果后作为可撤销的编辑应用
+#[derive(Default)]
+pub struct ScriptPanel {
+    /// 当前选中的片段下标
+    selected: usize,
+    running: Option<Running>,
+    result: Option<ScriptResult>,
+}
+
+/// 截取前 PREVIEW_LINES 行
+fn preview_text(value: &Value, indent_spaces: usize) -> String {
+    let text = to_string_with_indent(value, indent_spaces);
+    let total = text.lines().count();
+    if total <= PREVIEW_LINES {
+        return text;
+    }
+    let mut preview: String = text.lines().take(PREVIEW_LINES).collect::<Vec<_>>().join("\n");
+    preview.push_str(&tr!("\n… 还有 {} 行", total - PREVIEW_LINES));
+    preview
+}
+
+/// 行号栏，出错的行用错误色标出
+fn line_numbers(ui: &egui::Ui, code: &str, error_line: Option<usize>) -> LayoutJob {
+    let font_id = TextStyle::Monospace.resolve(ui.style());
+    let weak = ui.visuals().weak_text_color();
+    let error = Palette::of(ui).error;
+    let count = code.split('\n').count();
+    let width = count.to_string().len();
+    let mut job = LayoutJob::default();
+    for line in 1..=count {
+        let color = if error_line == Some(line) { error } else { weak };
+        let text = if line < count {
+            format!("{:>width$}\n", line)
+        } else {
+            format!("{:>width$}", line)
+        };
+        job.append(&text, 0.0, TextFormat::simple(font_id.clone(), color));
+    }
+    job
+}
+
+impl ScriptPanel {
+    /// 在子进程中运行脚本，避免耗时的脚本卡住界面，停止时也能立即结束
+    fn start(&mut self, code: &str, root: &Value, input: (u64, u64)) {
+        let spawned = script::runner_command().and_then(|command| ScriptProcess::spawn(command, code, root.clone()));
+        match spawned {
+            Ok(process) => {
+                self.running = Some(Running {
+                    input,
+                    process,
+                    started: Instant::now(),
+                });
+            }
+            Err(e) => {
+                self.result = Some(ScriptResult {
+                    input,
+                    outcome: Err(ScriptError {
+                        line: None,
+                        message: tr!("无法启动脚本：{}", e),
+                    }),
+                });
+            }
+        }
+    }
+
+    /// 取回子进程的运行结果
+    fn poll(&mut self, ctx: &egui::Context, indent_spaces: usize) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        let Some(outcome) = running.process.try_result() else {
+            ctx.request_repaint_after(POLL_INTERVAL);
+            return;
+        };
+        self.result = Some(ScriptResult {
+            input: running.input,
+            outcome: outcome.map(|value| {
+                let preview = preview_text(&value, indent_spaces);
+                (value, preview)
+            }),
+        });
+        self.running = None;
+    }
+
+    /// 绘制面板内容；点击“应用到文档”时返回新的文档值
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        snippets: &mut Vec<ScriptSnippet>,
+        doc_id: u64,
+        root: Option<&Value>,
+        text: &str,
+        indent_spaces: usize,
+    ) -> Option<Value> {
+        self.poll(ui.ctx(), indent_spaces);
+        if snippets.is_empty() {
+            snippets.push(ScriptSnippet::default());
+        }
+        self.selected = self.selected.min(snippets.len() - 1);
+        let palette = Palette::of(ui);
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("片段："));
+            egui::ComboBox::from_id_salt("script_snippet")
+                .selected_text(snippets[self.selected].name.as_str())
+                .show_ui(ui, |ui| {
+                    for (i, snippet) in snippets.iter().enumerate() {
+                        ui.selectable_value(&mut self.selected, i, snippet.name.as_str());
+                    }
+                });
+            if ui.button(tr!("➕ 新建")).on_hover_text(tr!("以当前脚本为模板新建片段")).clicked() {
+                let mut snippet = snippets[self.selected].clone();
+                snippet.name = tr!("片段 {}", snippets.len() + 1);
+                snippets.push(snippet);
+                self.selected = snippets.len() - 1;
+            }
+            if ui.add_enabled(snippets.len() > 1, egui::Button::new(tr!("🗑 删除"))).clicked() {
+                snippets.remove(self.selected);
+                self.selected = self.selected.saturating_sub(1);
+            }
+        });
+
+        let input = (doc_id, text_hash(text));
+        let error_line = self
+            .result
+            .as_ref()
+            .and_then(|r| r.outcome.as_ref().err())
+            .and_then(|e| e.line);
+        let snippet = &mut snippets[self.selected];
+        ui.horizontal(|ui| {
+            ui.label(tr!("名称："));
+            ui.text_edit_singleline(&mut snippet.name);
+        });
+        let mut run = false;
+        egui::ScrollArea::vertical()
+            .id_salt("script_code")
+            .max_height(240.0)
+            .show(ui, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.label(line_numbers(ui, &snippet.code, error_line));
+                    let response = ui.add(
+                        egui::TextEdit::multiline(&mut snippet.code)
+                            .code_editor()
+                            .desired_rows(8)
+                            .desired_width(f32::INFINITY),
+                    );
+                    if response.has_focus() && ui.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::Enter)) {
+                        run = true;
+                    }
+                });
+            });
+        ui.weak(tr!("jq 语法：输入 . 与变量 $last_json 都是当前文档，脚本须恰好输出一个值"));
+
+        ui.horizontal(|ui| {
+            match &self.running {
+                Some(running) => {
+                    ui.spinner();
+                    ui.label(tr!("运行中… {} 秒", format!("{:.1}", running.started.elapsed().as_secs_f32())));
+                    if ui.button(tr!("⏹ 停止")).on_hover_text(tr!("结束脚本并放弃本次运行的结果")).clicked() {
+                        self.running = None;
+                    }
+                }
+                None => {
+                    run |= ui
+                        .add_enabled(root.is_some(), egui::Button::new(tr!("▶ 运行")))
+                        .on_hover_text("Ctrl+Enter")
+                        .clicked();
+                }
+            }
+        });
+        if let (true, None, Some(root)) = (run, &self.running, root) {
+            self.start(&snippet.code, root, input);
+        }
+
+        let result = self.result.as_ref()?;
+        ui.separator();
+        let (value, preview) = match &result.outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                ui.colored_label(palette.error, e.to_string());
+                return None;
+            }
+        };
+        let stale = result.input != input;
+        if stale {
+            ui.colored_label(palette.error, tr!("文档已改变，请重新运行"));
+        }
+        egui::ScrollArea::both()
+            .id_salt("script_result")
+            .max_height(PREVIEW_HEIGHT)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                ui.monospace(preview);
+            });
+
+        let unchanged = root == Some(value);
+        let mut apply = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!stale && !unchanged, egui::Button::new(tr!("✅ 应用到文档")))
+                .on_hover_text(tr!("替换整个文档，可撤销"))
+                .clicked()
+            {
+                apply = Some(value.clone());
+            }
+            if ui.button(tr!("📋 复制结果")).clicked() {
+                ui.ctx().copy_text(to_string_with_indent(value, indent_spaces));
+            }
+            if unchanged {
+                ui.weak(tr!("结果与当前文档相同"));
+            }
+        });
+        if apply.is_some() {
+            self.result = None;
+        }
+        apply
+    }
+}
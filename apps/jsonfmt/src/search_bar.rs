@@ -0,0 +1,182 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use appkit::theme::Palette;
+use eframe::egui;
+use serde_json::Value;
+
+use crate::i18n::tr;
+use crate::tree_search::{self, Pruned, SearchTarget, TreeQuery};
+use crate::value_editor::ValueKind;
+
+/// 搜索结果变化后自动展开匹配节点祖先的帧数（每帧展开一层）
+const EXPAND_FRAMES: u8 = 32;
+
+/// 解析树上方的搜索栏：搜索条件、结果计数与上一个/下一个导航
+#[derive(Default)]
+pub struct SearchBar {
+    pub query: TreeQuery,
+    /// 只显示匹配节点所在的分支
+    pub only_matching: bool,
+    hits: Vec<String>,
+    hit_set: HashSet<String>,
+    ancestors: HashSet<String>,
+    pruned: Option<Pruned>,
+    /// 当前导航到的结果下标
+    current: Option<usize>,
+    error: Option<String>,
+    /// 结果缓存的键：原文、搜索条件和筛选模式的哈希
+    cache_key: u64,
+    expand_frames: u8,
+    focus_pending: bool,
+}
+
+impl SearchBar {
+    /// 原文或搜索条件变化后重新搜索
+    pub fn refresh(&mut self, text: &str, root: &Value) {
+        let mut hasher = DefaultHasher::new();
+        (text, &self.query, self.only_matching).hash(&mut hasher);
+        let key = hasher.finish();
+        if key == self.cache_key {
+            return;
+        }
+        self.cache_key = key;
+        self.error = None;
+        self.hits = if self.query.is_active() {
+            tree_search::search(root, &self.query).unwrap_or_else(|e| {
+                self.error = Some(e);
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        self.hit_set = self.hits.iter().cloned().collect();
+        self.ancestors = tree_search::ancestors(&self.hits);
+        self.pruned = if self.only_matching {
+            tree_search::prune(root, &self.hits)
+        } else {
+            None
+        };
+        self.current = self.current.filter(|&i| i < self.hits.len());
+        self.expand_frames = EXPAND_FRAMES;
+    }
+
+    /// 是否以筛选后的树显示
+    pub fn filtering(&self) -> bool {
+        self.only_matching && self.query.is_active()
+    }
+
+    /// 筛选模式下只包含匹配分支的树；没有匹配时为 None
+    pub fn pruned(&self) -> Option<&Pruned> {
+        self.pruned.as_ref()
+    }
+
+    /// 按文档顺序排列的全部匹配路径
+    pub fn hits(&self) -> &[String] {
+        &self.hits
+    }
+
+    pub fn is_hit(&self, pointer: &str) -> bool {
+        self.hit_set.contains(pointer)
+    }
+
+    /// 节点是否需要展开以显示其下的匹配（结果变化后的若干帧内）
+    pub fn should_expand(&self, pointer: &str) -> bool {
+        self.expand_frames > 0 && self.ancestors.contains(pointer)
+    }
+
+    /// 每帧结束时调用，自动展开只持续有限帧，之后用户可自由折叠
+    pub fn end_frame(&mut self, ctx: &egui::Context) {
+        if self.expand_frames > 0 && !self.ancestors.is_empty() {
+            self.expand_frames -= 1;
+            ctx.request_repaint();
+        }
+    }
+
+    /// 下一帧把焦点移到搜索框
+    pub fn focus(&mut self) {
+        self.focus_pending = true;
+    }
+
+    fn step(&mut self, forward: bool) -> Option<String> {
+        let len = self.hits.len();
+        if len == 0 {
+            return None;
+        }
+        let next = match self.current {
+            None if forward => 0,
+            None => len - 1,
+            Some(i) if forward => (i + 1) % len,
+            Some(i) => (i + len - 1) % len,
+        };
+        self.current = Some(next);
+        Some(self.hits[next].clone())
+    }
+
+    /// 绘制搜索栏，返回需要导航到的结果路径
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<String> {
+        let mut target = None;
+        let (enter, shift) = ui.input(|i| (i.key_pressed(egui::Key::Enter), i.modifiers.shift));
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("解析树："));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.query.text)
+                    .hint_text(tr!("🔍 搜索..."))
+                    .desired_width(f32::INFINITY),
+            );
+            if std::mem::take(&mut self.focus_pending) {
+                response.request_focus();
+            }
+            if response.changed() {
+                self.current = None;
+            }
+            // 回车跳到下一个结果，Shift+回车跳到上一个
+            if response.lost_focus() && enter {
+                target = self.step(!shift);
+                response.request_focus();
+            }
+        });
+
+        ui.horizontal_wrapped(|ui| {
+            egui::ComboBox::from_id_salt("tree_search_target")
+                .selected_text(self.query.target.label())
+                .show_ui(ui, |ui| {
+                    for t in SearchTarget::ALL {
+                        ui.selectable_value(&mut self.query.target, t, t.label());
+                    }
+                });
+            egui::ComboBox::from_id_salt("tree_search_kind")
+                .selected_text(self.query.kind.map_or(tr!("任意类型"), ValueKind::label))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.query.kind, None, tr!("任意类型"));
+                    for kind in ValueKind::ALL {
+                        ui.selectable_value(&mut self.query.kind, Some(kind), kind.label());
+                    }
+                });
+            ui.toggle_value(&mut self.query.case_sensitive, "Aa").on_hover_text(tr!("区分大小写"));
+            ui.toggle_value(&mut self.query.regex, ".*").on_hover_text(tr!("正则表达式"));
+            ui.checkbox(&mut self.only_matching, tr!("仅显示匹配分支"));
+
+            if self.query.is_active() {
+                match self.current {
+                    _ if self.hits.is_empty() => ui.label(tr!("无结果")),
+                    Some(i) => ui.label(format!("{}/{}", i + 1, self.hits.len())),
+                    None => ui.label(tr!("{} 个结果", self.hits.len())),
+                };
+                if ui.button("⬆").on_hover_text(tr!("上一个 (Shift+Enter)")).clicked() {
+                    target = self.step(false);
+                }
+                if ui.button("⬇").on_hover_text(tr!("下一个 (Enter)")).clicked() {
+                    target = self.step(true);
+                }
+            }
+        });
+
+        if let Some(err) = &self.error {
+            ui.colored_label(Palette::of(ui).error, err);
+        }
+        target
+    }
+}
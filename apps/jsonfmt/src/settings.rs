@@ -0,0 +1,53 @@
+use appkit::theme::ThemeSettings;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::Keymap;
+use crate::redact::RedactionProfile;
+use crate::script::{example_snippets, ScriptSnippet};
+
+/// eframe 持久化存储中设置项的键
+const STORAGE_KEY: &str = "jsonfmt_settings";
+
+/// 跨会话保存的用户设置
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub redaction_profiles: Vec<RedactionProfile>,
+    pub theme: ThemeSettings,
+    pub keymap: Keymap,
+    /// 解析树默认展开的层数
+    pub tree_depth: u8,
+    /// 编辑和载入时是否自动按当前缩进重新排版整个文档
+    pub auto_format: bool,
+    pub script_snippets: Vec<ScriptSnippet>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            redaction_profiles: vec![RedactionProfile::default()],
+            theme: ThemeSettings::default(),
+            keymap: Keymap::default(),
+            tree_depth: 3,
+            auto_format: false,
+            script_snippets: example_snippets(),
+        }
+    }
+}
+
+impl Settings {
+    /// 从存储中读取设置，读取失败时使用默认值
+    pub fn load(storage: Option<&dyn eframe::Storage>) -> Self {
+        let mut settings: Self = storage
+            .and_then(|s| eframe::get_value(s, STORAGE_KEY))
+            .unwrap_or_default();
+        if settings.redaction_profiles.is_empty() {
+            settings.redaction_profiles.push(RedactionProfile::default());
+        }
+        settings
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, STORAGE_KEY, self);
+    }
+}
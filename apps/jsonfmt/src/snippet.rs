@@ -0,0 +1,355 @@
+use serde_json::Value;
+
+use crate::i18n::tr;
+use crate::json_path::is_identifier;
+
+/// 悬停预览最多显示的行数
+const PREVIEW_LINES: usize = 12;
+
+/// “复制为”支持的代码片段格式
+#[derive(Clone, Copy, PartialEq)]
+pub enum SnippetFormat {
+    /// 单行 JSON
+    Minified,
+    /// Rust：`serde_json::json!({ ... })`
+    RustJson,
+    /// Python 字面量：`True`/`False`/`None`
+    Python,
+    /// JavaScript 字面量，合法标识符的键名不加引号
+    JavaScript,
+    /// 单行 JSON 再作为字符串转义一次：`"{\"a\":1}"`
+    EscapedString,
+    /// `curl -d` 命令，请求体为单行 JSON
+    Curl,
+    /// YAML 块格式
+    Yaml,
+}
+
+impl SnippetFormat {
+    pub const ALL: [SnippetFormat; 7] = [
+        SnippetFormat::Minified,
+        SnippetFormat::RustJson,
+        SnippetFormat::Python,
+        SnippetFormat::JavaScript,
+        SnippetFormat::EscapedString,
+        SnippetFormat::Curl,
+        SnippetFormat::Yaml,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SnippetFormat::Minified => tr!("压缩的 JSON"),
+            SnippetFormat::RustJson => "Rust json!",
+            SnippetFormat::Python => tr!("Python 字面量"),
+            SnippetFormat::JavaScript => tr!("JavaScript 字面量"),
+            SnippetFormat::EscapedString => tr!("转义的 JSON 字符串"),
+            SnippetFormat::Curl => tr!("curl 请求体"),
+            SnippetFormat::Yaml => "YAML",
+        }
+    }
+}
+
+/// 把节点转换为指定格式的代码片段
+pub fn render(value: &Value, format: SnippetFormat) -> String {
+    match format {
+        SnippetFormat::Minified => value.to_string(),
+        SnippetFormat::RustJson => {
+            let mut out = String::from("serde_json::json!(");
+            write_code(value, &RUST, 0, &mut out);
+            out.push(')');
+            out
+        }
+        SnippetFormat::Python => {
+            let mut out = String::new();
+            write_code(value, &PYTHON, 0, &mut out);
+            out
+        }
+        SnippetFormat::JavaScript => {
+            let mut out = String::new();
+            write_code(value, &JAVASCRIPT, 0, &mut out);
+            out
+        }
+        SnippetFormat::EscapedString => json_string(&value.to_string()),
+        SnippetFormat::Curl => format!(
+            "curl -H 'Content-Type: application/json' -d '{}' https://example.com/api",
+            value.to_string().replace('\'', r"'\''")
+        ),
+        SnippetFormat::Yaml => {
+            let mut out = String::new();
+            if is_nested(value) {
+                write_yaml(value, 0, &mut out);
+            } else {
+                out.push_str(&yaml_scalar(value));
+            }
+            out.trim_end_matches('\n').to_owned()
+        }
+    }
+}
+
+/// 悬停预览：只保留前几行
+pub fn preview(text: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().take(PREVIEW_LINES + 1).collect();
+    if lines.len() > PREVIEW_LINES {
+        lines.truncate(PREVIEW_LINES);
+        lines.push("…");
+    }
+    lines.join("\n")
+}
+
+/// JSON 字符串字面量
+fn json_string(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+/// 各语言字面量的写法
+struct CodeStyle {
+    null: &'static str,
+    true_: &'static str,
+    false_: &'static str,
+    indent: usize,
+    string: fn(&str) -> String,
+    key: fn(&str) -> String,
+}
+
+/// Rust 不认识 JSON 的 `\u0001` 转义，用 Debug 输出得到合法的 Rust 字符串
+fn rust_string(s: &str) -> String {
+    format!("{:?}", s)
+}
+
+/// 合法标识符的键名不加引号
+fn js_key(key: &str) -> String {
+    if is_identifier(key) {
+        key.to_owned()
+    } else {
+        json_string(key)
+    }
+}
+
+const RUST: CodeStyle = CodeStyle {
+    null: "null",
+    true_: "true",
+    false_: "false",
+    indent: 4,
+    string: rust_string,
+    key: rust_string,
+};
+
+const PYTHON: CodeStyle = CodeStyle {
+    null: "None",
+    true_: "True",
+    false_: "False",
+    indent: 4,
+    string: json_string,
+    key: json_string,
+};
+
+const JAVASCRIPT: CodeStyle = CodeStyle {
+    null: "null",
+    true_: "true",
+    false_: "false",
+    indent: 2,
+    string: json_string,
+    key: js_key,
+};
+
+/// 按 style 缩进输出字面量，depth 为当前嵌套层数
+fn write_code(value: &Value, style: &CodeStyle, depth: usize, out: &mut String) {
+    let pad = " ".repeat(style.indent * (depth + 1));
+    let close_pad = " ".repeat(style.indent * depth);
+    match value {
+        Value::Null => out.push_str(style.null),
+        Value::Bool(true) => out.push_str(style.true_),
+        Value::Bool(false) => out.push_str(style.false_),
+        Value::Number(n) => out.push_str(&n.to_string()),
+        Value::String(s) => out.push_str(&(style.string)(s)),
+        Value::Array(arr) if arr.is_empty() => out.push_str("[]"),
+        Value::Object(map) if map.is_empty() => out.push_str("{}"),
+        Value::Array(arr) => {
+            out.push_str("[\n");
+            for (idx, item) in arr.iter().enumerate() {
+                out.push_str(&pad);
+                write_code(item, style, depth + 1, out);
+                out.push_str(if idx + 1 < arr.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&close_pad);
+            out.push(']');
+        }
+        Value::Object(map) => {
+            out.push_str("{\n");
+            for (idx, (key, item)) in map.iter().enumerate() {
+                out.push_str(&pad);
+                out.push_str(&(style.key)(key));
+                out.push_str(": ");
+                write_code(item, style, depth + 1, out);
+                out.push_str(if idx + 1 < map.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&close_pad);
+            out.push('}');
+        }
+    }
+}
+
+/// 非空的对象或数组，YAML 中需要写成块
+fn is_nested(value: &Value) -> bool {
+    match value {
+        Value::Array(arr) => !arr.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => false,
+    }
+}
+
+/// 单行的 YAML 值
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => yaml_string(s),
+        Value::Array(_) => "[]".to_owned(),
+        Value::Object(_) => "{}".to_owned(),
+        other => other.to_string(),
+    }
+}
+
+/// 可能被 YAML 解析成其他类型或语法的字符串加双引号（JSON 转义在 YAML 双引号中同样有效）。
+/// 判断偏保守，宁可多加引号
+fn yaml_string(s: &str) -> String {
+    const KEYWORDS: [&str; 11] = ["true", "false", "null", "yes", "no", "on", "off", "y", "n", "~", ""];
+    let needs_quote = KEYWORDS.contains(&s.to_lowercase().as_str())
+        || s.trim() != s
+        || s.starts_with(|c: char| c.is_ascii_digit() || "-+.?:,[]{}#&*!|>'\"%@`".contains(c))
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.ends_with(':')
+        || s.chars().any(char::is_control);
+    if needs_quote {
+        json_string(s)
+    } else {
+        s.to_owned()
+    }
+}
+
+/// 输出非空容器的块格式，每行以 indent 个空格开头
+fn write_yaml(value: &Value, indent: usize, out: &mut String) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (key, item) in map {
+                out.push_str(&pad);
+                out.push_str(&yaml_string(key));
+                out.push(':');
+                if is_nested(item) {
+                    out.push('\n');
+                    write_yaml(item, indent + 2, out);
+                } else {
+                    out.push(' ');
+                    out.push_str(&yaml_scalar(item));
+                    out.push('\n');
+                }
+            }
+        }
+        Value::Array(arr) => {
+            for item in arr {
+                out.push_str(&pad);
+                out.push('-');
+                if is_nested(item) {
+                    // 嵌套块的第一行接在 “- ” 后面
+                    let mut nested = String::new();
+                    write_yaml(item, indent + 2, &mut nested);
+                    out.push(' ');
+                    out.push_str(&nested[indent + 2..]);
+                } else {
+                    out.push(' ');
+                    out.push_str(&yaml_scalar(item));
+                    out.push('\n');
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn yaml_quotes_ambiguous_strings() {
+        for s in ["true", "No", "off", "~", "", "null", "123", "1.5", "-x", " pad", "a: b", "x #c", "key:", "line\nbreak", "@at"] {
+            assert_eq!(yaml_string(s), json_string(s), "{s:?}");
+        }
+        for s in ["hello", "truely", "a:b", "a#b", "中文", "v1.2"] {
+            assert_eq!(yaml_string(s), s, "{s:?}");
+        }
+    }
+
+    #[test]
+    fn yaml_blocks() {
+        let value = json!({
+            "name": "x",
+            "on": true,
+            "list": [1, {"a": null, "b": []}, [2, 3]],
+            "empty": {},
+            "404": "404"
+        });
+        let expected = "\
+name: x
+\"on\": true
+list:
+  - 1
+  - a: null
+    b: []
+  - - 2
+    - 3
+empty: {}
+\"404\": \"404\"";
+        assert_eq!(render(&value, SnippetFormat::Yaml), expected);
+        assert_eq!(render(&json!("yes"), SnippetFormat::Yaml), "\"yes\"");
+        assert_eq!(render(&json!([]), SnippetFormat::Yaml), "[]");
+    }
+
+    #[test]
+    fn rust_strings_use_rust_escapes() {
+        let value = json!({"k\"ey": "tab\there \u{1} \\ é"});
+        assert_eq!(
+            render(&value, SnippetFormat::RustJson),
+            "serde_json::json!({\n    \"k\\\"ey\": \"tab\\there \\u{1} \\\\ é\"\n})"
+        );
+    }
+
+    #[test]
+    fn python_literals() {
+        let value = json!({"a": [true, false, null], "s": "it's \"q\"\n", "e": {}});
+        assert_eq!(
+            render(&value, SnippetFormat::Python),
+            "{\n    \"a\": [\n        True,\n        False,\n        None\n    ],\n    \"s\": \"it's \\\"q\\\"\\n\",\n    \"e\": {}\n}"
+        );
+    }
+
+    #[test]
+    fn javascript_quotes_only_non_identifiers() {
+        let value = json!({"plain": 1, "$ok_1": 2, "with space": 3, "1st": 4});
+        assert_eq!(
+            render(&value, SnippetFormat::JavaScript),
+            "{\n  plain: 1,\n  $ok_1: 2,\n  \"with space\": 3,\n  \"1st\": 4\n}"
+        );
+    }
+
+    #[test]
+    fn escaped_string_and_curl() {
+        let value = json!({"a": "it's"});
+        assert_eq!(render(&value, SnippetFormat::EscapedString), r#""{\"a\":\"it's\"}""#);
+        assert_eq!(
+            render(&value, SnippetFormat::Curl),
+            r#"curl -H 'Content-Type: application/json' -d '{"a":"it'\''s"}' https://example.com/api"#
+        );
+    }
+
+    #[test]
+    fn preview_truncates() {
+        let text = (0..20).map(|i| i.to_string()).collect::<Vec<_>>().join("\n");
+        let short = preview(&text);
+        assert_eq!(short.lines().count(), PREVIEW_LINES + 1);
+        assert!(short.ends_with("11\n…"));
+        assert_eq!(preview("a\nb"), "a\nb");
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::tree_ops::join_pointer;
+
+/// 最大嵌套层数，与 serde_json 的递归上限一致；原文可能是任意输入，避免递归过深导致栈溢出
+const MAX_DEPTH: usize = 128;
+
+/// 单个节点在原文中的位置（字节偏移）
+pub struct NodeSpan {
+    pub pointer: String,
+    /// 对象成员的键（含引号）所在范围
+    pub key: Option<Range<usize>>,
+    /// 值所在范围
+    pub value: Range<usize>,
+}
+
+impl NodeSpan {
+    /// 节点整体范围：有键时从键开始
+    pub fn full(&self) -> Range<usize> {
+        let start = self.key.as_ref().map_or(self.value.start, |k| k.start);
+        start..self.value.end
+    }
+}
+
+/// 解析时记录的所有节点位置，按文档顺序（先序）排列
+pub struct SpanMap {
+    nodes: Vec<NodeSpan>,
+    index: HashMap<String, usize>,
+}
+
+impl SpanMap {
+    /// 解析文本并记录每个值的位置；文本不是合法 JSON 或嵌套过深时返回 None
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parser = Parser {
+            text,
+            bytes: text.as_bytes(),
+            pos: 0,
+            depth: 0,
+            nodes: Vec::new(),
+        };
+        parser.skip_ws();
+        parser.value(String::new(), None)?;
+        parser.skip_ws();
+        if parser.pos != parser.bytes.len() {
+            return None;
+        }
+        let index = parser
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.pointer.clone(), i))
+            .collect();
+        Some(Self {
+            nodes: parser.nodes,
+            index,
+        })
+    }
+
+    pub fn get(&self, pointer: &str) -> Option<&NodeSpan> {
+        self.index.get(pointer).map(|&i| &self.nodes[i])
+    }
+
+    /// 查找包含指定偏移的最深节点
+    pub fn node_at(&self, offset: usize) -> Option<&NodeSpan> {
+        // 先序排列中子节点位于父节点之后，最后一个命中的即为最深
+        self.nodes.iter().rev().find(|n| {
+            let full = n.full();
+            full.start <= offset && offset <= full.end
+        })
+    }
+}
+
+/// 字符下标与字节偏移互相转换（egui 的光标使用字符下标）
+pub fn char_to_byte(text: &str, char_idx: usize) -> usize {
+    text.char_indices().nth(char_idx).map_or(text.len(), |(b, _)| b)
+}
+
+pub fn byte_to_char(text: &str, byte_idx: usize) -> usize {
+    text[..byte_idx.min(text.len())].chars().count()
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    /// 当前所在容器的嵌套层数
+    depth: usize,
+    nodes: Vec<NodeSpan>,
+}
+
+impl Parser<'_> {
+    fn skip_ws(&mut self) {
+        while matches!(self.bytes.get(self.pos), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> Option<()> {
+        self.skip_ws();
+        (self.bytes.get(self.pos) == Some(&byte)).then(|| self.pos += 1)
+    }
+
+    fn value(&mut self, pointer: String, key: Option<Range<usize>>) -> Option<()> {
+        self.skip_ws();
+        let start = self.pos;
+        // 先占位，子节点解析完成后再补上结束位置，保持先序
+        let slot = self.nodes.len();
+        self.nodes.push(NodeSpan {
+            pointer: pointer.clone(),
+            key,
+            value: start..start,
+        });
+
+        match self.bytes.get(self.pos)? {
+            b'{' | b'[' if self.depth >= MAX_DEPTH => return None,
+            b'{' => {
+                self.depth += 1;
+                self.pos += 1;
+                self.skip_ws();
+                if self.bytes.get(self.pos) == Some(&b'}') {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        self.skip_ws();
+                        let key_span = self.string()?;
+                        let name: String = serde_json::from_str(&self.text[key_span.clone()]).ok()?;
+                        self.eat(b':')?;
+                        self.value(join_pointer(&pointer, &name), Some(key_span))?;
+                        self.skip_ws();
+                        match self.bytes.get(self.pos)? {
+                            b',' => self.pos += 1,
+                            b'}' => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => return None,
+                        }
+                    }
+                }
+                self.depth -= 1;
+            }
+            b'[' => {
+                self.depth += 1;
+                self.pos += 1;
+                self.skip_ws();
+                if self.bytes.get(self.pos) == Some(&b']') {
+                    self.pos += 1;
+                } else {
+                    let mut idx = 0;
+                    loop {
+                        self.value(format!("{}/{}", pointer, idx), None)?;
+                        idx += 1;
+                        self.skip_ws();
+                        match self.bytes.get(self.pos)? {
+                            b',' => self.pos += 1,
+                            b']' => {
+                                self.pos += 1;
+                                break;
+                            }
+                            _ => return None,
+                        }
+                    }
+                }
+                self.depth -= 1;
+            }
+            b'"' => {
+                self.string()?;
+            }
+            _ => {
+                // 数字、true、false、null：读到分隔符为止
+                while let Some(b) = self.bytes.get(self.pos) {
+                    if matches!(b, b',' | b'}' | b']' | b' ' | b'\t' | b'\n' | b'\r') {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return None;
+                }
+            }
+        }
+
+        self.nodes[slot].value = start..self.pos;
+        Some(())
+    }
+
+    /// 读取一个字符串字面量，返回含引号的范围
+    fn string(&mut self) -> Option<Range<usize>> {
+        let start = self.pos;
+        if self.bytes.get(self.pos) != Some(&b'"') {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            match self.bytes.get(self.pos)? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(start..self.pos);
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slice<'a>(text: &'a str, map: &SpanMap, pointer: &str) -> (&'a str, &'a str) {
+        let node = map.get(pointer).unwrap();
+        let key = node.key.clone().map_or("", |k| &text[k]);
+        (key, &text[node.value.clone()])
+    }
+
+    #[test]
+    fn records_keys_and_values() {
+        let text = r#" {"a": [1, true, {"b": null}], "s": "x\"y", "e": {}} "#;
+        let map = SpanMap::parse(text).unwrap();
+        assert_eq!(slice(text, &map, ""), ("", &text[1..text.len() - 1]));
+        assert_eq!(slice(text, &map, "/a"), (r#""a""#, r#"[1, true, {"b": null}]"#));
+        assert_eq!(slice(text, &map, "/a/1"), ("", "true"));
+        assert_eq!(slice(text, &map, "/a/2/b"), (r#""b""#, "null"));
+        assert_eq!(slice(text, &map, "/s"), (r#""s""#, r#""x\"y""#));
+        assert_eq!(slice(text, &map, "/e"), (r#""e""#, "{}"));
+        assert!(map.get("/a/3").is_none());
+    }
+
+    #[test]
+    fn keys_are_unescaped_then_pointer_escaped() {
+        let text = r#"{"a/b": {"m~n": 1, "A": 2}}"#;
+        let map = SpanMap::parse(text).unwrap();
+        assert_eq!(slice(text, &map, "/a~1b/m~0n"), (r#""m~n""#, "1"));
+        assert_eq!(slice(text, &map, "/a~1b/A"), (r#""A""#, "2"));
+    }
+
+    #[test]
+    fn node_at_finds_the_deepest_node() {
+        let text = r#"{"a": [10, 20], "b": 3}"#;
+        let map = SpanMap::parse(text).unwrap();
+        let at = |offset| map.node_at(offset).map(|n| n.pointer.as_str());
+        assert_eq!(at(text.find("20").unwrap() + 1), Some("/a/1"));
+        // 键也属于该节点
+        assert_eq!(at(text.find("\"b\"").unwrap()), Some("/b"));
+        assert_eq!(at(text.find('[').unwrap()), Some("/a"));
+        assert_eq!(at(0), Some(""));
+    }
+
+    #[test]
+    fn rejects_invalid_text() {
+        for text in ["", "{", r#"{"a" 1}"#, "[1 2]", "[1,]", "{} {}", r#"{"a": "x}"#] {
+            assert!(SpanMap::parse(text).is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn nesting_depth_is_capped() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(SpanMap::parse(&nested(MAX_DEPTH)).is_some());
+        assert!(SpanMap::parse(&nested(MAX_DEPTH + 1)).is_none());
+        // 远超上限的输入也不会栈溢出
+        assert!(SpanMap::parse(&nested(1_000_000)).is_none());
+        let objects = format!("{}1{}", r#"{"a":"#.repeat(MAX_DEPTH + 1), "}".repeat(MAX_DEPTH + 1));
+        assert!(SpanMap::parse(&objects).is_none());
+    }
+
+    #[test]
+    fn char_byte_conversion() {
+        let text = "{\"名\": 1}";
+        assert_eq!(char_to_byte(text, 2), 2);
+        assert_eq!(char_to_byte(text, 3), 5);
+        assert_eq!(char_to_byte(text, 100), text.len());
+        assert_eq!(byte_to_char(text, 5), 3);
+        assert_eq!(byte_to_char(text, 100), text.chars().count());
+    }
+}
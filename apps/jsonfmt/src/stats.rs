@@ -0,0 +1,288 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::tree_ops::join_pointer;
+use crate::value_editor::ValueKind;
+
+/// 各类排行保留的条数
+const TOP_N: usize = 10;
+/// 最长字符串的预览长度
+const PREVIEW_LEN: usize = 60;
+
+/// 子树及其压缩后的字节数，子节点按大小降序
+pub struct SizeNode {
+    pub pointer: String,
+    /// 键名或下标，根节点为空
+    pub label: String,
+    pub kind: ValueKind,
+    pub bytes: usize,
+    pub children: Vec<SizeNode>,
+}
+
+/// 数组中对象元素的键名出现次数
+pub struct KeyFrequency {
+    pub pointer: String,
+    /// 数组中对象元素的个数
+    pub objects: usize,
+    /// 键名及出现次数，按次数降序
+    pub keys: Vec<(String, usize)>,
+}
+
+pub struct LongString {
+    pub pointer: String,
+    pub chars: usize,
+    pub preview: String,
+}
+
+/// 文档的统计信息
+pub struct Stats {
+    /// 节点总数（包括根节点）
+    pub total_nodes: usize,
+    /// 最深节点的路径段数
+    pub max_depth: usize,
+    /// 按 ValueKind::ALL 顺序排列的各类型节点数
+    pub type_counts: Vec<(ValueKind, usize)>,
+    pub size: SizeNode,
+    /// 最大的对象/数组子树（不含根节点）
+    pub largest: Vec<(String, usize)>,
+    /// 含对象元素最多的若干数组的键名频率
+    pub key_frequency: Vec<KeyFrequency>,
+    pub longest_strings: Vec<LongString>,
+}
+
+#[derive(Default)]
+struct Collector {
+    total_nodes: usize,
+    max_depth: usize,
+    type_counts: HashMap<ValueKind, usize>,
+    containers: Vec<(String, usize)>,
+    key_frequency: Vec<KeyFrequency>,
+    strings: Vec<LongString>,
+}
+
+impl Stats {
+    pub fn new(root: &Value) -> Self {
+        let mut collector = Collector::default();
+        let size = collector.walk(root, String::new(), String::new(), 0);
+
+        let mut largest = collector.containers;
+        largest.sort_by_key(|x| Reverse(x.1));
+        largest.truncate(TOP_N);
+
+        let mut key_frequency = collector.key_frequency;
+        key_frequency.sort_by_key(|x| Reverse(x.objects));
+        key_frequency.truncate(TOP_N);
+
+        let mut longest_strings = collector.strings;
+        longest_strings.sort_by_key(|x| Reverse(x.chars));
+        longest_strings.truncate(TOP_N);
+
+        Self {
+            total_nodes: collector.total_nodes,
+            max_depth: collector.max_depth,
+            type_counts: ValueKind::ALL
+                .iter()
+                .map(|k| (*k, collector.type_counts.get(k).copied().unwrap_or(0)))
+                .collect(),
+            size,
+            largest,
+            key_frequency,
+            longest_strings,
+        }
+    }
+}
+
+impl Collector {
+    fn walk(&mut self, value: &Value, pointer: String, label: String, depth: usize) -> SizeNode {
+        let kind = ValueKind::of(value);
+        self.total_nodes += 1;
+        self.max_depth = self.max_depth.max(depth);
+        *self.type_counts.entry(kind).or_insert(0) += 1;
+
+        let mut children = Vec::new();
+        // 压缩后的大小：括号、逗号，对象还要加上键名和冒号
+        let bytes = match value {
+            Value::Object(map) => {
+                let mut bytes = 2 + map.len().saturating_sub(1);
+                for (k, child) in map {
+                    let node = self.walk(child, join_pointer(&pointer, k), k.clone(), depth + 1);
+                    bytes += json_len(k) + 1 + node.bytes;
+                    children.push(node);
+                }
+                bytes
+            }
+            Value::Array(arr) => {
+                self.count_keys(&pointer, arr);
+                let mut bytes = 2 + arr.len().saturating_sub(1);
+                for (i, child) in arr.iter().enumerate() {
+                    let node = self.walk(child, format!("{}/{}", pointer, i), i.to_string(), depth + 1);
+                    bytes += node.bytes;
+                    children.push(node);
+                }
+                bytes
+            }
+            Value::String(s) => {
+                self.strings.push(LongString {
+                    pointer: pointer.clone(),
+                    chars: s.chars().count(),
+                    preview: s.chars().take(PREVIEW_LEN).collect(),
+                });
+                // 只保留较长的若干个，避免字符串很多时占用过多内存
+                if self.strings.len() > TOP_N * 4 {
+                    self.strings.sort_by_key(|x| Reverse(x.chars));
+                    self.strings.truncate(TOP_N);
+                }
+                json_len(s)
+            }
+            _ => value.to_string().len(),
+        };
+
+        if matches!(kind, ValueKind::Object | ValueKind::Array) && !pointer.is_empty() {
+            self.containers.push((pointer.clone(), bytes));
+        }
+        children.sort_by_key(|x| Reverse(x.bytes));
+        SizeNode {
+            pointer,
+            label,
+            kind,
+            bytes,
+            children,
+        }
+    }
+
+    fn count_keys(&mut self, pointer: &str, arr: &[Value]) {
+        let mut objects = 0;
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for map in arr.iter().filter_map(Value::as_object) {
+            objects += 1;
+            for key in map.keys() {
+                match index.get(key.as_str()) {
+                    Some(&i) => counts[i].1 += 1,
+                    None => {
+                        index.insert(key, counts.len());
+                        counts.push((key.clone(), 1));
+                    }
+                }
+            }
+        }
+        if objects < 2 {
+            return;
+        }
+        // 稳定排序，次数相同的保持首次出现的顺序
+        counts.sort_by_key(|x| Reverse(x.1));
+        self.key_frequency.push(KeyFrequency {
+            pointer: pointer.to_string(),
+            objects,
+            keys: counts,
+        });
+    }
+}
+
+/// 字符串序列化为 JSON 后的字节数
+fn json_len(s: &str) -> usize {
+    serde_json::to_string(s).map_or(s.len() + 2, |json| json.len())
+}
+
+/// 以 B / KB / MB 显示字节数
+pub fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample() -> Value {
+        json!({
+            "users": [
+                {"name": "张三", "tags": ["a\"b", "\n"], "age": 30},
+                {"name": "Li", "email": null},
+                {"name": "Wang", "age": 41.5, "admin": true},
+                "not an object"
+            ],
+            "empty": {},
+            "meta": {"version": [1, 2], "note": "x"}
+        })
+    }
+
+    /// 每个子树的大小都应等于该子树压缩序列化后的字节数
+    fn check_sizes(root: &Value, node: &SizeNode) {
+        let value = root.pointer(&node.pointer).unwrap();
+        assert_eq!(node.bytes, value.to_string().len(), "{}", node.pointer);
+        assert!(node.children.windows(2).all(|w| w[0].bytes >= w[1].bytes));
+        for child in &node.children {
+            check_sizes(root, child);
+        }
+    }
+
+    #[test]
+    fn sizes_match_compact_json() {
+        let root = sample();
+        let stats = Stats::new(&root);
+        check_sizes(&root, &stats.size);
+        assert_eq!(stats.size.label, "");
+        assert_eq!(stats.size.children[0].label, "users");
+        let scalar = Stats::new(&json!("é"));
+        assert_eq!(scalar.size.bytes, 4);
+        assert!(scalar.largest.is_empty());
+    }
+
+    #[test]
+    fn counts_nodes_types_and_depth() {
+        let stats = Stats::new(&sample());
+        assert_eq!(stats.total_nodes, 22);
+        assert_eq!(stats.max_depth, 4);
+        let counts: Vec<usize> = stats.type_counts.iter().map(|(_, n)| *n).collect();
+        // 字符串、数字、布尔、null、对象、数组
+        assert_eq!(counts, [7, 4, 1, 1, 6, 3]);
+        assert_eq!(counts.iter().sum::<usize>(), stats.total_nodes);
+    }
+
+    #[test]
+    fn rankings() {
+        let root = sample();
+        let stats = Stats::new(&root);
+        assert_eq!(stats.largest[0], ("/users".to_string(), root["users"].to_string().len()));
+        assert!(!stats.largest.iter().any(|(p, _)| p.is_empty()));
+        assert!(stats.largest.iter().any(|(p, n)| p == "/empty" && *n == 2));
+
+        // 只统计含两个以上对象元素的数组；次数相同时保持首次出现的顺序
+        assert_eq!(stats.key_frequency.len(), 1);
+        let users = &stats.key_frequency[0];
+        assert_eq!(users.pointer, "/users");
+        assert_eq!(users.objects, 3);
+        let keys: Vec<(&str, usize)> = users.keys.iter().map(|(k, n)| (k.as_str(), *n)).collect();
+        assert_eq!(keys, [("name", 3), ("age", 2), ("tags", 1), ("email", 1), ("admin", 1)]);
+
+        assert_eq!(stats.longest_strings[0].pointer, "/users/3");
+        assert_eq!(stats.longest_strings[0].chars, 13);
+        let long = "长".repeat(PREVIEW_LEN + 5);
+        let stats = Stats::new(&json!([long]));
+        assert_eq!(stats.longest_strings[0].chars, PREVIEW_LEN + 5);
+        assert_eq!(stats.longest_strings[0].preview.chars().count(), PREVIEW_LEN);
+    }
+
+    #[test]
+    fn keeps_only_top_strings() {
+        let strings: Vec<Value> = (0..TOP_N * 5).map(|i| json!("x".repeat(i))).collect();
+        let stats = Stats::new(&Value::Array(strings));
+        let lengths: Vec<usize> = stats.longest_strings.iter().map(|s| s.chars).collect();
+        assert_eq!(lengths, ((TOP_N * 4)..TOP_N * 5).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn bytes_are_formatted_with_units() {
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1_048_576), "3.0 MB");
+    }
+}
@@ -0,0 +1,299 @@
+use appkit::theme::Palette;
+use eframe::egui::{self, Color32, Pos2, Rect, Sense, Vec2};
+use serde_json::Value;
+
+use crate::bulk_panel::text_hash;
+use crate::i18n::tr;
+use crate::stats::{format_bytes, SizeNode, Stats};
+use crate::value_editor::ValueKind;
+
+/// 矩形树图的高度
+const TREEMAP_HEIGHT: f32 = 320.0;
+/// 矩形树图最多向下展开的层数
+const TREEMAP_DEPTH: usize = 4;
+/// 每个容器最多单独绘制的子节点数，其余合并为一块
+const TREEMAP_CHILDREN: usize = 200;
+/// 容器顶部标题栏的高度
+const TREEMAP_HEADER: f32 = 14.0;
+
+/// 统计面板：文档规模、类型分布、键名频率与按大小划分的矩形树图
+#[derive(Default)]
+pub struct StatsPanel {
+    /// 统计结果及对应原文的哈希
+    cache: Option<(u64, Stats)>,
+}
+
+/// 各类型的颜色，取自当前主题的语法配色，与编辑器中的着色一致
+fn kind_color(kind: ValueKind, palette: &Palette) -> Color32 {
+    match kind {
+        ValueKind::Object => palette.key,
+        ValueKind::Array => palette.punct,
+        ValueKind::String => palette.string,
+        ValueKind::Number => palette.number,
+        ValueKind::Bool | ValueKind::Null => palette.literal,
+    }
+}
+
+/// 在该底色上清晰可读的文字颜色
+fn text_color_on(fill: Color32) -> Color32 {
+    if egui::Rgba::from(fill).intensity() > 0.3 { Color32::BLACK } else { Color32::WHITE }
+}
+
+/// 占比，整体为 0 时返回 0
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 * 100.0 / total as f64 }
+}
+
+/// 一行中最差的长宽比，用于决定行中放入多少项
+fn worst_ratio(row: &[f32], side: f32) -> f32 {
+    let sum: f32 = row.iter().sum();
+    let max = row.iter().copied().fold(f32::MIN, f32::max);
+    let min = row.iter().copied().fold(f32::MAX, f32::min);
+    let side2 = side * side;
+    (side2 * max / (sum * sum)).max(sum * sum / (side2 * min))
+}
+
+/// 按面积比例划分矩形（squarified 算法），sizes 需按降序排列
+fn squarify(sizes: &[f32], rect: Rect) -> Vec<Rect> {
+    let total: f32 = sizes.iter().sum();
+    if total <= 0.0 || rect.area() <= 0.0 {
+        return vec![Rect::NOTHING; sizes.len()];
+    }
+    let areas: Vec<f32> = sizes.iter().map(|s| s / total * rect.area()).collect();
+    let mut out = Vec::with_capacity(areas.len());
+    let mut rect = rect;
+    let mut start = 0;
+    while start < areas.len() {
+        let side = rect.width().min(rect.height());
+        let mut end = start + 1;
+        while end < areas.len() && worst_ratio(&areas[start..=end], side) <= worst_ratio(&areas[start..end], side) {
+            end += 1;
+        }
+        let row = &areas[start..end];
+        let row_area: f32 = row.iter().sum();
+        if rect.width() >= rect.height() {
+            // 在左侧放一列
+            let width = row_area / rect.height();
+            let mut y = rect.top();
+            for area in row {
+                let height = area / width;
+                out.push(Rect::from_min_size(Pos2::new(rect.left(), y), Vec2::new(width, height)));
+                y += height;
+            }
+            rect.min.x += width;
+        } else {
+            // 在顶部放一行
+            let height = row_area / rect.width();
+            let mut x = rect.left();
+            for area in row {
+                let width = area / height;
+                out.push(Rect::from_min_size(Pos2::new(x, rect.top()), Vec2::new(width, height)));
+                x += width;
+            }
+            rect.min.y += height;
+        }
+        start = end;
+    }
+    out
+}
+
+/// 矩形树图中的一块
+struct Tile<'a> {
+    rect: Rect,
+    node: Option<&'a SizeNode>,
+    /// 合并显示的其余子节点
+    rest: Option<(usize, usize)>,
+}
+
+/// 递归划分节点的矩形，先放入的是外层节点
+fn layout<'a>(node: &'a SizeNode, rect: Rect, depth: usize, tiles: &mut Vec<Tile<'a>>) {
+    tiles.push(Tile { rect, node: Some(node), rest: None });
+    let inner = Rect::from_min_max(
+        Pos2::new(rect.left() + 2.0, rect.top() + TREEMAP_HEADER),
+        Pos2::new(rect.right() - 2.0, rect.bottom() - 2.0),
+    );
+    if depth >= TREEMAP_DEPTH || node.children.is_empty() || inner.width() < 16.0 || inner.height() < 16.0 {
+        return;
+    }
+    let shown = &node.children[..node.children.len().min(TREEMAP_CHILDREN)];
+    let rest: usize = node.children[shown.len()..].iter().map(|c| c.bytes).sum();
+    let mut sizes: Vec<f32> = shown.iter().map(|c| c.bytes as f32).collect();
+    if rest > 0 {
+        sizes.push(rest as f32);
+    }
+    let rects = squarify(&sizes, inner);
+    for (child, child_rect) in shown.iter().zip(&rects) {
+        layout(child, *child_rect, depth + 1, tiles);
+    }
+    if rest > 0 {
+        tiles.push(Tile {
+            rect: rects[shown.len()],
+            node: None,
+            rest: Some((node.children.len() - shown.len(), rest)),
+        });
+    }
+}
+
+/// 可点击的节点路径
+fn pointer_link(ui: &mut egui::Ui, pointer: &str, target: &mut Option<String>) {
+    let text = if pointer.is_empty() { "/" } else { pointer };
+    if ui.link(egui::RichText::new(text).monospace()).clicked() {
+        *target = Some(pointer.to_string());
+    }
+}
+
+impl StatsPanel {
+    /// 绘制面板内容，返回点击的节点路径
+    pub fn show(&mut self, ui: &mut egui::Ui, root: Option<&Value>, text: &str) -> Option<String> {
+        let Some(root) = root else {
+            ui.label(tr!("没有可统计的 JSON"));
+            return None;
+        };
+        let key = text_hash(text);
+        if self.cache.as_ref().is_none_or(|(k, _)| *k != key) {
+            self.cache = Some((key, Stats::new(root)));
+        }
+        let (_, stats) = self.cache.as_ref()?;
+        let total = stats.size.bytes;
+        let mut target = None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("stats_summary").num_columns(2).show(ui, |ui| {
+                ui.label(tr!("节点总数："));
+                ui.label(stats.total_nodes.to_string());
+                ui.end_row();
+                ui.label(tr!("最大深度："));
+                ui.label(stats.max_depth.to_string());
+                ui.end_row();
+                ui.label(tr!("压缩后大小："));
+                ui.label(tr!("{}（原文 {}）", format_bytes(total), format_bytes(text.len())));
+                ui.end_row();
+                ui.label(tr!("类型分布："));
+                ui.horizontal_wrapped(|ui| {
+                    let palette = Palette::of(ui);
+                    for (kind, count) in &stats.type_counts {
+                        ui.colored_label(kind_color(*kind, &palette), format!("{} {}", kind.label(), count));
+                    }
+                });
+                ui.end_row();
+            });
+
+            ui.separator();
+            ui.strong(tr!("矩形树图"));
+            ui.weak(tr!("面积对应压缩后的大小，点击可在树中定位"));
+            self.show_treemap(ui, &stats.size, &mut target);
+
+            egui::CollapsingHeader::new(tr!("最大的子树")).default_open(true).show(ui, |ui| {
+                egui::Grid::new("stats_largest").striped(true).show(ui, |ui| {
+                    for (pointer, bytes) in &stats.largest {
+                        pointer_link(ui, pointer, &mut target);
+                        ui.label(format_bytes(*bytes));
+                        ui.label(format!("{:.1}%", percent(*bytes, total)));
+                        ui.end_row();
+                    }
+                });
+            });
+
+            egui::CollapsingHeader::new(tr!("数组元素的键名频率")).show(ui, |ui| {
+                if stats.key_frequency.is_empty() {
+                    ui.weak(tr!("没有包含多个对象的数组"));
+                }
+                for freq in &stats.key_frequency {
+                    ui.horizontal(|ui| {
+                        pointer_link(ui, &freq.pointer, &mut target);
+                        ui.weak(tr!("（{} 个对象）", freq.objects));
+                    });
+                    ui.indent(("key_frequency", &freq.pointer), |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for (key, count) in &freq.keys {
+                                let text = format!("{} {}/{}", key, count, freq.objects);
+                                // 不是每个元素都有的键名用弱化颜色显示
+                                if *count == freq.objects {
+                                    ui.monospace(text);
+                                } else {
+                                    ui.weak(text);
+                                }
+                            }
+                        });
+                    });
+                }
+            });
+
+            egui::CollapsingHeader::new(tr!("最长的字符串")).show(ui, |ui| {
+                for long in &stats.longest_strings {
+                    ui.horizontal(|ui| {
+                        pointer_link(ui, &long.pointer, &mut target);
+                        ui.weak(tr!("{} 个字符", long.chars));
+                    });
+                    ui.add(egui::Label::new(egui::RichText::new(&long.preview).weak()).truncate());
+                }
+            });
+        });
+        target
+    }
+
+    fn show_treemap(&self, ui: &mut egui::Ui, size: &SizeNode, target: &mut Option<String>) {
+        let width = ui.available_width();
+        let (response, painter) = ui.allocate_painter(Vec2::new(width, TREEMAP_HEIGHT), Sense::click());
+        let mut tiles = Vec::new();
+        layout(size, response.rect, 0, &mut tiles);
+
+        let palette = Palette::of(ui);
+        let font = egui::FontId::proportional(11.0);
+        for tile in &tiles {
+            let fill = match tile.node {
+                Some(node) if node.children.is_empty() => kind_color(node.kind, &palette),
+                Some(node) => kind_color(node.kind, &palette).gamma_multiply(0.55),
+                None => palette.line_number,
+            };
+            let text_color = text_color_on(fill);
+            painter.rect_filled(tile.rect, 2.0, fill);
+            painter.rect_stroke(
+                tile.rect,
+                2.0,
+                egui::Stroke::new(1.0, Color32::from_black_alpha(120)),
+                egui::StrokeKind::Inside,
+            );
+            let label = match (tile.node, tile.rest) {
+                (Some(node), _) => format!("{} {}", node.label, format_bytes(node.bytes)),
+                (None, Some((count, _))) => tr!("其余 {} 项", count),
+                (None, None) => String::new(),
+            };
+            if tile.rect.width() > 40.0 && tile.rect.height() > TREEMAP_HEADER {
+                let galley = painter.layout(label, font.clone(), text_color, tile.rect.width() - 4.0);
+                let clip = painter.with_clip_rect(tile.rect.intersect(painter.clip_rect()));
+                clip.galley(tile.rect.left_top() + Vec2::new(2.0, 1.0), galley, text_color);
+            }
+        }
+
+        // 悬停和点击时取最内层的块
+        let hovered = response
+            .hover_pos()
+            .and_then(|pos| tiles.iter().rev().find(|t| t.rect.contains(pos)));
+        if let Some(tile) = hovered {
+            // 与树中搜索命中同色，去掉透明度以便在各种填充色上看清
+            let [r, g, b, _] = palette.tree_match.to_srgba_unmultiplied();
+            painter.rect_stroke(
+                tile.rect,
+                2.0,
+                egui::Stroke::new(2.0, Color32::from_rgb(r, g, b)),
+                egui::StrokeKind::Inside,
+            );
+            let tooltip = match (tile.node, tile.rest) {
+                (Some(node), _) => tr!(
+                    "{}\n{}，占 {}%",
+                    if node.pointer.is_empty() { "/" } else { &node.pointer },
+                    format_bytes(node.bytes),
+                    format!("{:.1}", percent(node.bytes, size.bytes))
+                ),
+                (None, Some((count, bytes))) => tr!("其余 {} 项，共 {}", count, format_bytes(bytes)),
+                (None, None) => String::new(),
+            };
+            let clicked = response.clicked();
+            response.on_hover_text(tooltip);
+            if clicked {
+                *target = tile.node.map(|n| n.pointer.clone());
+            }
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::edit::JsonEdit;
+use crate::i18n::tr;
+use crate::tree_ops::{join_pointer, Placement};
+
+/// 表格的一列
+#[derive(Clone, PartialEq)]
+pub enum Column {
+    /// 对象成员
+    Key(String),
+    /// 不是对象的元素本身
+    Value,
+}
+
+impl Column {
+    pub fn title(&self) -> &str {
+        match self {
+            Column::Key(key) => key,
+            Column::Value => tr!("（值）"),
+        }
+    }
+
+    /// 元素在该列中的单元格；对象缺少该键时为 None
+    pub fn cell<'a>(&self, element: &'a Value) -> Option<&'a Value> {
+        match (self, element) {
+            (Column::Key(key), Value::Object(map)) => map.get(key),
+            (Column::Value, Value::Object(_)) | (Column::Key(_), _) => None,
+            (Column::Value, value) => Some(value),
+        }
+    }
+}
+
+/// 所有元素键名的并集（按首次出现的顺序），有非对象元素时再加一列“值”
+pub fn columns(array: &[Value]) -> Vec<Column> {
+    let mut columns = Vec::new();
+    let mut has_scalar = false;
+    for element in array {
+        match element {
+            Value::Object(map) => {
+                for key in map.keys() {
+                    if !columns.iter().any(|c| matches!(c, Column::Key(k) if k == key)) {
+                        columns.push(Column::Key(key.clone()));
+                    }
+                }
+            }
+            _ => has_scalar = true,
+        }
+    }
+    if has_scalar {
+        columns.push(Column::Value);
+    }
+    columns
+}
+
+/// 单元格显示的文本：字符串不带引号，缺失为空
+pub fn cell_text(cell: Option<&Value>) -> String {
+    match cell {
+        None => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+    }
+}
+
+/// 排序时的比较：数字按数值排在其他值之前，其余按单元格文本的字典序，
+/// 缺失的无论升降序都排在最后。数字与文本分开比较，保证是全序
+fn compare(a: Option<&Value>, b: Option<&Value>, ascending: bool) -> Ordering {
+    let ordering = match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(Value::Number(x)), Some(Value::Number(y))) => {
+            x.as_f64().partial_cmp(&y.as_f64()).unwrap_or(Ordering::Equal)
+        }
+        (Some(Value::Number(_)), Some(_)) => Ordering::Less,
+        (Some(_), Some(Value::Number(_))) => Ordering::Greater,
+        (Some(x), Some(y)) => cell_text(Some(x)).cmp(&cell_text(Some(y))),
+    };
+    if ascending || a.is_none() || b.is_none() { ordering } else { ordering.reverse() }
+}
+
+/// 经过筛选和排序后要显示的行（元素下标）
+///
+/// 每列的筛选条件不区分大小写地匹配单元格文本，空条件不筛选。
+pub fn visible_rows(
+    array: &[Value],
+    columns: &[Column],
+    filters: &[String],
+    sort: Option<(usize, bool)>,
+) -> Vec<usize> {
+    let filters: Vec<(&Column, String)> = columns
+        .iter()
+        .zip(filters)
+        .filter(|(_, f)| !f.trim().is_empty())
+        .map(|(c, f)| (c, f.trim().to_lowercase()))
+        .collect();
+    let mut rows: Vec<usize> = (0..array.len())
+        .filter(|&i| {
+            filters
+                .iter()
+                .all(|(c, f)| cell_text(c.cell(&array[i])).to_lowercase().contains(f))
+        })
+        .collect();
+    if let Some((column, ascending)) = sort.and_then(|(col, asc)| Some((columns.get(col)?, asc))) {
+        rows.sort_by(|&a, &b| compare(column.cell(&array[a]), column.cell(&array[b]), ascending));
+    }
+    rows
+}
+
+/// 修改单元格的编辑操作；对象缺少该键时追加该成员
+pub fn cell_edit(array_pointer: &str, row: usize, column: &Column, element: &Value, new_value: Value) -> Option<JsonEdit> {
+    let element_pointer = format!("{}/{}", array_pointer, row);
+    match column {
+        Column::Key(key) if element.get(key).is_some() => Some(JsonEdit::EditValue {
+            pointer: join_pointer(&element_pointer, key),
+            new_value,
+        }),
+        Column::Key(key) if element.is_object() => Some(JsonEdit::InsertValue {
+            at: Placement::Append(element_pointer),
+            key: Some(key.clone()),
+            value: new_value,
+        }),
+        Column::Key(_) => None,
+        Column::Value if element.is_object() => None,
+        Column::Value => Some(JsonEdit::EditValue {
+            pointer: element_pointer,
+            new_value,
+        }),
+    }
+}
+
+/// 按 RFC 4180 转义一个 CSV 字段
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// 把指定的行导出为 CSV，首行为列名
+pub fn to_csv(array: &[Value], columns: &[Column], rows: &[usize]) -> String {
+    let mut out = String::new();
+    let header: Vec<String> = columns.iter().map(|c| csv_field(c.title())).collect();
+    out.push_str(&header.join(","));
+    out.push_str("\r\n");
+    for &row in rows {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|c| csv_field(&cell_text(c.cell(&array[row]))))
+            .collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn key(k: &str) -> Column {
+        Column::Key(k.to_string())
+    }
+
+    #[test]
+    fn columns_union_keys_in_first_seen_order() {
+        let array = [json!({"b": 1, "a": 2}), json!(3), json!({"c": 4, "a": 5}), json!([6])];
+        assert!(columns(&array) == [key("b"), key("a"), key("c"), Column::Value]);
+        assert!(columns(&[json!({"x": 1})]) == [key("x")]);
+        assert!(columns(&[]).is_empty());
+        assert_eq!(key("a").cell(&array[1]), None);
+        assert_eq!(Column::Value.cell(&array[0]), None);
+        assert_eq!(cell_text(Column::Value.cell(&array[3])), "[6]");
+        assert_eq!(cell_text(key("a").cell(&json!({"a": "s"}))), "s");
+    }
+
+    #[test]
+    fn mixed_types_sort_with_missing_cells_last() {
+        let array = [
+            json!({"v": 10}),
+            json!({"v": "b"}),
+            json!({}),
+            json!({"v": 9.5}),
+            json!({"v": "A"}),
+            json!({"v": null}),
+            json!({"v": true}),
+            json!({"v": "5"}),
+            json!({"v": 100}),
+        ];
+        let columns = [key("v")];
+        // 数字按数值排在前面，其余按单元格文本比较；若数字也按文本与字符串比较，
+        // 9.5 < 100 < "5" < 9.5 会形成环
+        assert_eq!(visible_rows(&array, &columns, &[], Some((0, true))), [3, 0, 8, 7, 4, 1, 5, 6, 2]);
+        assert_eq!(visible_rows(&array, &columns, &[], Some((0, false))), [6, 5, 1, 4, 7, 8, 0, 3, 2]);
+        assert_eq!(visible_rows(&array, &columns, &[], None), [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(visible_rows(&array, &columns, &[], Some((1, true))), [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn filters_ignore_case_and_blank_conditions() {
+        let array = [json!({"n": "Alice", "k": 1}), json!({"n": "bob", "k": 2}), json!({"k": 3})];
+        let columns = columns(&array);
+        let filters = |n: &str, k: &str| [n.to_string(), k.to_string()];
+        assert_eq!(visible_rows(&array, &columns, &filters(" AL ", ""), None), [0]);
+        assert_eq!(visible_rows(&array, &columns, &filters("", "2"), None), [1]);
+        assert_eq!(visible_rows(&array, &columns, &filters("  ", ""), None), [0, 1, 2]);
+        assert!(visible_rows(&array, &columns, &filters("b", "1"), None).is_empty());
+    }
+
+    #[test]
+    fn cell_edits_and_csv() {
+        let element = json!({"a": 1});
+        assert!(matches!(
+            cell_edit("/items", 2, &key("a"), &element, json!(2)),
+            Some(JsonEdit::EditValue { pointer, .. }) if pointer == "/items/2/a"
+        ));
+        assert!(matches!(
+            cell_edit("/items", 2, &key("b/c"), &element, json!(2)),
+            Some(JsonEdit::InsertValue { at: Placement::Append(parent), key: Some(k), .. })
+                if parent == "/items/2" && k == "b/c"
+        ));
+        assert!(cell_edit("", 0, &Column::Value, &element, json!(2)).is_none());
+        assert!(cell_edit("", 0, &key("a"), &json!(1), json!(2)).is_none());
+
+        let array = [json!({"a": "x,y", "b": "say \"hi\""}), json!(1)];
+        let columns = columns(&array);
+        assert_eq!(
+            to_csv(&array, &columns, &[1, 0]),
+            "a,b,（值）\r\n,,1\r\n\"x,y\",\"say \"\"hi\"\"\",\r\n"
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use eframe::egui;
+use egui_extras::{Column as TableColumn, TableBuilder};
+use serde_json::Value;
+
+use crate::edit::JsonEdit;
+use crate::i18n::tr;
+use crate::table::{self, Column};
+use crate::value_editor::ValueEditor;
+
+/// 表格行高
+const ROW_HEIGHT: f32 = 22.0;
+/// 表头高度：列名按钮加一行筛选框
+const HEADER_HEIGHT: f32 = 48.0;
+
+/// 正在编辑的单元格；使用带类型的值编辑器，保持原值的类型
+struct CellEditor {
+    /// 元素在数组中的下标
+    row: usize,
+    col: usize,
+    editor: ValueEditor,
+}
+
+/// 以表格查看数组：列为所有元素键名的并集，可排序、筛选、编辑单元格和复制为 CSV
+pub struct TableView {
+    /// 数组节点的 JSON Pointer
+    pub pointer: String,
+    columns: Vec<Column>,
+    /// 每列的筛选条件
+    filters: Vec<String>,
+    /// 排序的列和是否升序
+    sort: Option<(usize, bool)>,
+    /// 筛选、排序后显示的元素下标
+    rows: Vec<usize>,
+    /// 列和行缓存的键：原文、筛选条件和排序的哈希
+    cache_key: u64,
+    editing: Option<CellEditor>,
+}
+
+impl TableView {
+    pub fn new(pointer: String) -> Self {
+        Self {
+            pointer,
+            columns: Vec::new(),
+            filters: Vec::new(),
+            sort: None,
+            rows: Vec::new(),
+            cache_key: 0,
+            editing: None,
+        }
+    }
+
+    /// 原文、筛选或排序变化后重新计算列和行
+    fn refresh(&mut self, text: &str, array: &[Value]) {
+        let mut hasher = DefaultHasher::new();
+        (text, &self.filters, self.sort).hash(&mut hasher);
+        let key = hasher.finish();
+        if key == self.cache_key {
+            return;
+        }
+        self.cache_key = key;
+        let columns = table::columns(array);
+        if columns != self.columns {
+            self.filters = vec![String::new(); columns.len()];
+            self.sort = None;
+            self.columns = columns;
+        }
+        self.rows = table::visible_rows(array, &self.columns, &self.filters, self.sort);
+    }
+
+    /// 绘制表格；单元格编辑完成时返回对应的编辑操作
+    pub fn show(&mut self, ui: &mut egui::Ui, root: Option<&Value>, text: &str) -> Option<Vec<JsonEdit>> {
+        let Some(array) = root.and_then(|r| r.pointer(&self.pointer)).and_then(Value::as_array) else {
+            ui.label(tr!("{} 已不是数组", self.pointer));
+            return None;
+        };
+        self.refresh(text, array);
+
+        ui.horizontal(|ui| {
+            ui.monospace(if self.pointer.is_empty() { "/" } else { &self.pointer });
+            ui.label(tr!("显示 {} / {} 行，{} 列", self.rows.len(), array.len(), self.columns.len()));
+            if ui
+                .button(tr!("📋 复制为 CSV"))
+                .on_hover_text(tr!("按当前的筛选和排序复制"))
+                .clicked()
+            {
+                ui.ctx().copy_text(table::to_csv(array, &self.columns, &self.rows));
+            }
+            if self.sort.is_some() && ui.button(tr!("取消排序")).clicked() {
+                self.sort = None;
+            }
+        });
+        ui.weak(tr!("点击列名排序，双击单元格编辑"));
+        ui.separator();
+
+        let columns = &self.columns;
+        let filters = &mut self.filters;
+        let sort = &mut self.sort;
+        let rows = &self.rows;
+        let editing = &mut self.editing;
+
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            TableBuilder::new(ui)
+                .id_salt(("table_view", &self.pointer))
+                .striped(true)
+                .resizable(true)
+                .auto_shrink(false)
+                .column(TableColumn::auto().at_least(36.0))
+                .columns(TableColumn::initial(140.0).at_least(40.0).clip(true), columns.len())
+                .header(HEADER_HEIGHT, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("#");
+                    });
+                    for (i, column) in columns.iter().enumerate() {
+                        header.col(|ui| {
+                            ui.vertical(|ui| {
+                                let arrow = match *sort {
+                                    Some((c, true)) if c == i => " ▲",
+                                    Some((c, false)) if c == i => " ▼",
+                                    _ => "",
+                                };
+                                let title = egui::RichText::new(format!("{}{}", column.title(), arrow)).strong();
+                                if ui.add(egui::Button::new(title).frame(false)).clicked() {
+                                    // 依次切换为升序、降序
+                                    *sort = match *sort {
+                                        Some((c, true)) if c == i => Some((i, false)),
+                                        _ => Some((i, true)),
+                                    };
+                                }
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut filters[i])
+                                        .hint_text(tr!("筛选"))
+                                        .desired_width(f32::INFINITY),
+                                );
+                            });
+                        });
+                    }
+                })
+                .body(|body| {
+                    // 只绘制可见的行，大数组也能流畅滚动
+                    body.rows(ROW_HEIGHT, rows.len(), |mut row| {
+                        let idx = rows[row.index()];
+                        let element = &array[idx];
+                        row.col(|ui| {
+                            ui.weak(idx.to_string());
+                        });
+                        for (col, column) in columns.iter().enumerate() {
+                            row.col(|ui| {
+                                let cell = column.cell(element);
+                                let text = table::cell_text(cell);
+                                let label = match cell {
+                                    Some(Value::String(_)) | None => egui::RichText::new(text),
+                                    Some(_) => egui::RichText::new(text).monospace(),
+                                };
+                                let response = ui.add(egui::Label::new(label).truncate().sense(egui::Sense::click()));
+                                let editable = cell.is_some() || element.is_object();
+                                if editable && response.double_clicked() {
+                                    // 缺失的单元格默认按字符串编辑
+                                    let value = cell.cloned().unwrap_or_else(|| Value::String(String::new()));
+                                    *editing = Some(CellEditor {
+                                        row: idx,
+                                        col,
+                                        editor: ValueEditor::from_value(&value),
+                                    });
+                                }
+                            });
+                        }
+                    });
+                });
+        });
+
+        self.show_cell_editor(ui.ctx(), array).map(|edit| vec![edit])
+    }
+
+    /// 显示单元格编辑窗口；保存时返回对应的编辑操作
+    fn show_cell_editor(&mut self, ctx: &egui::Context, array: &[Value]) -> Option<JsonEdit> {
+        let editing = self.editing.as_mut()?;
+        let (Some(element), Some(column)) = (array.get(editing.row), self.columns.get(editing.col)) else {
+            self.editing = None;
+            return None;
+        };
+        let mut save = false;
+        let mut close = false;
+        egui::Window::new(tr!("✏️ 编辑单元格"))
+            .id(egui::Id::new(("table_cell_editor", &self.pointer)))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tr!("第 {} 行，{}", editing.row, column.title()));
+                let valid = editing.editor.show(ui, ("table_cell", &self.pointer, editing.row, editing.col));
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(valid, egui::Button::new(tr!("✅ 保存"))).clicked() {
+                        save = true;
+                    }
+                    if ui.button(tr!("❌ 取消")).clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        let mut committed = None;
+        if save {
+            if let Ok(new_value) = editing.editor.build()
+                && column.cell(element) != Some(&new_value)
+            {
+                committed = table::cell_edit(&self.pointer, editing.row, column, element, new_value);
+            }
+            close = true;
+        }
+        if close {
+            self.editing = None;
+        }
+        committed
+    }
+}
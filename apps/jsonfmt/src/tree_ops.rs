@@ -0,0 +1,304 @@
+use serde_json::{Map, Value};
+
+use crate::diff::escape_pointer_segment;
+
+/// 节点插入位置
+#[derive(Clone)]
+pub enum Placement {
+    /// 在指定节点之前
+    Before(String),
+    /// 在指定节点之后
+    After(String),
+    /// 追加到指定容器末尾
+    Append(String),
+}
+
+/// 剪切后等待粘贴的节点
+#[derive(Clone)]
+pub struct CutNode {
+    pub key: Option<String>,
+    pub value: Value,
+}
+
+/// 拖拽中的节点，作为 egui 拖放的载荷
+pub struct DragNode {
+    pub pointer: String,
+}
+
+/// 把 JSON Pointer 拆分为父路径和最后一段（已反转义）
+pub fn split_pointer(pointer: &str) -> Option<(&str, String)> {
+    let idx = pointer.rfind('/')?;
+    let segment = pointer[idx + 1..].replace("~1", "/").replace("~0", "~");
+    Some((&pointer[..idx], segment))
+}
+
+/// 在父路径后追加一段
+pub fn join_pointer(parent: &str, segment: &str) -> String {
+    format!("{}/{}", parent, escape_pointer_segment(segment))
+}
+
+/// 子节点在容器中的位置
+fn position_of(container: &Value, segment: &str) -> Option<usize> {
+    match container {
+        Value::Array(arr) => segment.parse::<usize>().ok().filter(|&i| i < arr.len()),
+        Value::Object(map) => map.keys().position(|k| k == segment),
+        _ => None,
+    }
+}
+
+fn container_len(container: &Value) -> Option<usize> {
+    match container {
+        Value::Array(arr) => Some(arr.len()),
+        Value::Object(map) => Some(map.len()),
+        _ => None,
+    }
+}
+
+/// 生成对象中不重复的键名：base、base_1、base_2……
+pub fn unique_key(map: &Map<String, Value>, base: &str) -> String {
+    let mut counter = 0;
+    let mut key = base.to_string();
+    while map.contains_key(&key) {
+        counter += 1;
+        key = format!("{}_{}", base, counter);
+    }
+    key
+}
+
+/// 把插入位置解析为（容器路径，插入下标）
+pub fn resolve(root: &Value, placement: &Placement) -> Option<(String, usize)> {
+    match placement {
+        Placement::Before(pointer) | Placement::After(pointer) => {
+            let (parent, segment) = split_pointer(pointer)?;
+            let idx = position_of(root.pointer(parent)?, &segment)?;
+            let offset = usize::from(matches!(placement, Placement::After(_)));
+            Some((parent.to_string(), idx + offset))
+        }
+        Placement::Append(pointer) => Some((pointer.clone(), container_len(root.pointer(pointer)?)?)),
+    }
+}
+
+/// 取出容器中指定下标的子节点，返回（键名，值）
+fn take_entry(container: &mut Value, idx: usize) -> Option<(Option<String>, Value)> {
+    match container {
+        Value::Array(arr) if idx < arr.len() => Some((None, arr.remove(idx))),
+        Value::Object(map) if idx < map.len() => {
+            // 保持其余键的顺序：取出全部键值对后重建对象
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            let (key, value) = entries.remove(idx);
+            map.extend(entries);
+            Some((Some(key), value))
+        }
+        _ => None,
+    }
+}
+
+/// 在容器的指定下标处插入子节点；对象中键名冲突时自动改名，返回实际使用的键名
+fn insert_entry(container: &mut Value, idx: usize, key: Option<String>, value: Value) -> Option<String> {
+    match container {
+        Value::Array(arr) => {
+            arr.insert(idx.min(arr.len()), value);
+            None
+        }
+        Value::Object(map) => {
+            let key = unique_key(map, key.as_deref().unwrap_or("new_key"));
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.insert(idx.min(entries.len()), (key.clone(), value));
+            map.extend(entries);
+            Some(key)
+        }
+        _ => None,
+    }
+}
+
+/// 在指定位置插入值
+pub fn insert_value(root: &mut Value, placement: &Placement, key: Option<String>, value: Value) {
+    if let Some((parent, idx)) = resolve(root, placement)
+        && let Some(container) = root.pointer_mut(&parent)
+    {
+        insert_entry(container, idx, key, value);
+    }
+}
+
+/// 复制节点并插入到其后面
+pub fn duplicate(root: &mut Value, pointer: &str) {
+    let Some((_, segment)) = split_pointer(pointer) else {
+        return;
+    };
+    let Some(value) = root.pointer(pointer).cloned() else {
+        return;
+    };
+    let key = format!("{}_copy", segment);
+    insert_value(root, &Placement::After(pointer.to_string()), Some(key), value);
+}
+
+/// 在同一容器内把节点上移或下移一位
+pub fn shift(root: &mut Value, pointer: &str, up: bool) {
+    let Some((parent, segment)) = split_pointer(pointer) else {
+        return;
+    };
+    let Some(container) = root.pointer_mut(parent) else {
+        return;
+    };
+    let (Some(idx), Some(len)) = (position_of(container, &segment), container_len(container)) else {
+        return;
+    };
+    let target = if up { idx.checked_sub(1) } else { Some(idx + 1).filter(|&i| i < len) };
+    if let Some(target) = target
+        && let Some((key, value)) = take_entry(container, idx)
+    {
+        insert_entry(container, target, key, value);
+    }
+}
+
+/// 把节点移动到新位置，可跨容器；不允许移动到自身内部
+pub fn move_node(root: &mut Value, from: &str, to: &Placement) {
+    let Some((from_parent, from_segment)) = split_pointer(from) else {
+        return;
+    };
+    let Some((to_parent, mut to_idx)) = resolve(root, to) else {
+        return;
+    };
+    if to_parent == from || to_parent.starts_with(&format!("{}/", from)) {
+        return;
+    }
+    let Some(from_idx) = root.pointer(from_parent).and_then(|c| position_of(c, &from_segment)) else {
+        return;
+    };
+
+    if to_parent == from_parent {
+        // 同一容器内重排：取出后插入位置前移一位
+        let Some(container) = root.pointer_mut(from_parent) else {
+            return;
+        };
+        if from_idx < to_idx {
+            to_idx -= 1;
+        }
+        if let Some((key, value)) = take_entry(container, from_idx) {
+            insert_entry(container, to_idx, key, value);
+        }
+        return;
+    }
+
+    // 跨容器：先用 null 占位取出源节点，保证插入时路径不变
+    let Some(value) = root.pointer_mut(from).map(std::mem::take) else {
+        return;
+    };
+    let key = root.pointer(from_parent).filter(|c| c.is_object()).map(|_| from_segment.clone());
+    let Some(target) = root.pointer_mut(&to_parent) else {
+        return;
+    };
+    let target_is_array = target.is_array();
+    insert_entry(target, to_idx, key, value);
+
+    // 若插入的数组位于源节点的路径上，且插在其之前，则源路径中对应下标后移一位
+    let mut from_path = from_parent.to_string();
+    if target_is_array && from_parent.starts_with(&format!("{}/", to_parent)) {
+        let rest = &from_parent[to_parent.len() + 1..];
+        let (head, tail) = rest.split_once('/').map_or((rest, ""), |(h, t)| (h, t));
+        if let Ok(i) = head.parse::<usize>()
+            && to_idx <= i
+        {
+            from_path = format!("{}/{}", to_parent, i + 1);
+            if !tail.is_empty() {
+                from_path = format!("{}/{}", from_path, tail);
+            }
+        }
+    }
+    if let Some(container) = root.pointer_mut(&from_path)
+        && let Some(idx) = position_of(container, &from_segment)
+    {
+        take_entry(container, idx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn moved(mut root: Value, from: &str, to: Placement) -> Value {
+        move_node(&mut root, from, &to);
+        root
+    }
+
+    #[test]
+    fn split_and_join_escape_segments() {
+        assert_eq!(split_pointer("/a~1b/m~0n"), Some(("/a~1b", "m~n".to_owned())));
+        assert_eq!(split_pointer("/x"), Some(("", "x".to_owned())));
+        assert_eq!(split_pointer(""), None);
+        assert_eq!(join_pointer("/a~1b", "m~n"), "/a~1b/m~0n");
+    }
+
+    #[test]
+    fn resolve_placements() {
+        let root = json!({"list": [1, 2, 3], "obj": {"a": 1, "b/c": 2}});
+        assert_eq!(resolve(&root, &Placement::Before("/list/1".into())), Some(("/list".into(), 1)));
+        assert_eq!(resolve(&root, &Placement::After("/list/1".into())), Some(("/list".into(), 2)));
+        assert_eq!(resolve(&root, &Placement::Append("/list".into())), Some(("/list".into(), 3)));
+        assert_eq!(resolve(&root, &Placement::After("/obj/b~1c".into())), Some(("/obj".into(), 2)));
+        assert_eq!(resolve(&root, &Placement::Before("/list/9".into())), None);
+        assert_eq!(resolve(&root, &Placement::Append("/list/0".into())), None);
+    }
+
+    #[test]
+    fn reorder_within_container() {
+        let root = json!([0, 1, 2, 3]);
+        assert_eq!(moved(root.clone(), "/1", Placement::After("/2".into())), json!([0, 2, 1, 3]));
+        assert_eq!(moved(root.clone(), "/3", Placement::Before("/0".into())), json!([3, 0, 1, 2]));
+        assert_eq!(moved(root, "/0", Placement::Append("".into())), json!([1, 2, 3, 0]));
+
+        let root = json!({"a": 1, "b": 2, "c": 3});
+        let out = moved(root, "/c", Placement::Before("/a".into()));
+        assert_eq!(out.as_object().unwrap().keys().collect::<Vec<_>>(), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn cannot_move_into_own_subtree() {
+        let root = json!({"a": {"b": {"c": []}}, "ab": {}});
+        assert_eq!(moved(root.clone(), "/a", Placement::Append("/a".into())), root);
+        assert_eq!(moved(root.clone(), "/a", Placement::Append("/a/b/c".into())), root);
+        assert_eq!(moved(root.clone(), "/a", Placement::Before("/a/b".into())), root);
+        // 只有同名前缀、并不在子树中的容器可以移入
+        assert_eq!(moved(root, "/a", Placement::Append("/ab".into())), json!({"ab": {"a": {"b": {"c": []}}}}));
+    }
+
+    #[test]
+    fn move_across_containers_keeps_key() {
+        let root = json!({"a": {"k": 1, "x": 0}, "b": {"k": 2}});
+        let out = moved(root, "/a/k", Placement::Append("/b".into()));
+        assert_eq!(out, json!({"a": {"x": 0}, "b": {"k": 2, "k_1": 1}}));
+    }
+
+    #[test]
+    fn move_with_escaped_segments() {
+        let root = json!({"a/b": {"m~n": 1}, "t": {}});
+        let out = moved(root, "/a~1b/m~0n", Placement::Append("/t".into()));
+        assert_eq!(out, json!({"a/b": {}, "t": {"m~n": 1}}));
+    }
+
+    #[test]
+    fn insert_before_source_ancestor_shifts_source_path() {
+        let root = json!({"list": ["a", "b", {"x": 1, "y": 2}]});
+        let out = moved(root, "/list/2/x", Placement::Before("/list/0".into()));
+        assert_eq!(out, json!({"list": [1, "a", "b", {"y": 2}]}));
+    }
+
+    #[test]
+    fn shift_and_duplicate() {
+        let mut root = json!({"a": 1, "b": 2, "list": [1, 2]});
+        shift(&mut root, "/b", true);
+        assert_eq!(root.as_object().unwrap().keys().collect::<Vec<_>>(), ["b", "a", "list"]);
+        shift(&mut root, "/list", false);
+        assert_eq!(root.as_object().unwrap().keys().collect::<Vec<_>>(), ["b", "a", "list"]);
+        shift(&mut root, "/list/0", false);
+        assert_eq!(root["list"], json!([2, 1]));
+
+        duplicate(&mut root, "/a");
+        duplicate(&mut root, "/a");
+        assert_eq!(root.as_object().unwrap().keys().collect::<Vec<_>>(), ["b", "a", "a_copy_1", "a_copy", "list"]);
+        duplicate(&mut root, "/list/1");
+        assert_eq!(root["list"], json!([2, 1, 1]));
+    }
+}
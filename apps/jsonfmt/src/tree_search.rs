@@ -0,0 +1,265 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::find::{self, FindOptions};
+use crate::i18n::tr;
+use crate::tree_ops::join_pointer;
+use crate::value_editor::ValueKind;
+
+/// 树搜索匹配的对象
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SearchTarget {
+    #[default]
+    Both,
+    Keys,
+    Values,
+}
+
+impl SearchTarget {
+    pub const ALL: [SearchTarget; 3] = [SearchTarget::Both, SearchTarget::Keys, SearchTarget::Values];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchTarget::Both => tr!("键和值"),
+            SearchTarget::Keys => tr!("仅键名"),
+            SearchTarget::Values => tr!("仅值"),
+        }
+    }
+}
+
+/// 树搜索条件
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct TreeQuery {
+    pub text: String,
+    pub target: SearchTarget,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// 只匹配指定类型的节点
+    pub kind: Option<ValueKind>,
+}
+
+impl TreeQuery {
+    /// 是否设置了任何搜索条件
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty() || self.kind.is_some()
+    }
+}
+
+/// 按先序返回所有匹配节点的 JSON Pointer（不含根节点）
+pub fn search(root: &Value, query: &TreeQuery) -> Result<Vec<String>, String> {
+    let re = if query.text.is_empty() {
+        None
+    } else {
+        let options = FindOptions {
+            case_sensitive: query.case_sensitive,
+            regex: query.regex,
+            ..Default::default()
+        };
+        Some(find::compile(&query.text, &options)?)
+    };
+    let mut hits = Vec::new();
+    walk(root, String::new(), None, re.as_ref(), query, &mut hits);
+    Ok(hits)
+}
+
+/// 标量值用于匹配的文本：字符串取原值，其余取 JSON 写法
+fn scalar_text(value: &Value) -> Option<Cow<'_, str>> {
+    match value {
+        Value::String(s) => Some(Cow::Borrowed(s)),
+        Value::Object(_) | Value::Array(_) => None,
+        other => Some(Cow::Owned(other.to_string())),
+    }
+}
+
+fn walk(value: &Value, pointer: String, key: Option<&str>, re: Option<&Regex>, query: &TreeQuery, hits: &mut Vec<String>) {
+    let kind_ok = query.kind.is_none_or(|kind| ValueKind::of(value) == kind);
+    let text_ok = re.is_none_or(|re| {
+        let key_hit = query.target != SearchTarget::Values && key.is_some_and(|k| re.is_match(k));
+        let value_hit = query.target != SearchTarget::Keys && scalar_text(value).is_some_and(|s| re.is_match(&s));
+        key_hit || value_hit
+    });
+    if !pointer.is_empty() && kind_ok && text_ok {
+        hits.push(pointer.clone());
+    }
+    match value {
+        Value::Object(map) => {
+            for (k, child) in map {
+                walk(child, join_pointer(&pointer, k), Some(k), re, query, hits);
+            }
+        }
+        Value::Array(arr) => {
+            for (i, child) in arr.iter().enumerate() {
+                walk(child, format!("{}/{}", pointer, i), None, re, query, hits);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 所有匹配节点的祖先（含根节点），展开这些节点即可看到全部匹配
+pub fn ancestors(hits: &[String]) -> HashSet<String> {
+    let mut set = HashSet::new();
+    for hit in hits {
+        for (idx, _) in hit.match_indices('/') {
+            set.insert(hit[..idx].to_string());
+        }
+    }
+    set
+}
+
+/// 只保留匹配分支的树；数组元素被剔除后下标会变化，因此记录与原路径的对应关系
+pub struct Pruned {
+    pub value: Value,
+    to_original: HashMap<String, String>,
+    from_original: HashMap<String, String>,
+}
+
+impl Pruned {
+    /// 筛选后的树中的路径对应的原路径
+    pub fn original<'a>(&'a self, pointer: &'a str) -> &'a str {
+        self.to_original.get(pointer).map_or(pointer, String::as_str)
+    }
+
+    /// 原路径在筛选后的树中的路径
+    pub fn filtered<'a>(&'a self, pointer: &'a str) -> Option<&'a str> {
+        self.from_original.get(pointer).map(String::as_str)
+    }
+}
+
+/// 裁剪出只包含匹配节点及其祖先的树，匹配节点的子树整体保留；没有匹配时返回 None
+pub fn prune(root: &Value, hits: &[String]) -> Option<Pruned> {
+    if hits.is_empty() {
+        return None;
+    }
+    let mut pruned = Pruned {
+        value: Value::Null,
+        to_original: HashMap::new(),
+        from_original: HashMap::new(),
+    };
+    let hit_set: HashSet<&str> = hits.iter().map(String::as_str).collect();
+    let ancestors = ancestors(hits);
+    pruned.value = prune_node(root, "", "", &hit_set, &ancestors, false, &mut pruned)?;
+    Some(pruned)
+}
+
+fn prune_node(
+    value: &Value,
+    original: &str,
+    filtered: &str,
+    hits: &HashSet<&str>,
+    ancestors: &HashSet<String>,
+    inside_hit: bool,
+    pruned: &mut Pruned,
+) -> Option<Value> {
+    let inside_hit = inside_hit || hits.contains(original);
+    if !inside_hit && !ancestors.contains(original) {
+        return None;
+    }
+    pruned.to_original.insert(filtered.to_string(), original.to_string());
+    pruned.from_original.insert(original.to_string(), filtered.to_string());
+    Some(match value {
+        Value::Object(map) => {
+            let mut out = Map::new();
+            for (k, child) in map {
+                let (orig, filt) = (join_pointer(original, k), join_pointer(filtered, k));
+                if let Some(v) = prune_node(child, &orig, &filt, hits, ancestors, inside_hit, pruned) {
+                    out.insert(k.clone(), v);
+                }
+            }
+            Value::Object(out)
+        }
+        Value::Array(arr) => {
+            let mut out = Vec::new();
+            for (i, child) in arr.iter().enumerate() {
+                let orig = format!("{}/{}", original, i);
+                let filt = format!("{}/{}", filtered, out.len());
+                if let Some(v) = prune_node(child, &orig, &filt, hits, ancestors, inside_hit, pruned) {
+                    out.push(v);
+                }
+            }
+            Value::Array(out)
+        }
+        other => other.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample() -> Value {
+        json!({
+            "id": 7,
+            "name": "Gadget",
+            "tags": ["id-7", "blue"],
+            "owner": {"id": "u1", "email": null},
+            "a/b": true
+        })
+    }
+
+    fn query(text: &str) -> TreeQuery {
+        TreeQuery {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn scope_limits_keys_or_values() {
+        let root = sample();
+        assert_eq!(search(&root, &query("id")).unwrap(), ["/id", "/tags/0", "/owner/id"]);
+        let keys = TreeQuery { target: SearchTarget::Keys, ..query("id") };
+        assert_eq!(search(&root, &keys).unwrap(), ["/id", "/owner/id"]);
+        let values = TreeQuery { target: SearchTarget::Values, ..query("id") };
+        assert_eq!(search(&root, &values).unwrap(), ["/tags/0"]);
+        // 非字符串标量按 JSON 写法匹配，容器本身只按键名匹配
+        assert_eq!(search(&root, &query("null")).unwrap(), ["/owner/email"]);
+        assert_eq!(search(&root, &query("owner")).unwrap(), ["/owner"]);
+    }
+
+    #[test]
+    fn regex_and_case_sensitivity() {
+        let root = sample();
+        assert_eq!(search(&root, &query("gadget")).unwrap(), ["/name"]);
+        let sensitive = TreeQuery { case_sensitive: true, ..query("gadget") };
+        assert!(search(&root, &sensitive).unwrap().is_empty());
+        let re = TreeQuery { regex: true, ..query("^(u|7)") };
+        assert_eq!(search(&root, &re).unwrap(), ["/id", "/owner/id"]);
+        // 不开启正则时按字面匹配
+        assert_eq!(search(&root, &query("a/b")).unwrap(), ["/a~1b"]);
+        let invalid = TreeQuery { regex: true, ..query("(") };
+        assert!(search(&root, &invalid).is_err());
+    }
+
+    #[test]
+    fn type_filter_alone_and_combined() {
+        let root = sample();
+        let strings = TreeQuery { kind: Some(ValueKind::String), ..Default::default() };
+        assert!(strings.is_active());
+        assert_eq!(search(&root, &strings).unwrap(), ["/name", "/tags/0", "/tags/1", "/owner/id"]);
+        let string_ids = TreeQuery { kind: Some(ValueKind::String), ..query("id") };
+        assert_eq!(search(&root, &string_ids).unwrap(), ["/tags/0", "/owner/id"]);
+        let containers = TreeQuery { kind: Some(ValueKind::Array), ..Default::default() };
+        assert_eq!(search(&root, &containers).unwrap(), ["/tags"]);
+        assert!(!TreeQuery::default().is_active());
+    }
+
+    #[test]
+    fn prune_keeps_hits_and_maps_indices() {
+        let root = sample();
+        let hits = vec!["/tags/1".to_string(), "/owner".to_string()];
+        let pruned = prune(&root, &hits).unwrap();
+        assert_eq!(pruned.value, json!({"tags": ["blue"], "owner": {"id": "u1", "email": null}}));
+        assert_eq!(pruned.original("/tags/0"), "/tags/1");
+        assert_eq!(pruned.filtered("/tags/1"), Some("/tags/0"));
+        assert_eq!(pruned.filtered("/tags/0"), None);
+        assert_eq!(pruned.original("/owner/id"), "/owner/id");
+        assert!(prune(&root, &[]).is_none());
+        assert_eq!(ancestors(&hits), HashSet::from(["".to_string(), "/tags".to_string()]));
+    }
+}
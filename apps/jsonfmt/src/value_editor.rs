@@ -0,0 +1,183 @@
+use appkit::theme::Palette;
+use eframe::egui;
+use serde_json::Value;
+
+use crate::i18n::tr;
+
+/// 超过该长度的字符串默认使用多行编辑
+const MULTILINE_THRESHOLD: usize = 60;
+
+/// 值编辑器可选择的类型
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    String,
+    Number,
+    Bool,
+    Null,
+    Object,
+    Array,
+}
+
+impl ValueKind {
+    pub const ALL: [ValueKind; 6] = [
+        ValueKind::String,
+        ValueKind::Number,
+        ValueKind::Bool,
+        ValueKind::Null,
+        ValueKind::Object,
+        ValueKind::Array,
+    ];
+
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::String(_) => ValueKind::String,
+            Value::Number(_) => ValueKind::Number,
+            Value::Bool(_) => ValueKind::Bool,
+            Value::Null => ValueKind::Null,
+            Value::Object(_) => ValueKind::Object,
+            Value::Array(_) => ValueKind::Array,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ValueKind::String => tr!("字符串"),
+            ValueKind::Number => tr!("数字"),
+            ValueKind::Bool => tr!("布尔"),
+            ValueKind::Null => "null",
+            ValueKind::Object => tr!("对象"),
+            ValueKind::Array => tr!("数组"),
+        }
+    }
+}
+
+/// 带类型的值编辑器：先选类型再输入内容，保存前校验
+pub struct ValueEditor {
+    kind: ValueKind,
+    text: String,
+    bool_value: bool,
+    multiline: bool,
+}
+
+impl ValueEditor {
+    pub fn from_value(value: &Value) -> Self {
+        let text = match value {
+            Value::String(s) => s.clone(),
+            Value::Null | Value::Bool(_) => String::new(),
+            Value::Number(n) => n.to_string(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        Self {
+            kind: ValueKind::of(value),
+            multiline: text.len() > MULTILINE_THRESHOLD || text.contains('\n'),
+            bool_value: value.as_bool().unwrap_or(false),
+            text,
+        }
+    }
+
+    /// 按当前类型解析输入，失败时返回错误说明
+    pub fn build(&self) -> Result<Value, String> {
+        match self.kind {
+            ValueKind::String => Ok(Value::String(self.text.clone())),
+            ValueKind::Bool => Ok(Value::Bool(self.bool_value)),
+            ValueKind::Null => Ok(Value::Null),
+            ValueKind::Number => match serde_json::from_str::<Value>(self.text.trim()) {
+                Ok(v @ Value::Number(_)) => Ok(v),
+                _ => Err(tr!("\"{}\" 不是有效的数字", self.text.trim())),
+            },
+            ValueKind::Object | ValueKind::Array => {
+                let v = serde_json::from_str::<Value>(&self.text).map_err(|e| e.to_string())?;
+                if ValueKind::of(&v) == self.kind {
+                    Ok(v)
+                } else {
+                    Err(tr!("内容是{}，不是{}", ValueKind::of(&v).label(), self.kind.label()))
+                }
+            }
+        }
+    }
+
+    /// 切换类型时尽量保留已输入的内容
+    fn set_kind(&mut self, kind: ValueKind) {
+        if kind == self.kind {
+            return;
+        }
+        match kind {
+            ValueKind::Object if !self.text.trim_start().starts_with('{') => self.text = "{}".to_string(),
+            ValueKind::Array if !self.text.trim_start().starts_with('[') => self.text = "[]".to_string(),
+            ValueKind::Bool => self.bool_value = self.text.trim() == "true",
+            _ => {}
+        }
+        if matches!(kind, ValueKind::Object | ValueKind::Array) {
+            self.multiline = true;
+        }
+        self.kind = kind;
+    }
+
+    /// 绘制编辑器，返回当前输入能否保存；id_salt 用于区分同时显示的多个编辑器
+    pub fn show(&mut self, ui: &mut egui::Ui, id_salt: impl std::hash::Hash) -> bool {
+        ui.horizontal(|ui| {
+            ui.label(tr!("类型："));
+            let mut kind = self.kind;
+            egui::ComboBox::from_id_salt(("value_editor_kind", id_salt))
+                .selected_text(kind.label())
+                .show_ui(ui, |ui| {
+                    for k in ValueKind::ALL {
+                        ui.selectable_value(&mut kind, k, k.label());
+                    }
+                });
+            self.set_kind(kind);
+
+            if self.kind == ValueKind::String {
+                ui.checkbox(&mut self.multiline, tr!("多行"));
+            }
+        });
+
+        match self.kind {
+            ValueKind::String if self.multiline => {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.text)
+                        .desired_rows(6)
+                        .desired_width(360.0),
+                );
+            }
+            ValueKind::String | ValueKind::Number => {
+                ui.add(egui::TextEdit::singleline(&mut self.text).desired_width(360.0));
+            }
+            ValueKind::Bool => {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.bool_value, true, "true");
+                    ui.radio_value(&mut self.bool_value, false, "false");
+                });
+            }
+            ValueKind::Null => {
+                ui.weak(tr!("值将被设置为 null"));
+            }
+            ValueKind::Object | ValueKind::Array => {
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.text)
+                        .code_editor()
+                        .desired_rows(8)
+                        .desired_width(360.0),
+                );
+            }
+        }
+
+        // 保存前显示结果类型和预览
+        match self.build() {
+            Ok(value) => {
+                let preview = value.to_string();
+                let preview = match preview.char_indices().nth(80) {
+                    Some((idx, _)) => format!("{}…", &preview[..idx]),
+                    None => preview,
+                };
+                ui.label(tr!("结果类型：{}", ValueKind::of(&value).label()));
+                ui.monospace(preview);
+                true
+            }
+            Err(e) => {
+                ui.colored_label(Palette::of(ui).error, tr!("错误：{}", e));
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// 文件变更后等待稳定的时间，避免写入过程中读到半截内容
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 两次检查文件状态的最小间隔
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// 文件状态快照：修改时间 + 文件大小
+type Stamp = (Option<SystemTime>, u64);
+
+/// 跟随文件：轮询磁盘上的文件，变更稳定后返回新内容
+pub struct FileWatcher {
+    path: PathBuf,
+    last_stamp: Option<Stamp>,
+    pending: Option<(Stamp, Instant)>,
+    last_check: Instant,
+}
+
+impl FileWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        let last_stamp = stamp_of(&path);
+        Self {
+            path,
+            last_stamp,
+            pending: None,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// 检查文件是否变化；仅在变化已稳定超过防抖时间后返回新内容
+    pub fn poll(&mut self) -> Option<Result<String, String>> {
+        let now = Instant::now();
+        if now.duration_since(self.last_check) < POLL_INTERVAL {
+            return None;
+        }
+        self.last_check = now;
+
+        let current = stamp_of(&self.path);
+        if current == self.last_stamp {
+            self.pending = None;
+            return None;
+        }
+
+        match self.pending {
+            // 状态仍在变化，重新计时
+            Some((stamp, _)) if Some(stamp) != current => {
+                self.pending = current.map(|s| (s, now));
+                None
+            }
+            Some((_, since)) if now.duration_since(since) >= DEBOUNCE => {
+                self.pending = None;
+                self.last_stamp = current;
+                Some(std::fs::read_to_string(&self.path).map_err(|e| e.to_string()))
+            }
+            Some(_) => None,
+            None => {
+                match current {
+                    Some(stamp) => self.pending = Some((stamp, now)),
+                    // 文件被删除或暂时不可读（例如原子替换过程中），等待其重新出现
+                    None => self.last_stamp = None,
+                }
+                None
+            }
+        }
+    }
+}
+
+fn stamp_of(path: &Path) -> Option<Stamp> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok(), meta.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 轮询直到返回结果或超时
+    fn wait_for_change(watcher: &mut FileWatcher) -> Option<Result<String, String>> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if let Some(result) = watcher.poll() {
+                return Some(result);
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        None
+    }
+
+    #[test]
+    fn reloads_after_change_settles() {
+        let path = std::env::temp_dir().join(format!("jsonfmt_watch_{}.json", std::process::id()));
+        std::fs::write(&path, "{}").unwrap();
+        let mut watcher = FileWatcher::new(path.clone());
+
+        std::thread::sleep(POLL_INTERVAL);
+        assert!(watcher.poll().is_none(), "unchanged file must not reload");
+
+        let started = Instant::now();
+        std::fs::write(&path, r#"{"a": 1}"#).unwrap();
+        let result = wait_for_change(&mut watcher);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(result, Some(Ok(r#"{"a": 1}"#.to_owned())));
+        assert!(started.elapsed() >= DEBOUNCE, "change must be debounced");
+    }
+}
@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use eframe::egui::{self, FontData, FontDefinitions, FontFamily};
+
+use crate::i18n::tr;
+
+/// 已加载字体的说明在 egui 临时存储中的键
+const LOADED_ID: &str = "appkit_loaded_fonts";
+
+/// 启用 bundled-fonts 特性时内置的思源黑体（Noto Sans SC）
+#[cfg(feature = "bundled-fonts")]
+const BUNDLED_CJK: &[u8] = include_bytes!("../fonts/NotoSansSC-Regular.otf");
+
+/// 常见的中文字体文件名，按优先级排列
+const CJK_FILES: &[&str] = &[
+    // Windows
+    "msyh.ttc",
+    "msyh.ttf",
+    "simhei.ttf",
+    "simsun.ttc",
+    // macOS
+    "PingFang.ttc",
+    "Hiragino Sans GB.ttc",
+    "STHeiti Medium.ttc",
+    "Arial Unicode.ttf",
+    // Linux 发行版
+    "NotoSansCJK-Regular.ttc",
+    "NotoSansCJKsc-Regular.otf",
+    "SourceHanSansSC-Regular.otf",
+    "SourceHanSans-Regular.ttc",
+    "wqy-microhei.ttc",
+    "wqy-zenhei.ttc",
+    "DroidSansFallbackFull.ttf",
+];
+
+/// 单色的 emoji 与符号字体；egui 不能绘制彩色位图字体（如 NotoColorEmoji、Apple Color Emoji）
+const SYMBOL_FILES: &[&str] = &[
+    "seguiemj.ttf",
+    "seguisym.ttf",
+    "Apple Symbols.ttf",
+    "NotoEmoji-Regular.ttf",
+    "NotoSansSymbols2-Regular.ttf",
+    "Symbola.ttf",
+    "DejaVuSans.ttf",
+];
+
+/// 字体在回退链中的角色
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// 用户指定的字体，优先于其他所有字体
+    Custom,
+    Cjk,
+    Symbol,
+}
+
+impl Role {
+    fn label(self) -> &'static str {
+        match self {
+            Role::Custom => tr!("自定义"),
+            Role::Cjk => tr!("中文"),
+            Role::Symbol => tr!("符号"),
+        }
+    }
+}
+
+/// 系统字体目录
+fn font_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if cfg!(target_os = "windows") {
+        let windir = std::env::var_os("WINDIR").map_or_else(|| PathBuf::from(r"C:\Windows"), PathBuf::from);
+        dirs.push(windir.join("Fonts"));
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join(r"Microsoft\Windows\Fonts"));
+        }
+    } else if cfg!(target_os = "macos") {
+        dirs.push(PathBuf::from("/System/Library/Fonts"));
+        dirs.push(PathBuf::from("/System/Library/Fonts/Supplemental"));
+        dirs.push(PathBuf::from("/Library/Fonts"));
+        if let Some(home) = &home {
+            dirs.push(home.join("Library/Fonts"));
+        }
+    } else {
+        if let Some(data) = std::env::var_os("XDG_DATA_HOME") {
+            dirs.push(PathBuf::from(data).join("fonts"));
+        }
+        if let Some(home) = &home {
+            dirs.push(home.join(".local/share/fonts"));
+            dirs.push(home.join(".fonts"));
+        }
+        dirs.push(PathBuf::from("/usr/local/share/fonts"));
+        dirs.push(PathBuf::from("/usr/share/fonts"));
+    }
+    dirs
+}
+
+/// 在目录中（包括子目录，最多 4 层）按文件名查找字体
+fn find_in_dirs(dirs: &[PathBuf], names: &[&str]) -> Option<PathBuf> {
+    fn walk(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if depth < 4 {
+                    walk(&path, depth + 1, found);
+                }
+            } else {
+                found.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for dir in dirs {
+        walk(dir, 0, &mut files);
+    }
+    // 按 names 的顺序选取，文件名不区分大小写
+    names.iter().find_map(|name| {
+        files
+            .iter()
+            .find(|f| f.file_name().is_some_and(|n| n.to_string_lossy().eq_ignore_ascii_case(name)))
+            .cloned()
+    })
+}
+
+/// 运行 fontconfig 的命令行工具，失败时返回 None
+fn fontconfig(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// 通过 fontconfig 查找支持中文的字体：优先取 fc-match 的首选结果，它不支持中文时从 fc-list 中挑选常规字重
+fn fontconfig_cjk() -> Option<PathBuf> {
+    let list = fontconfig("fc-list", &[":lang=zh", "file"])?;
+    let files: Vec<&str> = list
+        .lines()
+        .map(|l| l.trim().trim_end_matches(':'))
+        .filter(|l| !l.is_empty())
+        .collect();
+    let preferred = fontconfig("fc-match", &["-f", "%{file}", "sans-serif:lang=zh-cn"]);
+    if let Some(file) = preferred.filter(|p| files.contains(&p.as_str())) {
+        return Some(PathBuf::from(file));
+    }
+    let regular = |f: &str| !f.contains("Bold") && !f.contains("Light") && !f.contains("Serif");
+    files.iter().copied().find(|f| regular(f)).or(files.first().copied()).map(PathBuf::from)
+}
+
+/// 按回退顺序列出要加载的字体：用户指定的字体、系统中文字体、符号字体
+fn discover(custom_path: &str) -> Vec<(Role, PathBuf)> {
+    let mut chain = Vec::new();
+    let custom_path = custom_path.trim();
+    if !custom_path.is_empty() {
+        chain.push((Role::Custom, PathBuf::from(custom_path)));
+    }
+
+    let dirs = font_dirs();
+    let cjk = if cfg!(any(target_os = "windows", target_os = "macos")) {
+        None
+    } else {
+        fontconfig_cjk()
+    };
+    if let Some(path) = cjk.or_else(|| find_in_dirs(&dirs, CJK_FILES)) {
+        chain.push((Role::Cjk, path));
+    }
+    if let Some(path) = find_in_dirs(&dirs, SYMBOL_FILES) {
+        chain.push((Role::Symbol, path));
+    }
+    chain
+}
+
+/// 把字体加入比例字体和等宽字体的回退链，front 为真时放在最前
+fn add_font(fonts: &mut FontDefinitions, name: String, data: FontData, front: bool) {
+    fonts.font_data.insert(name.clone(), data.into());
+    for family in [FontFamily::Proportional, FontFamily::Monospace] {
+        if let Some(list) = fonts.families.get_mut(&family) {
+            if front {
+                list.insert(0, name.clone());
+            } else {
+                list.push(name.clone());
+            }
+        }
+    }
+}
+
+/// 读取字体文件并检查能否解析；egui 遇到无法解析的字体会直接 panic，所以先用同一个解析库试一遍
+fn read_font(path: &Path) -> Result<Vec<u8>, String> {
+    let bytes = std::fs::read(path).map_err(|e| tr!("无法读取：{}", e))?;
+    ab_glyph::FontRef::try_from_slice(&bytes).map_err(|_| tr!("不是有效的 TTF/OTF 字体").to_owned())?;
+    Ok(bytes)
+}
+
+/// 加载系统字体并设置回退链：用户字体在最前，其余排在 egui 内置字体之后，只补充内置字体缺少的字形。
+/// custom_path 为空时只使用自动发现的字体；它指向的字体无法加载时会被清空，避免每次启动都重试。
+/// 返回已加载字体的说明。
+pub fn install_fonts(ctx: &egui::Context, custom_path: &mut String) -> Vec<String> {
+    let mut fonts = FontDefinitions::default();
+    let mut loaded = Vec::new();
+
+    for (role, path) in discover(custom_path) {
+        match read_font(&path) {
+            Ok(bytes) => {
+                let name = format!("{}:{}", role.label(), path.display());
+                add_font(&mut fonts, name, FontData::from_owned(bytes), role == Role::Custom);
+                loaded.push(tr!("{}：{}", role.label(), path.display()));
+            }
+            Err(e) => {
+                loaded.push(tr!("{}：已跳过 {}（{}）", role.label(), path.display(), e));
+                if role == Role::Custom {
+                    custom_path.clear();
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "bundled-fonts")]
+    {
+        add_font(&mut fonts, "bundled-cjk".to_owned(), FontData::from_static(BUNDLED_CJK), false);
+        loaded.push(tr!("内置：Noto Sans SC").to_owned());
+    }
+
+    ctx.set_fonts(fonts);
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(LOADED_ID), loaded.clone()));
+    loaded
+}
+
+/// 最近一次 install_fonts 加载的字体说明
+pub fn loaded_fonts(ctx: &egui::Context) -> Vec<String> {
+    ctx.data(|d| d.get_temp(egui::Id::new(LOADED_ID))).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_font_rejects_non_font_files() {
+        let path = std::env::temp_dir().join("appkit_not_a_font.ttf");
+        std::fs::write(&path, b"{\"not\": \"a font\"}").unwrap();
+        assert!(read_font(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(read_font(&path).is_err());
+    }
+}
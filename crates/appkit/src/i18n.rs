@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// 当前界面语言，存放 Locale 的下标
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// 界面语言
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Locale {
+    ZhCn,
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    /// 语言自己的名称，不随界面语言变化
+    pub fn label(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "简体中文",
+            Locale::EnUs => "English",
+        }
+    }
+
+    /// 解析 zh_CN.UTF-8、en-US、en 等形式的语言标记，只看语言部分
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let language = tag.split(['_', '-', '.', '@']).next()?.to_ascii_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    /// 从环境检测系统语言：先看 LC_ALL、LC_MESSAGES、LANG、LANGUAGE，再问系统；无法识别时使用中文。
+    /// 结果只检测一次
+    pub fn detect() -> Locale {
+        static DETECTED: OnceLock<Locale> = OnceLock::new();
+        *DETECTED.get_or_init(|| {
+            let from_env = ["LC_ALL", "LC_MESSAGES", "LANG", "LANGUAGE"]
+                .iter()
+                .filter_map(|name| std::env::var(name).ok())
+                // LANGUAGE 可以是以冒号分隔的列表；C/POSIX 表示未设置语言
+                .flat_map(|value| value.split(':').map(str::to_owned).collect::<Vec<_>>())
+                .find(|tag| !tag.is_empty() && tag != "C" && tag != "POSIX");
+            from_env
+                .or_else(system_locale)
+                .and_then(|tag| Locale::parse(&tag))
+                .unwrap_or(Locale::ZhCn)
+        })
+    }
+
+    pub fn current() -> Locale {
+        Locale::ALL[CURRENT.load(Ordering::Relaxed) as usize]
+    }
+
+    /// 切换界面语言，下一帧起生效
+    pub fn set_current(self) {
+        CURRENT.store(self as u8, Ordering::Relaxed);
+    }
+}
+
+/// macOS 上读取全局偏好中的语言
+#[cfg(target_os = "macos")]
+fn system_locale() -> Option<String> {
+    let output = std::process::Command::new("defaults").args(["read", "-g", "AppleLocale"]).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Windows 上读取用户区域设置；不弹出控制台窗口
+#[cfg(target_os = "windows")]
+fn system_locale() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let output = std::process::Command::new("reg")
+        .args(["query", r"HKCU\Control Panel\International", "/v", "LocaleName"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    // 输出形如 "    LocaleName    REG_SZ    zh-CN"
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|l| l.contains("LocaleName"))?;
+    line.split_whitespace().last().map(str::to_owned)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn system_locale() -> Option<String> {
+    None
+}
+
+/// 消息目录：以中文原文为键、英文译文为值；没有译文的条目原样显示中文
+pub struct Catalog {
+    entries: &'static [(&'static str, &'static str)],
+    index: OnceLock<HashMap<&'static str, &'static str>>,
+}
+
+impl Catalog {
+    pub const fn new(entries: &'static [(&'static str, &'static str)]) -> Self {
+        Self {
+            entries,
+            index: OnceLock::new(),
+        }
+    }
+
+    /// 按当前语言取文本
+    pub fn get<'a>(&self, zh: &'a str) -> &'a str {
+        match Locale::current() {
+            Locale::ZhCn => zh,
+            Locale::EnUs => {
+                let index = self.index.get_or_init(|| self.entries.iter().copied().collect());
+                index.get(zh).copied().unwrap_or(zh)
+            }
+        }
+    }
+}
+
+/// 依次用参数替换模板中的 {}，参数多于占位符时忽略多余的参数
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(pos) = rest.find("{}") {
+        out.push_str(&rest[..pos]);
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        rest = &rest[pos + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// tr!("中文") 按当前语言取文本，tr!("共 {} 项", n) 取文本后依次填入参数。
+///
+/// 默认使用调用方 crate 的 `crate::i18n::CATALOG`，也可以在前面指定目录：tr!(OTHER_CATALOG; "中文")。
+/// 各应用在自己的 i18n 模块中定义 CATALOG 并 `pub(crate) use appkit::tr;`
+#[allow(clippy::crate_in_macro_def)] // 有意指向调用方 crate 的目录
+#[macro_export]
+macro_rules! tr {
+    ($zh:literal $(, $arg:expr)* $(,)?) => {
+        $crate::tr!(crate::i18n::CATALOG; $zh $(, $arg)*)
+    };
+    ($catalog:path; $zh:literal) => {
+        $catalog.get($zh)
+    };
+    ($catalog:path; $zh:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::format($catalog.get($zh), &[$(&$arg as &dyn ::std::fmt::Display),+])
+    };
+}
+pub(crate) use crate::tr;
+
+/// 共享界面的英文译文
+pub(crate) static CATALOG: Catalog = Catalog::new(&[
+    ("跟随系统", "Follow system"),
+    ("浅色", "Light"),
+    ("深色", "Dark"),
+    ("高对比度", "High contrast"),
+    ("键名", "Keys"),
+    ("字符串", "Strings"),
+    ("数字", "Numbers"),
+    ("标点", "Punctuation"),
+    ("错误", "Errors"),
+    ("新增 / 成功", "Added / success"),
+    ("行号", "Line numbers"),
+    ("树中的搜索命中", "Search hits in tree"),
+    ("树中的变化", "Changes in tree"),
+    ("主题：", "Theme:"),
+    ("语言：", "Language:"),
+    ("界面缩放：", "Zoom:"),
+    ("重置", "Reset"),
+    ("字号：", "Font size:"),
+    ("字体文件：", "Font file:"),
+    ("留空则自动查找系统字体", "Leave empty to use system fonts"),
+    ("应用", "Apply"),
+    ("重新加载字体", "Reload fonts"),
+    ("已加载的字体", "Loaded fonts"),
+    ("只使用内置字体", "Only built-in fonts"),
+    ("{}主题的配色", "{} theme colors"),
+    ("恢复默认", "Restore defaults"),
+    ("自定义", "Custom"),
+    ("中文", "CJK"),
+    ("符号", "Symbols"),
+    ("{}：{}", "{}: {}"),
+    ("{}：已跳过 {}（{}）", "{}: skipped {} ({})"),
+    ("无法读取：{}", "cannot read: {}"),
+    ("不是有效的 TTF/OTF 字体", "not a valid TTF/OTF font"),
+    ("内置：Noto Sans SC", "Bundled: Noto Sans SC"),
+]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static OTHER: Catalog = Catalog::new(&[("共 {} 项", "{} items")]);
+
+    #[test]
+    fn tr_uses_crate_or_given_catalog() {
+        Locale::EnUs.set_current();
+        assert_eq!(tr!("浅色"), "Light");
+        assert_eq!(tr!(OTHER; "共 {} 项", 3), "3 items");
+        assert_eq!(tr!(OTHER; "未翻译"), "未翻译");
+        Locale::ZhCn.set_current();
+        assert_eq!(tr!(OTHER; "共 {} 项", 3), "共 3 项");
+        assert_eq!(format("{}-{}", &[&1]), "1-");
+    }
+}
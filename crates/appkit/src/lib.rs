@@ -0,0 +1,5 @@
+//! 工作空间内各工具共用的界面基础设施
+
+pub mod fonts;
+pub mod i18n;
+pub mod theme;
@@ -0,0 +1,327 @@
+use eframe::egui::{self, Color32, Stroke};
+use serde::{Deserialize, Serialize};
+
+use crate::fonts;
+use crate::i18n::{tr, Locale};
+
+/// egui 默认的正文字号
+pub const DEFAULT_FONT_SIZE: f32 = 13.0;
+/// 当前生效的配色在 egui 临时存储中的键
+const PALETTE_ID: &str = "appkit_palette";
+
+/// 主题模式
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeMode {
+    /// 跟随系统的深色/浅色设置
+    #[default]
+    System,
+    Light,
+    Dark,
+    /// 纯黑背景、白色文字和高亮边框
+    HighContrast,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 4] = [ThemeMode::System, ThemeMode::Light, ThemeMode::Dark, ThemeMode::HighContrast];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeMode::System => tr!("跟随系统"),
+            ThemeMode::Light => tr!("浅色"),
+            ThemeMode::Dark => tr!("深色"),
+            ThemeMode::HighContrast => tr!("高对比度"),
+        }
+    }
+}
+
+/// 应用自己绘制的内容所用的颜色：语法着色、行号、错误提示和树中的高亮
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    pub key: Color32,
+    pub string: Color32,
+    pub number: Color32,
+    /// true、false 和 null
+    pub literal: Color32,
+    /// 括号、逗号和冒号
+    pub punct: Color32,
+    pub error: Color32,
+    pub success: Color32,
+    pub line_number: Color32,
+    /// 树中搜索命中节点的背景
+    pub tree_match: Color32,
+    /// 树中发生变化的节点的背景
+    pub tree_changed: Color32,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+impl Palette {
+    pub const DARK: Palette = Palette {
+        key: Color32::from_rgb(156, 220, 254),
+        string: Color32::from_rgb(206, 145, 120),
+        number: Color32::from_rgb(181, 206, 168),
+        literal: Color32::from_rgb(86, 156, 214),
+        punct: Color32::from_gray(180),
+        error: Color32::from_rgb(244, 71, 71),
+        success: Color32::from_rgb(90, 190, 100),
+        line_number: Color32::from_gray(128),
+        tree_match: Color32::from_rgba_unmultiplied_const(80, 200, 120, 60),
+        tree_changed: Color32::from_rgba_unmultiplied_const(230, 180, 40, 70),
+    };
+
+    pub const LIGHT: Palette = Palette {
+        key: Color32::from_rgb(4, 81, 165),
+        string: Color32::from_rgb(163, 21, 21),
+        number: Color32::from_rgb(9, 134, 88),
+        literal: Color32::from_rgb(0, 0, 255),
+        punct: Color32::from_gray(60),
+        error: Color32::from_rgb(200, 30, 30),
+        success: Color32::from_rgb(40, 140, 40),
+        line_number: Color32::from_gray(128),
+        tree_match: Color32::from_rgba_unmultiplied_const(80, 200, 120, 60),
+        tree_changed: Color32::from_rgba_unmultiplied_const(230, 180, 40, 70),
+    };
+
+    pub const HIGH_CONTRAST: Palette = Palette {
+        key: Color32::from_rgb(120, 220, 255),
+        string: Color32::from_rgb(255, 190, 110),
+        number: Color32::from_rgb(150, 255, 150),
+        literal: Color32::from_rgb(255, 130, 255),
+        punct: Color32::WHITE,
+        error: Color32::from_rgb(255, 90, 90),
+        success: Color32::from_rgb(0, 255, 0),
+        line_number: Color32::from_gray(200),
+        tree_match: Color32::from_rgba_unmultiplied_const(0, 255, 120, 110),
+        tree_changed: Color32::from_rgba_unmultiplied_const(255, 200, 0, 120),
+    };
+
+    /// 当前界面使用的配色；尚未应用主题时按深浅色取内置配色
+    pub fn of(ui: &egui::Ui) -> Palette {
+        let dark = ui.visuals().dark_mode;
+        let active: Option<(Palette, Palette)> = ui.ctx().data(|d| d.get_temp(egui::Id::new(PALETTE_ID)));
+        match (active, dark) {
+            (Some((_, dark_palette)), true) => dark_palette,
+            (Some((light_palette, _)), false) => light_palette,
+            (None, true) => Palette::DARK,
+            (None, false) => Palette::LIGHT,
+        }
+    }
+
+    /// 可编辑的颜色及其名称
+    fn entries_mut(&mut self) -> [(&'static str, &mut Color32); 10] {
+        [
+            (tr!("键名"), &mut self.key),
+            (tr!("字符串"), &mut self.string),
+            (tr!("数字"), &mut self.number),
+            ("true / false / null", &mut self.literal),
+            (tr!("标点"), &mut self.punct),
+            (tr!("错误"), &mut self.error),
+            (tr!("新增 / 成功"), &mut self.success),
+            (tr!("行号"), &mut self.line_number),
+            (tr!("树中的搜索命中"), &mut self.tree_match),
+            (tr!("树中的变化"), &mut self.tree_changed),
+        ]
+    }
+}
+
+/// 高对比度主题的界面样式
+fn high_contrast_visuals() -> egui::Visuals {
+    let mut visuals = egui::Visuals::dark();
+    visuals.override_text_color = Some(Color32::WHITE);
+    visuals.panel_fill = Color32::BLACK;
+    visuals.window_fill = Color32::BLACK;
+    visuals.extreme_bg_color = Color32::BLACK;
+    visuals.faint_bg_color = Color32::from_gray(24);
+    visuals.code_bg_color = Color32::from_gray(30);
+    visuals.hyperlink_color = Color32::from_rgb(0, 220, 255);
+    visuals.window_stroke = Stroke::new(1.0, Color32::WHITE);
+    visuals.selection.bg_fill = Color32::from_rgb(0, 90, 200);
+    visuals.selection.stroke = Stroke::new(1.0, Color32::WHITE);
+
+    let widgets = &mut visuals.widgets;
+    widgets.noninteractive.bg_stroke = Stroke::new(1.0, Color32::from_gray(200));
+    widgets.noninteractive.fg_stroke = Stroke::new(1.0, Color32::WHITE);
+    for (state, border) in [
+        (&mut widgets.inactive, Color32::from_gray(200)),
+        (&mut widgets.hovered, Color32::YELLOW),
+        (&mut widgets.active, Color32::YELLOW),
+        (&mut widgets.open, Color32::WHITE),
+    ] {
+        state.bg_fill = Color32::from_gray(20);
+        state.weak_bg_fill = Color32::from_gray(20);
+        state.bg_stroke = Stroke::new(1.5, border);
+        state.fg_stroke = Stroke::new(1.5, Color32::WHITE);
+    }
+    visuals
+}
+
+/// 外观设置：主题、语言、缩放、字号、字体和各主题下的自定义配色
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeSettings {
+    pub mode: ThemeMode,
+    /// 整体界面缩放
+    pub zoom: f32,
+    /// 正文字号，其余文字按比例缩放
+    pub font_size: f32,
+    pub light: Palette,
+    pub dark: Palette,
+    pub high_contrast: Palette,
+    /// 用户指定的字体文件，优先于自动发现的系统字体；为空时不使用
+    pub font_path: String,
+    /// 界面语言，None 表示跟随系统
+    pub language: Option<Locale>,
+}
+
+impl Default for ThemeSettings {
+    fn default() -> Self {
+        Self {
+            mode: ThemeMode::System,
+            zoom: 1.0,
+            font_size: DEFAULT_FONT_SIZE,
+            light: Palette::LIGHT,
+            dark: Palette::DARK,
+            high_contrast: Palette::HIGH_CONTRAST,
+            font_path: String::new(),
+            language: None,
+        }
+    }
+}
+
+impl ThemeSettings {
+    /// 把设置应用到界面；只需在启动和设置变化时调用
+    pub fn apply(&self, ctx: &egui::Context) {
+        let (preference, dark_visuals, palettes) = match self.mode {
+            ThemeMode::System => (egui::ThemePreference::System, egui::Visuals::dark(), (self.light, self.dark)),
+            ThemeMode::Light => (egui::ThemePreference::Light, egui::Visuals::dark(), (self.light, self.dark)),
+            ThemeMode::Dark => (egui::ThemePreference::Dark, egui::Visuals::dark(), (self.light, self.dark)),
+            ThemeMode::HighContrast => (
+                egui::ThemePreference::Dark,
+                high_contrast_visuals(),
+                (self.high_contrast, self.high_contrast),
+            ),
+        };
+        ctx.set_visuals_of(egui::Theme::Dark, dark_visuals);
+        ctx.set_visuals_of(egui::Theme::Light, egui::Visuals::light());
+        ctx.set_theme(preference);
+        self.language.unwrap_or_else(Locale::detect).set_current();
+
+        let scale = self.font_size / DEFAULT_FONT_SIZE;
+        let defaults = egui::style::default_text_styles();
+        ctx.all_styles_mut(|style| {
+            for (text_style, font) in style.text_styles.iter_mut() {
+                if let Some(default) = defaults.get(text_style) {
+                    font.size = default.size * scale;
+                }
+            }
+        });
+        ctx.set_zoom_factor(self.zoom);
+        ctx.data_mut(|d| d.insert_temp(egui::Id::new(PALETTE_ID), palettes));
+    }
+
+    /// 记下用快捷键（Ctrl +/-）调整后的缩放，以便保存
+    pub fn sync_zoom(&mut self, ctx: &egui::Context) {
+        self.zoom = ctx.zoom_factor();
+    }
+
+    /// 当前生效主题对应的配色
+    fn active_palette_mut(&mut self, ctx: &egui::Context) -> (&'static str, &mut Palette, Palette) {
+        match (self.mode, ctx.theme()) {
+            (ThemeMode::HighContrast, _) => (tr!("高对比度"), &mut self.high_contrast, Palette::HIGH_CONTRAST),
+            (_, egui::Theme::Dark) => (tr!("深色"), &mut self.dark, Palette::DARK),
+            (_, egui::Theme::Light) => (tr!("浅色"), &mut self.light, Palette::LIGHT),
+        }
+    }
+
+    /// 绘制外观设置，修改后立即应用；返回是否有修改
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let before = self.clone();
+
+        egui::Grid::new("theme_settings").num_columns(2).show(ui, |ui| {
+            ui.label(tr!("主题："));
+            ui.horizontal_wrapped(|ui| {
+                for mode in ThemeMode::ALL {
+                    ui.selectable_value(&mut self.mode, mode, mode.label());
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr!("语言："));
+            ui.horizontal_wrapped(|ui| {
+                ui.selectable_value(&mut self.language, None, tr!("跟随系统"));
+                for locale in Locale::ALL {
+                    ui.selectable_value(&mut self.language, Some(locale), locale.label());
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr!("界面缩放："));
+            ui.horizontal(|ui| {
+                ui.add(egui::Slider::new(&mut self.zoom, 0.5..=3.0).step_by(0.1).suffix("×"));
+                if ui.small_button(tr!("重置")).clicked() {
+                    self.zoom = 1.0;
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr!("字号："));
+            ui.horizontal(|ui| {
+                ui.add(egui::Slider::new(&mut self.font_size, 8.0..=28.0).step_by(0.5));
+                if ui.small_button(tr!("重置")).clicked() {
+                    self.font_size = DEFAULT_FONT_SIZE;
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr!("字体文件："));
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.font_path)
+                        .hint_text(tr!("留空则自动查找系统字体"))
+                        .desired_width(260.0),
+                );
+                if ui.button(tr!("应用")).on_hover_text(tr!("重新加载字体")).clicked() {
+                    fonts::install_fonts(ui.ctx(), &mut self.font_path);
+                }
+            });
+            ui.end_row();
+        });
+        ui.collapsing(tr!("已加载的字体"), |ui| {
+            let loaded = fonts::loaded_fonts(ui.ctx());
+            if loaded.is_empty() {
+                ui.weak(tr!("只使用内置字体"));
+            }
+            for line in loaded {
+                ui.weak(line);
+            }
+        });
+
+        ui.separator();
+        let ctx = ui.ctx().clone();
+        let (name, palette, defaults) = self.active_palette_mut(&ctx);
+        ui.horizontal(|ui| {
+            ui.strong(tr!("{}主题的配色", name));
+            if ui.small_button(tr!("恢复默认")).clicked() {
+                *palette = defaults;
+            }
+        });
+        egui::Grid::new("theme_palette").num_columns(2).show(ui, |ui| {
+            for (label, color) in palette.entries_mut() {
+                ui.label(label);
+                ui.color_edit_button_srgba(color);
+                ui.end_row();
+            }
+        });
+
+        let changed = *self != before;
+        if changed {
+            self.apply(ui.ctx());
+        }
+        changed
+    }
+}